@@ -0,0 +1,177 @@
+use crate::operation::{self, NameList, Operation};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Conflict {
+  // The department created by the first operation is removed or dissolved by the second one.
+  CreatedThenRemoved(usize, usize, String),
+  // The person added to the department by the first operation is removed from it right after.
+  AddedThenRemoved(usize, usize, String, String),
+  Duplicate(usize, usize),
+}
+
+pub use self::Conflict::*;
+
+impl Conflict {
+  // The index of the operation the conflict is reported on.
+  pub fn operation_index(&self) -> usize {
+    match *self {
+      CreatedThenRemoved(_, i, _) | AddedThenRemoved(_, i, ..) | Duplicate(_, i) => i,
+    }
+  }
+}
+
+impl fmt::Display for Conflict {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      CreatedThenRemoved(i, j, department) => write!(
+        f,
+        "department \"{}\" is created by operation {} and removed by operation {}",
+        department,
+        i + 1,
+        j + 1
+      ),
+      AddedThenRemoved(i, j, name, department) => write!(
+        f,
+        "\"{}\" is added to \"{}\" by operation {} and removed by operation {}",
+        name,
+        department,
+        i + 1,
+        j + 1
+      ),
+      Duplicate(i, j) => write!(f, "operation {} repeats operation {}", j + 1, i + 1),
+    }
+  }
+}
+
+// Departments removed by the operation: "Remove HR." and "Dissolve HR." both remove HR.
+fn removed_departments(op: &Operation) -> &[String] {
+  match op.kind() {
+    operation::Remove if op.removes_departments() => op.get_departments().unwrap_or_default(),
+    operation::Dissolve => op.get_departments().unwrap_or_default(),
+    _ => &[],
+  }
+}
+
+fn created_then_removed(ops: &[Operation]) -> impl Iterator<Item = Conflict> + '_ {
+  ops.iter().enumerate().flat_map(move |(j, op)| {
+    removed_departments(op)
+      .iter()
+      .filter_map(move |department| {
+        let i = ops[..j].iter().rposition(|op| {
+          op.kind() == operation::Create
+            && op
+              .get_departments()
+              .unwrap_or_default()
+              .contains(department)
+        })?;
+        Some(CreatedThenRemoved(i, j, department.clone()))
+      })
+  })
+}
+
+fn added_then_removed(ops: &[Operation]) -> impl Iterator<Item = Conflict> + '_ {
+  ops.windows(2).enumerate().flat_map(|(i, pair)| {
+    let (add, remove) = (&pair[0], &pair[1]);
+    let added: Vec<(String, String)> = match add.kind() {
+      operation::Add => {
+        let departments = add.get_departments().unwrap_or_default();
+        let names = add
+          .get_names()
+          .and_then(NameList::listed)
+          .unwrap_or_default();
+        names
+          .iter()
+          .flat_map(|n| departments.iter().map(move |d| (n.clone(), d.clone())))
+          .collect()
+      }
+      _ => Vec::new(),
+    };
+    // Removing everyone from a department removes whoever was just added to it, unless they are
+    // left out of the removal.
+    let removes = move |(name, department): &(String, String)| {
+      remove.kind() == operation::Remove
+        && remove
+          .get_departments()
+          .unwrap_or_default()
+          .contains(department)
+        && match remove.get_names() {
+          Some(NameList::All) => !remove.get_except().unwrap_or_default().contains(name),
+          Some(NameList::Listed(names)) => names.contains(name),
+          None => false,
+        }
+    };
+    added
+      .into_iter()
+      .filter(removes)
+      .map(move |(name, department)| AddedThenRemoved(i, i + 1, name, department))
+  })
+}
+
+fn duplicates(ops: &[Operation]) -> impl Iterator<Item = Conflict> + '_ {
+  ops.iter().enumerate().filter_map(move |(j, op)| {
+    let i = ops[..j].iter().rposition(|other| other == op)?;
+    Some(Duplicate(i, j))
+  })
+}
+
+// Reports sequences of operations that contradict each other or are redundant. Conflicts are
+// ordered by the operation they are reported on, which is always the later one.
+pub fn analyze(ops: &[Operation]) -> Vec<Conflict> {
+  let mut conflicts: Vec<Conflict> = created_then_removed(ops)
+    .chain(added_then_removed(ops))
+    .chain(duplicates(ops))
+    .collect();
+  conflicts.sort_by_key(Conflict::operation_index);
+  conflicts
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[test]
+  fn test_analyze() {
+    let ops = lex_parse(
+      "Create HR. Add Mihai and Ana to HR. Remove Ana from HR. Show HR. Show HR. Dissolve HR.",
+    )
+    .unwrap();
+    let expect = vec![
+      AddedThenRemoved(1, 2, "Ana".into(), "HR".into()),
+      Duplicate(3, 4),
+      CreatedThenRemoved(0, 5, "HR".into()),
+    ];
+    assert_eq!(analyze(&ops), expect);
+  }
+
+  #[test]
+  fn test_analyze_everyone() {
+    let ops = lex_parse("Add Mihai to HR. Remove everyone from HR.").unwrap();
+    assert_eq!(
+      analyze(&ops),
+      vec![AddedThenRemoved(0, 1, "Mihai".into(), "HR".into())]
+    );
+    let ops = lex_parse("Add Mihai and Ana to HR. Remove everyone except Ana from HR.").unwrap();
+    assert_eq!(
+      analyze(&ops),
+      vec![AddedThenRemoved(0, 1, "Mihai".into(), "HR".into())]
+    );
+  }
+
+  #[test]
+  fn test_analyze_clean() {
+    let ops = lex_parse("Create HR. Add Mihai to HR. Show HR. Remove Mihai from PR.").unwrap();
+    assert!(analyze(&ops).is_empty());
+  }
+
+  #[test]
+  fn format() {
+    let ops = lex_parse("Create HR. Remove HR.").unwrap();
+    let got: Vec<String> = analyze(&ops).iter().map(Conflict::to_string).collect();
+    assert_eq!(
+      got,
+      vec![r#"department "HR" is created by operation 1 and removed by operation 2"#]
+    );
+  }
+}
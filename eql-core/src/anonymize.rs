@@ -0,0 +1,200 @@
+use crate::operation::{Alias, List, NameList, Operation};
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pseudonyms {
+  by_name: HashMap<String, String>,
+  by_pseudonym: HashMap<String, String>,
+  order: Vec<String>,
+}
+
+// Pseudonyms must lex as words, so they are numbered with letters: A, B, ..., Z, AA, AB, ...
+fn make_pseudonym(mut index: usize) -> String {
+  let mut letters = Vec::new();
+  loop {
+    letters.push((b'A' + (index % 26) as u8) as char);
+    if index < 26 {
+      break;
+    }
+    index = index / 26 - 1;
+  }
+  format!("Person {}", letters.into_iter().rev().collect::<String>())
+}
+
+// Aliases are names too, so they are mapped along with the names they belong to.
+fn map_names(op: &Operation, mut f: impl FnMut(&String) -> String) -> Operation {
+  let op = match op.get_except() {
+    Some(except) => {
+      let except = except.iter().map(&mut f).collect::<List>();
+      op.clone().set_except(except).unwrap()
+    }
+    None => op.clone(),
+  };
+  match op.get_names() {
+    Some(names) => {
+      let names = match names {
+        NameList::Listed(names) => NameList::Listed(names.iter().map(&mut f).collect()),
+        NameList::All => NameList::All,
+      };
+      let aliases = op
+        .aliases()
+        .iter()
+        .map(|a| Alias {
+          name: f(&a.name),
+          alias: f(&a.alias),
+        })
+        .collect();
+      let op = op
+        .clone()
+        .set_names(names)
+        .and_then(|op| op.set_aliases(aliases))
+        .unwrap();
+      match op.get_new_name().map(String::from) {
+        Some(new_name) => op.set_new_name(f(&new_name)).unwrap(),
+        None => op,
+      }
+    }
+    None => op,
+  }
+}
+
+impl Pseudonyms {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn insert(&mut self, name: String, pseudonym: String) {
+    self.by_pseudonym.insert(pseudonym.clone(), name.clone());
+    self.by_name.insert(name, pseudonym.clone());
+    self.order.push(pseudonym);
+  }
+
+  pub fn pseudonym(&mut self, name: &str) -> String {
+    if let Some(pseudonym) = self.by_name.get(name) {
+      return pseudonym.clone();
+    }
+    let pseudonym = make_pseudonym(self.order.len());
+    self.insert(name.into(), pseudonym.clone());
+    pseudonym
+  }
+
+  pub fn name(&self, pseudonym: &str) -> Option<&str> {
+    self.by_pseudonym.get(pseudonym).map(String::as_str)
+  }
+
+  pub fn anonymize(&mut self, ops: &[Operation]) -> Vec<Operation> {
+    ops
+      .iter()
+      .map(|op| map_names(op, |name| self.pseudonym(name)))
+      .collect()
+  }
+
+  // Names that have no pseudonym in the mapping are left untouched.
+  pub fn restore(&self, ops: &[Operation]) -> Vec<Operation> {
+    ops
+      .iter()
+      .map(|op| map_names(op, |name| self.name(name).unwrap_or(name).into()))
+      .collect()
+  }
+}
+
+impl fmt::Display for Pseudonyms {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self
+      .order
+      .iter()
+      .try_for_each(|pseudonym| writeln!(f, "{} = {}", pseudonym, self.by_pseudonym[pseudonym]))
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MappingError {
+  line_number: usize,
+}
+
+impl fmt::Display for MappingError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "Error on line {}: expected a mapping of the form \"pseudonym = name\"",
+      self.line_number
+    )
+  }
+}
+
+impl error::Error for MappingError {}
+
+impl FromStr for Pseudonyms {
+  type Err = MappingError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut res = Self::new();
+    for (i, line) in s.lines().enumerate() {
+      if line.trim().is_empty() {
+        continue;
+      }
+      let (pseudonym, name) = line
+        .split_once(" = ")
+        .ok_or(MappingError { line_number: i + 1 })?;
+      res.insert(name.trim().into(), pseudonym.trim().into());
+    }
+    Ok(res)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[test]
+  fn test_make_pseudonym() {
+    let got: Vec<String> = [0, 1, 25, 26, 27, 701, 702]
+      .iter()
+      .map(|i| make_pseudonym(*i))
+      .collect();
+    let expect = vec![
+      "Person A",
+      "Person B",
+      "Person Z",
+      "Person AA",
+      "Person AB",
+      "Person ZZ",
+      "Person AAA",
+    ];
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn anonymize_and_restore() {
+    let ops =
+      lex_parse("Add Mihai and Ana, also known as Anna, to HR. Remove Mihai from HR. Show HR.")
+        .unwrap();
+    let mut pseudonyms = Pseudonyms::new();
+    let anonymized = pseudonyms.anonymize(&ops);
+    let expect = lex_parse(
+      "Add Person A and Person B, also known as Person C, to HR. Remove Person A from HR. Show HR.",
+    )
+    .unwrap();
+    assert_eq!(anonymized, expect);
+
+    let mapping = pseudonyms.to_string();
+    assert_eq!(
+      mapping,
+      "Person A = Mihai\nPerson B = Ana\nPerson C = Anna\n"
+    );
+    let restored = mapping.parse::<Pseudonyms>().unwrap().restore(&anonymized);
+    assert_eq!(restored, ops);
+  }
+
+  #[test]
+  fn invalid_mapping() {
+    let err = "Person A = Mihai\nPerson B"
+      .parse::<Pseudonyms>()
+      .unwrap_err();
+    assert_eq!(err, MappingError { line_number: 2 });
+  }
+}
@@ -0,0 +1,78 @@
+// How names are compared when results are sorted. Comparing code points, the default, puts names
+// that start with an accented letter or are written in another script after all of basic Latin.
+// With the "collation" feature, Locale compares them the way the language of a locale sorts them.
+
+use core::cmp;
+
+pub trait Collate {
+  fn compare(&self, a: &str, b: &str) -> cmp::Ordering;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CodePoints;
+
+impl Collate for CodePoints {
+  fn compare(&self, a: &str, b: &str) -> cmp::Ordering {
+    a.cmp(b)
+  }
+}
+
+impl<F: Fn(&str, &str) -> cmp::Ordering> Collate for F {
+  fn compare(&self, a: &str, b: &str) -> cmp::Ordering {
+    self(a, b)
+  }
+}
+
+#[cfg(feature = "collation")]
+pub use self::icu::Locale;
+
+#[cfg(feature = "collation")]
+mod icu {
+  use super::Collate;
+  use core::cmp;
+  use icu_collator::{Collator, CollatorError, CollatorOptions};
+  use icu_provider::DataLocale;
+
+  pub struct Locale(Collator);
+
+  impl Locale {
+    // Takes a BCP 47 tag such as "ro" or "sv-FI". Locales without a collation of their own use
+    // the root one, which already sorts accented letters next to the ones they are based on.
+    pub fn new(locale: &str) -> Result<Self, CollatorError> {
+      let locale = locale.parse::<DataLocale>()?;
+      Collator::try_new(&locale, CollatorOptions::new()).map(Locale)
+    }
+  }
+
+  impl Collate for Locale {
+    fn compare(&self, a: &str, b: &str) -> cmp::Ordering {
+      self.0.compare(a, b)
+    }
+  }
+}
+
+#[cfg(all(test, feature = "collation"))]
+mod tests {
+  use super::*;
+  use crate::operation::Ordering;
+
+  #[test]
+  fn sort_names() {
+    let mut names = ["Zoe", "Ștefan", "Ana", "Sorin", "Åsa"];
+    Ordering::Name { descending: false }.sort(&mut names, &CodePoints);
+    assert_eq!(names, ["Ana", "Sorin", "Zoe", "Åsa", "Ștefan"]);
+
+    let ro = Locale::new("ro").unwrap();
+    Ordering::Name { descending: false }.sort(&mut names, &ro);
+    assert_eq!(names, ["Ana", "Åsa", "Sorin", "Ștefan", "Zoe"]);
+    Ordering::Name { descending: true }.sort(&mut names, &ro);
+    assert_eq!(names, ["Zoe", "Ștefan", "Sorin", "Åsa", "Ana"]);
+
+    // Swedish sorts Å after Z.
+    let sv = Locale::new("sv").unwrap();
+    Ordering::Name { descending: false }.sort(&mut names, &sv);
+    assert_eq!(names, ["Ana", "Sorin", "Ștefan", "Zoe", "Åsa"]);
+
+    assert!(Locale::new("not a locale").is_err());
+  }
+}
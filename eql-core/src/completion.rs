@@ -0,0 +1,244 @@
+use crate::lexer;
+use crate::operation::{self, NameList, Operation};
+use crate::parser::constants::*;
+
+// Supplies the names and departments completions are drawn from, e.g. from the database the
+// operations run against.
+pub trait CompletionProvider {
+  fn departments(&self) -> Vec<String>;
+  fn names(&self) -> Vec<String>;
+}
+
+// Tracks the departments and their members as the operations of a session are recorded, for when
+// there is no database to ask.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History {
+  departments: Vec<String>,
+  members: Vec<(String, String)>,
+}
+
+impl History {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn members<'a>(&'a self, department: &'a str) -> impl Iterator<Item = &'a str> {
+    self
+      .members
+      .iter()
+      .filter(move |(d, _)| d == department)
+      .map(|(_, name)| name.as_str())
+  }
+
+  fn remove_department(&mut self, department: &str, reassign_to: Option<&str>) {
+    self.departments.retain(|d| d != department);
+    for (d, _) in self.members.iter_mut().filter(|(d, _)| d == department) {
+      d.clear();
+      d.extend(reassign_to);
+    }
+    self.members.retain(|(d, _)| !d.is_empty());
+  }
+
+  pub fn record(&mut self, op: &Operation) {
+    let departments = op.get_departments().unwrap_or_default();
+    let except = op.get_except().unwrap_or_default();
+    let names: Vec<String> = op
+      .get_names()
+      .and_then(NameList::listed)
+      .unwrap_or_default()
+      .iter()
+      .filter(|n| !except.contains(n))
+      .cloned()
+      .collect();
+    let everyone = op.get_names().is_some_and(NameList::is_all);
+    let affects = |n: &String| names.contains(n) || (everyone && !except.contains(n));
+    match op.kind() {
+      operation::Create => {
+        for department in departments {
+          if !self.departments.contains(department) {
+            self.departments.push(department.clone());
+          }
+        }
+      }
+      operation::Add => {
+        for department in departments {
+          for name in &names {
+            let member = (department.clone(), name.clone());
+            if !self.members.contains(&member) {
+              self.members.push(member);
+            }
+          }
+        }
+      }
+      operation::Remove if op.removes_departments() => {
+        for department in departments {
+          self.remove_department(department, None);
+        }
+      }
+      operation::Remove => self
+        .members
+        .retain(|(d, n)| !(departments.contains(d) && affects(n))),
+      operation::Dissolve => {
+        for department in departments {
+          self.remove_department(department, op.get_reassign_to());
+        }
+      }
+      operation::RenameEmployee => {
+        for (_, name) in self.members.iter_mut().filter(|(_, n)| names.contains(n)) {
+          *name = op.new_name().into();
+        }
+      }
+      operation::Move => {
+        let to = op.get_reassign_to().unwrap_or_default();
+        for (department, _) in self
+          .members
+          .iter_mut()
+          .filter(|(d, n)| departments.contains(d) && affects(n))
+        {
+          *department = to.into();
+        }
+      }
+      operation::RenameDepartment => {
+        let renamed = self
+          .departments
+          .iter_mut()
+          .chain(self.members.iter_mut().map(|(d, _)| d));
+        for department in renamed.filter(|d| departments.contains(d)) {
+          *department = op.new_name().into();
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+impl CompletionProvider for History {
+  fn departments(&self) -> Vec<String> {
+    let members = self.members.iter().map(|(d, _)| d);
+    let mut departments: Vec<String> = self.departments.iter().chain(members).cloned().collect();
+    departments.sort();
+    departments.dedup();
+    departments
+  }
+
+  fn names(&self) -> Vec<String> {
+    let mut names: Vec<String> = self.members.iter().map(|(_, n)| n.clone()).collect();
+    names.sort();
+    names.dedup();
+    names
+  }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expected {
+  Keyword,
+  Names,
+  Departments,
+  Both,
+}
+
+// What the word being typed is, judging by the words of the statement before it.
+fn expected(words: &[&str]) -> Expected {
+  words
+    .iter()
+    .rev()
+    .find_map(|w| match lexer::word(w) {
+      KEYWORD_ADD | KEYWORD_RENAME | KEYWORD_WHERE | KEYWORD_MOVE | KEYWORD_FIND | LINKER_IS
+      | LINKER_ARE => Some(Expected::Names),
+      KEYWORD_REMOVE => Some(Expected::Both),
+      KEYWORD_CREATE | KEYWORD_SHOW | KEYWORD_DISSOLVE | CONDITION_IF | LINKER_TO | LINKER_FROM => {
+        Some(Expected::Departments)
+      }
+      _ => None,
+    })
+    .unwrap_or(if words.is_empty() {
+      Expected::Keyword
+    } else {
+      Expected::Both
+    })
+}
+
+// Returns the completions of the word at the end of the input. At the start of a statement these
+// are the keywords, and inside one the names or departments the provider knows of, depending on
+// what the statement expects there.
+pub fn complete(input: &str, provider: &dyn CompletionProvider) -> Vec<String> {
+  let statement = input
+    .rfind(['.', '!', '?'])
+    .map_or(input, |i| &input[i + 1..]);
+  let mut words: Vec<&str> = statement
+    .split(|c: char| c.is_whitespace() || c == ',')
+    .filter(|w| !w.is_empty())
+    .collect();
+  let prefix = match statement.ends_with(|c: char| c.is_whitespace() || c == ',') {
+    true => "",
+    false => words.pop().unwrap_or_default(),
+  };
+
+  let mut candidates: Vec<String> = match expected(&words) {
+    Expected::Keyword => KEYWORDS
+      .iter()
+      .chain(&[CONDITION_IF])
+      .map(|k| k.get().to_string())
+      .collect(),
+    Expected::Names => provider.names(),
+    Expected::Departments => provider.departments(),
+    Expected::Both => [provider.names(), provider.departments()].concat(),
+  };
+  candidates.retain(|c| c.starts_with(prefix) && c != prefix);
+  candidates.sort();
+  candidates.dedup();
+  candidates
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[test]
+  fn record_history() {
+    let mut history = History::new();
+    let ops = lex_parse(
+      "Create HR, PR. Add Mihai, Ana to HR. Add Mircea to PR. Remove Ana from HR. Dissolve PR and move everyone to HR. Rename employee Mihai to Mihnea.",
+    )
+    .unwrap();
+    ops.iter().for_each(|op| history.record(op));
+    assert_eq!(
+      history.members("HR").collect::<Vec<_>>(),
+      vec!["Mihnea", "Mircea"]
+    );
+    assert_eq!(history.departments(), vec!["HR"]);
+
+    let ops = lex_parse("Rename HR to People.").unwrap();
+    history.record(&ops[0]);
+    assert_eq!(history.departments(), vec!["People"]);
+    assert_eq!(history.members("People").count(), 2);
+
+    let ops = lex_parse("Create IT. Move Mihnea from People to IT.").unwrap();
+    ops.iter().for_each(|op| history.record(op));
+    assert_eq!(history.members("IT").collect::<Vec<_>>(), vec!["Mihnea"]);
+    assert_eq!(history.members("People").count(), 1);
+
+    let ops = lex_parse("Move everyone except Mircea from People to IT. Remove everyone from IT.");
+    ops.unwrap().iter().for_each(|op| history.record(op));
+    assert_eq!(
+      history.members("People").collect::<Vec<_>>(),
+      vec!["Mircea"]
+    );
+    assert_eq!(history.members("IT").count(), 0);
+    assert_eq!(history.departments(), vec!["IT", "People"]);
+  }
+
+  #[test]
+  fn complete_words() {
+    let mut history = History::new();
+    let ops = lex_parse("Create HR, Marketing. Add Mihai to HR.").unwrap();
+    ops.iter().for_each(|op| history.record(op));
+    assert_eq!(complete("Remove Mi", &history), vec!["Mihai"]);
+    assert_eq!(complete("Remove M", &history), vec!["Marketing", "Mihai"]);
+    assert_eq!(complete("Add Mihai to ", &history), vec!["HR", "Marketing"]);
+    assert_eq!(complete("Show HR. Cr", &history), vec!["Create"]);
+    assert_eq!(complete("Add Ana, Mi", &history), vec!["Mihai"]);
+    assert!(complete("Add Mihai", &history).is_empty());
+  }
+}
@@ -0,0 +1,111 @@
+use crate::operation::Operation;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Change<'a> {
+  Added(usize, &'a Operation),
+  Removed(usize, &'a Operation),
+  Modified(usize, &'a Operation, usize, &'a Operation),
+}
+
+pub use self::Change::*;
+
+impl fmt::Display for Change<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Added(i, op) => write!(f, "+ {}: {}", i + 1, op),
+      Removed(i, op) => write!(f, "- {}: {}", i + 1, op),
+      Modified(i, old, j, new) => write!(f, "~ {} -> {}: {}\n    => {}", i + 1, j + 1, old, new),
+    }
+  }
+}
+
+fn longest_common_subsequence(old: &[Operation], new: &[Operation]) -> Vec<Vec<usize>> {
+  let mut lengths = vec![vec![0; new.len() + 1]; old.len() + 1];
+  for i in (0..old.len()).rev() {
+    for j in (0..new.len()).rev() {
+      lengths[i][j] = if old[i] == new[j] {
+        lengths[i + 1][j + 1] + 1
+      } else {
+        lengths[i + 1][j].max(lengths[i][j + 1])
+      };
+    }
+  }
+  lengths
+}
+
+// Removals directly followed by additions of the same operation kind are reported as modifications.
+fn pair_modifications(changes: Vec<Change>) -> Vec<Change> {
+  let mut res: Vec<Change> = Vec::with_capacity(changes.len());
+  for change in changes {
+    let modified = match (res.last(), &change) {
+      (Some(Removed(i, old)), Added(j, new)) if old.kind() == new.kind() => {
+        Some(Modified(*i, old, *j, new))
+      }
+      _ => None,
+    };
+    match modified {
+      Some(m) => *res.last_mut().unwrap() = m,
+      None => res.push(change),
+    }
+  }
+  res
+}
+
+pub fn diff<'a>(old: &'a [Operation], new: &'a [Operation]) -> Vec<Change<'a>> {
+  let lengths = longest_common_subsequence(old, new);
+  let mut changes = Vec::new();
+
+  let (mut i, mut j) = (0, 0);
+  while i < old.len() && j < new.len() {
+    if old[i] == new[j] {
+      i += 1;
+      j += 1;
+    } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+      changes.push(Removed(i, &old[i]));
+      i += 1;
+    } else {
+      changes.push(Added(j, &new[j]));
+      j += 1;
+    }
+  }
+  changes.extend((i..old.len()).map(|i| Removed(i, &old[i])));
+  changes.extend((j..new.len()).map(|j| Added(j, &new[j])));
+
+  pair_modifications(changes)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[test]
+  fn test_diff() {
+    let old = lex_parse("Create HR. Add Mihai to HR. Show HR. Remove Ioan from HR.").unwrap();
+    let new = lex_parse("Create HR. Add Mihai and Ana to HR. Show HR. Create Sales.").unwrap();
+    let expect = vec![
+      Modified(1, &old[1], 1, &new[1]),
+      Removed(3, &old[3]),
+      Added(3, &new[3]),
+    ];
+    assert_eq!(diff(&old, &new), expect);
+  }
+
+  #[test]
+  fn test_diff_equal() {
+    let ops = lex_parse("Create HR. Show HR.").unwrap();
+    assert!(diff(&ops, &ops).is_empty());
+  }
+
+  #[test]
+  fn format() {
+    let old = lex_parse("Add Mihai to HR.").unwrap();
+    let new = lex_parse("Add Ana to HR!").unwrap();
+    let got: Vec<String> = diff(&old, &new).iter().map(Change::to_string).collect();
+    assert_eq!(
+      got,
+      vec!["~ 1 -> 1: Add Mihai to HR\n    => Add Ana to HR (overwrite if existing)"]
+    );
+  }
+}
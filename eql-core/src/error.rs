@@ -0,0 +1,150 @@
+use crate::{lexer, parser};
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+// Every error has a stable code, so it can be looked up with explain and `eql --explain`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorKind {
+  UnknownToken,
+  MissingOperation,
+  Unterminated,
+  InvalidTerminator,
+  TrailingTokens,
+  EmptyList,
+  UnterminatedList,
+  ReservedWord,
+  MultipleElements,
+  MissingVerb,
+  MissingNoun,
+  InvalidCondition,
+  UndefinedTeam,
+  InvalidDefinition,
+  UnsupportedSyntax,
+  InputTooLong,
+  InvalidUtf8,
+  InvalidEscape,
+  TooManyTokens,
+  LineTooLong,
+  InvalidLimit,
+  DuplicateClause,
+  MisplacedAlias,
+  MisplacedClause,
+  InvalidEffective,
+}
+
+impl ErrorKind {
+  pub const ALL: [ErrorKind; 25] = [
+    ErrorKind::UnknownToken,
+    ErrorKind::MissingOperation,
+    ErrorKind::Unterminated,
+    ErrorKind::InvalidTerminator,
+    ErrorKind::TrailingTokens,
+    ErrorKind::EmptyList,
+    ErrorKind::UnterminatedList,
+    ErrorKind::ReservedWord,
+    ErrorKind::MultipleElements,
+    ErrorKind::MissingVerb,
+    ErrorKind::MissingNoun,
+    ErrorKind::InvalidCondition,
+    ErrorKind::UndefinedTeam,
+    ErrorKind::InvalidDefinition,
+    ErrorKind::UnsupportedSyntax,
+    ErrorKind::InputTooLong,
+    ErrorKind::InvalidUtf8,
+    ErrorKind::InvalidEscape,
+    ErrorKind::TooManyTokens,
+    ErrorKind::LineTooLong,
+    ErrorKind::InvalidLimit,
+    ErrorKind::DuplicateClause,
+    ErrorKind::MisplacedAlias,
+    ErrorKind::MisplacedClause,
+    ErrorKind::InvalidEffective,
+  ];
+
+  // Codes are assigned in the order of ALL and never reused.
+  pub fn code(self) -> u16 {
+    self as u16 + 1
+  }
+}
+
+impl fmt::Display for ErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "E{:04}", self.code())
+  }
+}
+
+impl FromStr for ErrorKind {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    ErrorKind::ALL
+      .iter()
+      .find(|k| k.to_string().eq_ignore_ascii_case(s))
+      .copied()
+      .ok_or_else(|| format!("{} is not an error code", s))
+  }
+}
+
+// How serious a diagnostic is. Lexing and parsing errors are always errors, while lints can be
+// either.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+  Warning,
+  Error,
+}
+
+impl fmt::Display for Severity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match self {
+      Severity::Warning => "Warning",
+      Severity::Error => "Error",
+    })
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error<'a> {
+  Lex(lexer::Error<'a>),
+  Parse(parser::Error<'a>),
+  // The cancellation flag was raised before the input was fully parsed.
+  Cancelled,
+}
+
+impl Error<'_> {
+  pub fn kind(&self) -> Option<ErrorKind> {
+    match self {
+      Error::Lex(e) => Some(e.kind()),
+      Error::Parse(e) => Some(e.kind()),
+      Error::Cancelled => None,
+    }
+  }
+}
+
+impl fmt::Display for Error<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::Lex(e) => e.fmt(f),
+      Error::Parse(e) => e.fmt(f),
+      Error::Cancelled => f.write_str("Parsing was cancelled"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error<'_> {}
+
+impl<'a> From<lexer::Error<'a>> for Error<'a> {
+  fn from(e: lexer::Error<'a>) -> Self {
+    Error::Lex(e)
+  }
+}
+
+impl<'a> From<parser::Error<'a>> for Error<'a> {
+  fn from(e: parser::Error<'a>) -> Self {
+    Error::Parse(e)
+  }
+}
@@ -0,0 +1,235 @@
+use crate::error::ErrorKind;
+
+// Longer descriptions of every error, with an example of a wrong statement and its correction.
+pub fn explain(kind: ErrorKind) -> &'static str {
+  match kind {
+    ErrorKind::UnknownToken => {
+      "The script contains a character that isn't part of the language. Names and departments can \
+only contain letters and numbers unless they are quoted, and the only punctuation is the comma and the \
+terminators.
+
+Wrong:   Add Mihai & Ana to HR.
+Correct: Add Mihai and Ana to HR."
+    }
+    ErrorKind::MissingOperation => {
+      "Every statement must start with an operation keyword, such as Add, Create, Remove or Show, \
+or with a condition. Keywords are capitalized at the start of a statement.
+
+Wrong:   Mihai to HR.
+Correct: Add Mihai to HR."
+    }
+    ErrorKind::Unterminated => {
+      "Every statement must end with a terminator: a full stop, an exclamation mark or a question \
+mark, depending on what the operation supports.
+
+Wrong:   Create HR
+Correct: Create HR."
+    }
+    ErrorKind::InvalidTerminator => {
+      "The statement ends with a terminator the operation doesn't support. Only Add and Create can \
+end with an exclamation mark, which overwrites existing entries, and definitions can only end with \
+a full stop.
+
+Wrong:   Show HR!
+Correct: Show HR."
+    }
+    ErrorKind::TrailingTokens => {
+      "Something follows the end of the statement. Every statement \
+must be finished before the next one starts.
+
+Wrong:   Create HR. PR.
+Correct: Create HR. Create PR."
+    }
+    ErrorKind::EmptyList => {
+      "A list of names or departments is empty. Every list must have at least one element.
+
+Wrong:   Add to HR.
+Correct: Add Mihai to HR."
+    }
+    ErrorKind::UnterminatedList => {
+      "A list of names or departments isn't followed by what the operation expects, usually a \
+linking word such as \"to\" or \"from\", or a terminator.
+
+Wrong:   Add Mihai
+Correct: Add Mihai to HR."
+    }
+    ErrorKind::ReservedWord => {
+      "A reserved word, such as an operation keyword or a linking word, is used as a name or a \
+department. Reserved words can only be used in lists if they are quoted.
+
+Wrong:   Add Mihai to Show
+Correct: Add Mihai to \"Show\"."
+    }
+    ErrorKind::MultipleElements => {
+      "The operation only accepts a single name or department at this position, but a list was \
+given.
+
+Wrong:   Rename employee Mihai and Ana to Mihai Popescu.
+Correct: Rename employee Mihai to Mihai Popescu."
+    }
+    ErrorKind::MissingVerb => {
+      "Questions about where someone is must ask with \"is\" or \"are\".
+
+Wrong:   Where Mihai?
+Correct: Where is Mihai?"
+    }
+    ErrorKind::MissingNoun => {
+      "Renaming must say what is renamed: a department by its name, or a person after \"employee\" \
+or \"person\", so the two can't be confused.
+
+Wrong:   Rename to Mihai Popescu.
+Correct: Rename employee Mihai to Mihai Popescu."
+    }
+    ErrorKind::InvalidCondition => {
+      "A condition must list departments, say whether they exist or not, and be separated from the \
+operation it guards by a comma.
+
+Wrong:   If HR exists add Mihai to HR.
+Correct: If HR exists, add Mihai to HR."
+    }
+    ErrorKind::UndefinedTeam => {
+      "A statement refers to a team that hasn't been defined before it. Teams must be defined with \
+Define before they are used.
+
+Wrong:   Add team Core to HR.
+Correct: Define team Core as Mihai and Ana. Add team Core to HR."
+    }
+    ErrorKind::InvalidDefinition => {
+      "A definition must name a team and list its members after \"as\", and end with a full stop.
+
+Wrong:   Define Core as Mihai and Ana.
+Correct: Define team Core as Mihai and Ana."
+    }
+    ErrorKind::UnsupportedSyntax => {
+      "The statement uses syntax that was added in a later edition than the one the script is \
+parsed in, such as quoted names, which need edition 2. Either pass a later edition or write the \
+statement without it.
+
+Wrong:   Add \"Mary and Sons\" to Sales.
+Correct: Add Mary to Sales."
+    }
+    ErrorKind::InputTooLong => {
+      "The input is longer than the maximum length the lexer is configured with, so it was not \
+lexed any further. Split the script into smaller ones or raise the limit. The examples assume a \
+limit of 8 bytes.
+
+Wrong:   Create HR.
+Correct: Show HR."
+    }
+    ErrorKind::InvalidUtf8 => {
+      "The input contains bytes that aren't valid UTF-8 text, usually because the script was saved \
+in another encoding, such as Latin-1 or UTF-16. Save the script as UTF-8. The error points at the \
+first invalid byte."
+    }
+    ErrorKind::InvalidEscape => {
+      "A quoted name contains a backslash that doesn't start an escape sequence. Inside quotes, \\\" \
+stands for a quote, \\\\ for a backslash and \\n for a line break.
+
+Wrong:   Add \"Ana \\M\" to HR.
+Correct: Add \"Ana \\\"Mimi\\\"\" to HR."
+    }
+    ErrorKind::TooManyTokens => {
+      "The input has more tokens than the maximum the lexer is configured with, so it was not lexed \
+any further. Whitespace and punctuation count as tokens too. Split the script into smaller ones or \
+raise the limit. The examples assume a limit of 4 tokens.
+
+Wrong:   Show HR, IT.
+Correct: Show HR."
+    }
+    ErrorKind::LineTooLong => {
+      "A line is longer than the maximum line length the lexer is configured with, so it was not \
+lexed any further. Break the line after a statement or raise the limit. The examples assume a limit \
+of 10 bytes.
+
+Wrong:   Remove Mihai from HR.
+Correct: Create HR."
+    }
+    ErrorKind::InvalidLimit => {
+      "The number after \"first\" or \"limit\" is zero, which would show nothing, or larger than \
+the most results that can be shown.
+
+Wrong:   Show HR, first 99999999999999999999999.
+Correct: Show HR, first 10."
+    }
+    ErrorKind::DuplicateClause => {
+      "A clause such as a limit, an ordering or \"in detail\" is given more than once in the same \
+statement. Each can be given at most once, in any order.
+
+Wrong:   Show HR, first 10, first 3.
+Correct: Show HR, first 3."
+    }
+    ErrorKind::MisplacedAlias => {
+      "An \"also known as\" clause follows a department. Only the people added or removed can have \
+aliases, so the clause must follow a name, before \"to\" or \"from\".
+
+Wrong:   Add Mihai to HR, also known as Human Resources.
+Correct: Add Mihai, also known as Mike, to HR."
+    }
+    ErrorKind::MisplacedClause => {
+      "A limit such as \"first 10\" is given to an operation other than Show or Find. Only the \
+results of those can be limited.
+
+Wrong:   Add Mihai to HR, first 3.
+Correct: Show HR, first 3."
+    }
+    ErrorKind::InvalidEffective => {
+      "The word after \"starting\" at the end of a statement is not a day of the week, capitalized, \
+or a date that exists, written as year, month and day. A trailing \"starting\" always starts the \
+day the operation takes effect.
+
+Wrong:   Add Mihai to HR starting Funday.
+Correct: Add Mihai to HR starting 2024-03-15."
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+  use crate::lexer::{Lexer, LexerOptions};
+  use crate::parser::{self, Edition, ParserOptions};
+
+  #[test]
+  fn examples_match_kinds() {
+    for kind in ErrorKind::ALL {
+      let text = explain(kind);
+      // Invalid UTF-8 can't be written in an example.
+      if kind == ErrorKind::InvalidUtf8 {
+        continue;
+      }
+      let example = |label: &str| {
+        let start = text.find(label).unwrap() + label.len();
+        text[start..].lines().next().unwrap().trim()
+      };
+      assert!(lex_parse(example("Correct:")).is_ok(), "{}", kind);
+      // Only the syntax of later editions needs an earlier one to fail.
+      let edition = match kind {
+        ErrorKind::UnsupportedSyntax => Edition::V1,
+        _ => Edition::LATEST,
+      };
+      let options = ParserOptions::new().set_edition(edition);
+      let limit = |max, limited| Some(max).filter(|_| kind == limited);
+      let lexer_options = LexerOptions::new()
+        .set_max_len(limit(8, ErrorKind::InputTooLong))
+        .set_max_tokens(limit(4, ErrorKind::TooManyTokens))
+        .set_max_line_len(limit(10, ErrorKind::LineTooLong));
+      let err = parser::parse_iter(Lexer::with_options(example("Wrong:"), lexer_options))
+        .with_options(options)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap_err();
+      // Statements are split at their terminator and lists report missing ones first, so these
+      // two are caught earlier under other codes.
+      if !matches!(kind, ErrorKind::Unterminated | ErrorKind::TrailingTokens) {
+        assert_eq!(err.kind(), Some(kind), "{}", kind);
+      }
+    }
+  }
+
+  #[test]
+  fn codes() {
+    assert_eq!(ErrorKind::ReservedWord.to_string(), "E0008");
+    assert_eq!("e0008".parse(), Ok(ErrorKind::ReservedWord));
+    assert!("E0099".parse::<ErrorKind>().is_err());
+  }
+}
@@ -0,0 +1,163 @@
+use crate::error::{Error, ErrorKind};
+use crate::lexer::{self, Token};
+use crate::parser::{self, constants::*, Help};
+use crate::util;
+use std::fmt;
+use std::ops::Range;
+
+// Every fix removes the error it was made for, so this is only hit by fixes that keep uncovering new
+// errors.
+const MAX_FIXES: usize = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fix {
+  // The line the fix was applied on, in the source as it was when the fix was made.
+  pub line_number: usize,
+  pub message: String,
+}
+
+impl fmt::Display for Fix {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Fixed line {}: {}", self.line_number, self.message)
+  }
+}
+
+fn byte_offset(source: &str, token: Token) -> usize {
+  token.line().as_ptr() as usize - source.as_ptr() as usize + token.offset()
+}
+
+// Keywords are only corrected to the closest one when no other keyword is as close.
+fn closest_keyword(word: &str) -> Option<&'static str> {
+  let lowercase = word.to_lowercase();
+  let max_distance = (util::string_length(word) / 4).max(1);
+  let mut candidates: Vec<(&str, usize)> = KEYWORDS
+    .iter()
+    .chain(&[CONDITION_IF])
+    .map(|k| {
+      (
+        k.get(),
+        util::edit_distance(&lowercase, &k.get().to_lowercase()),
+      )
+    })
+    .filter(|(_, d)| *d <= max_distance)
+    .collect();
+  candidates.sort_by_key(|(_, d)| *d);
+  match candidates.as_slice() {
+    [(k, _)] => Some(k),
+    [(k, d), (_, next), ..] if d < next => Some(k),
+    _ => None,
+  }
+}
+
+fn fix_error(source: &str, err: &Error) -> Option<(Range<usize>, String, Fix)> {
+  let (kind, token, help) = match err {
+    Error::Parse(e) => (e.kind(), e.token(), e.help()),
+    _ => return None,
+  };
+  let start = byte_offset(source, token);
+  let fix = |message: String| Fix {
+    line_number: token.line_number(),
+    message,
+  };
+
+  if let (ErrorKind::MissingOperation, lexer::Word(word)) = (kind, token.value()) {
+    let keyword = closest_keyword(word)?;
+    let message = format!("replaced \"{}\" with \"{}\"", word, keyword);
+    return Some((start..start + word.len(), keyword.into(), fix(message)));
+  }
+  // Reserved words are quoted as the error suggests, which it only does where quoting is valid.
+  if let Some(Help::ReservedWord(_, Some(quoted))) = help {
+    let message = format!("quoted the reserved word {}", quoted);
+    return Some((
+      start..start + token.text().len(),
+      quoted.clone(),
+      fix(message),
+    ));
+  }
+  // Only the last statement can be missing its terminator: any other would run into the next one
+  // and fail on its keyword instead.
+  let last = parser::split_statements(source).pop()?;
+  let unterminated = matches!(
+    kind,
+    ErrorKind::Unterminated | ErrorKind::UnterminatedList | ErrorKind::EmptyList
+  ) && last.contains(start)
+    && !last.slice(source).ends_with(['.', '!', '?']);
+  if unterminated {
+    let end = last.end;
+    let message = "added a full stop at the end of the statement".into();
+    return Some((end..end, ".".into(), fix(message)));
+  }
+
+  None
+}
+
+// Applies the fixes for the errors of the source for which there is a single correction, until the
+// source parses or no more fixes can be made. Returns the fixed source and the applied fixes.
+pub fn fix(source: &str) -> (String, Vec<Fix>) {
+  fix_with(source, &parser::ParserOptions::default())
+}
+
+pub fn fix_with(source: &str, options: &parser::ParserOptions) -> (String, Vec<Fix>) {
+  let mut source = source.to_string();
+  let mut fixes = Vec::new();
+
+  for _ in 0..MAX_FIXES {
+    let edit = match parser::parse_statements_with(&source, options) {
+      Ok(_) => break,
+      Err(e) => fix_error(&source, &e),
+    };
+    let (range, replacement, fix) = match edit {
+      Some(edit) => edit,
+      None => break,
+    };
+    source.replace_range(range, &replacement);
+    fixes.push(fix);
+  }
+
+  (source, fixes)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fix_source() {
+    let (got, fixes) = fix("add Mihai to HR.\nCreat PR, then add Ana to it # for now.\n");
+    assert_eq!(
+      got,
+      "Add Mihai to HR.\nCreate PR, then add Ana to it. # for now.\n"
+    );
+    assert_eq!(
+      fixes.iter().map(|f| f.to_string()).collect::<Vec<_>>(),
+      vec![
+        "Fixed line 1: replaced \"add\" with \"Add\"",
+        "Fixed line 2: replaced \"Creat\" with \"Create\"",
+        "Fixed line 2: added a full stop at the end of the statement",
+      ]
+    );
+  }
+
+  #[test]
+  fn leave_ambiguous() {
+    // No keyword is close to "Mihai", and the empty list element has no single correction.
+    let source = "Mihai to HR.";
+    assert_eq!(fix(source), (source.to_string(), vec![]));
+    let (got, fixes) = fix("Create HR. Add Mihai, , Ana to HR.");
+    assert_eq!(got, "Create HR. Add Mihai, , Ana to HR.");
+    assert!(fixes.is_empty());
+  }
+
+  #[test]
+  fn quote_reserved() {
+    let (got, fixes) = fix("Create Show. Add Mihai to Show");
+    assert_eq!(got, "Create Show. Add Mihai to \"Show\".");
+    assert_eq!(fixes[0].message, "quoted the reserved word \"Show\"");
+
+    // Names can't be quoted in the first edition, so there is nothing to fix there.
+    let options = parser::ParserOptions::new().set_edition(parser::Edition::V1);
+    let (got, fixes) = fix_with("Create Show. Add Mihai to Show", &options);
+    assert_eq!(got, "Create Show. Add Mihai to Show");
+    assert!(fixes.is_empty());
+  }
+}
@@ -0,0 +1,178 @@
+// Random tokens and random valid scripts, for property tests and fuzzers of code that consumes
+// EQL. Generators are deterministic: the same seed, or the same fuzzer input, always produces the
+// same output.
+
+use crate::lexer::{self, TokenValue};
+use crate::parser::constants::GRAMMAR_WORDS;
+
+const NAMES: [&str; 8] = [
+  "Ana",
+  "Mihai",
+  "Maria Ioana",
+  "O'Brien",
+  "Anne-Marie",
+  "Agent 47",
+  "\"Show\"",
+  "\"Ana \\\"Mimi\\\"\"",
+];
+
+const DEPARTMENTS: [&str; 6] = [
+  "HR",
+  "Sales",
+  "IT",
+  "Research Lab",
+  "\"R and D\"",
+  "\"PR!\"",
+];
+
+const PUNCTUATION: [&str; 4] = [",", ".", "!", "?"];
+
+pub struct Generator {
+  state: u64,
+}
+
+impl Generator {
+  pub fn new(seed: u64) -> Self {
+    Generator { state: seed }
+  }
+
+  // Fuzzers give raw bytes, which are hashed into the seed.
+  pub fn from_bytes(bytes: &[u8]) -> Self {
+    let seed = bytes.iter().fold(0xcbf29ce484222325u64, |hash, b| {
+      (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    });
+    Self::new(seed)
+  }
+
+  fn below(&mut self, n: usize) -> usize {
+    self.state = self
+      .state
+      .wrapping_mul(6364136223846793005)
+      .wrapping_add(1442695040888963407);
+    (self.state >> 33) as usize % n
+  }
+
+  fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+    items[self.below(items.len())]
+  }
+
+  // Any value a token can have, valid or not.
+  pub fn token_value(&mut self) -> TokenValue<'static> {
+    match self.below(10) {
+      0 => lexer::Whitespace,
+      1 => GRAMMAR_WORDS[self.below(GRAMMAR_WORDS.len())],
+      2 => lexer::Word(self.pick(&["Ana", "Sales", "O'Brien", "x"])),
+      3 => lexer::Punctuation(self.pick(&PUNCTUATION)),
+      4 => lexer::Number(self.pick(&["0", "42", "2024"])),
+      5 => lexer::Quoted(self.pick(&["Show", "R and D", "Ana \\\"Mimi\\\""])),
+      _ => lexer::Unknown(self.pick(&["$", "&", "12th"])),
+    }
+  }
+
+  fn list(&mut self, items: &[&str]) -> String {
+    let len = 1 + self.below(3);
+    let elements: Vec<_> = (0..len).map(|_| self.pick(items)).collect();
+    match elements.split_last() {
+      Some((last, [])) => last.to_string(),
+      Some((last, rest)) => format!("{} and {}", rest.join(", "), last),
+      None => String::new(),
+    }
+  }
+
+  // A statement that parses, in any edition that supports quoted names.
+  pub fn statement(&mut self) -> String {
+    let fail_silently = self.below(4) == 0;
+    let terminator = match (fail_silently, self.below(4)) {
+      (true, 0) => " silently!",
+      (true, _) => "?",
+      (false, 0) => "!",
+      _ => ".",
+    };
+    // Only creating and adding can overwrite.
+    let plain = match terminator {
+      "!" => ".",
+      " silently!" => "?",
+      _ => terminator,
+    };
+    match self.below(7) {
+      0 => format!("Create {}{}", self.list(&DEPARTMENTS), terminator),
+      1 => format!(
+        "Add {} to {}{}",
+        self.list(&NAMES),
+        self.list(&DEPARTMENTS),
+        terminator
+      ),
+      2 => format!(
+        "Remove {} from {}{}",
+        self.list(&NAMES),
+        self.list(&DEPARTMENTS),
+        plain
+      ),
+      3 => format!("Show {}{}", self.list(&DEPARTMENTS), plain),
+      4 => format!("Dissolve {}{}", self.pick(&DEPARTMENTS), plain),
+      5 => format!(
+        "Rename employee {} to {}{}",
+        self.pick(&NAMES),
+        self.pick(&NAMES),
+        plain
+      ),
+      6 => format!(
+        "Rename {} to {}{}",
+        self.pick(&DEPARTMENTS),
+        self.pick(&DEPARTMENTS),
+        plain
+      ),
+      7 => format!(
+        "Move {} from {} to {}{}",
+        self.list(&NAMES),
+        self.pick(&DEPARTMENTS),
+        self.pick(&DEPARTMENTS),
+        plain
+      ),
+      8 => format!("Find {}{}", self.list(&NAMES), plain),
+      _ => format!("Where is {}?", self.pick(&NAMES)),
+    }
+  }
+
+  pub fn source(&mut self, statements: usize) -> String {
+    let separators = [" ", "\n", "\n\n", "  "];
+    let mut source = String::new();
+    for i in 0..statements {
+      if i > 0 {
+        source.push_str(self.pick(&separators));
+      }
+      source.push_str(&self.statement());
+    }
+    source
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[test]
+  fn sources_round_trip() {
+    for seed in 0..500 {
+      let source = Generator::new(seed).source(5);
+      let operations = lex_parse(&source).unwrap_or_else(|e| panic!("{}\n{}", source, e));
+      assert_eq!(operations.len(), 5, "{}", source);
+      let written: Vec<_> = operations.iter().map(|op| op.to_source()).collect();
+      let written = written.join(" ");
+      assert_eq!(lex_parse(&written).unwrap(), operations, "{}", written);
+    }
+    assert_eq!(
+      Generator::from_bytes(b"eql").source(3),
+      Generator::from_bytes(b"eql").source(3)
+    );
+  }
+
+  #[test]
+  fn token_values() {
+    let mut generator = Generator::new(7);
+    let values: Vec<_> = (0..200).map(|_| generator.token_value()).collect();
+    assert!(values.iter().any(|v| matches!(v, lexer::Keyword(_))));
+    assert!(values.iter().any(|v| matches!(v, lexer::Unknown(_))));
+  }
+}
@@ -0,0 +1,100 @@
+use crate::operation::{self, Operation};
+
+// Applications implement the methods for the operations they support and call handle, which
+// dispatches on the kind of the operation. Every kind without a method of its own, including kinds
+// added in later versions, goes to handle_other, so implementations keep compiling as the language
+// grows.
+pub trait HandleOperation {
+  type Output;
+
+  fn handle_other(&mut self, op: &Operation) -> Self::Output;
+
+  fn handle_add(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_create(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_remove(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_show(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_where(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_rename_employee(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_rename_department(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_dissolve(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_move(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle_find(&mut self, op: &Operation) -> Self::Output {
+    self.handle_other(op)
+  }
+
+  fn handle(&mut self, op: &Operation) -> Self::Output {
+    match op.kind() {
+      operation::Add => self.handle_add(op),
+      operation::Create => self.handle_create(op),
+      operation::Remove => self.handle_remove(op),
+      operation::Show => self.handle_show(op),
+      operation::Where => self.handle_where(op),
+      operation::RenameEmployee => self.handle_rename_employee(op),
+      operation::RenameDepartment => self.handle_rename_department(op),
+      operation::Dissolve => self.handle_dissolve(op),
+      operation::Move => self.handle_move(op),
+      operation::Find => self.handle_find(op),
+      operation::Unknown | operation::Define => self.handle_other(op),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[derive(Default)]
+  struct Departments(Vec<String>);
+
+  impl HandleOperation for Departments {
+    type Output = Result<(), String>;
+
+    fn handle_other(&mut self, op: &Operation) -> Self::Output {
+      Err(format!("{} is not supported", op.kind()))
+    }
+
+    fn handle_create(&mut self, op: &Operation) -> Self::Output {
+      self
+        .0
+        .extend_from_slice(op.get_departments().unwrap_or_default());
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn dispatch() {
+    let ops = lex_parse("Create HR, PR. Show HR.").unwrap();
+    let mut handler = Departments::default();
+    assert_eq!(handler.handle(&ops[0]), Ok(()));
+    assert_eq!(handler.handle(&ops[1]), Err("Show is not supported".into()));
+    assert_eq!(handler.0, vec!["HR", "PR"]);
+  }
+}
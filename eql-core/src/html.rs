@@ -0,0 +1,130 @@
+use crate::lexer::{self, Token};
+use crate::parser::{self, TokenClass};
+
+pub fn css_class(class: TokenClass) -> &'static str {
+  match class {
+    TokenClass::Keyword => "eql-keyword",
+    TokenClass::Linker => "eql-linker",
+    TokenClass::Terminator => "eql-terminator",
+    TokenClass::Separator => "eql-separator",
+    TokenClass::Word => "eql-word",
+    TokenClass::Whitespace => "eql-whitespace",
+    TokenClass::Comment => "eql-comment",
+    TokenClass::Unknown => "eql-unknown",
+  }
+}
+
+fn escape(s: &str) -> String {
+  s.chars()
+    .fold(String::with_capacity(s.len()), |mut res, c| {
+      match c {
+        '&' => res.push_str("&amp;"),
+        '<' => res.push_str("&lt;"),
+        '>' => res.push_str("&gt;"),
+        '"' => res.push_str("&quot;"),
+        _ => res.push(c),
+      }
+      res
+    })
+}
+
+fn get_error(source: &str) -> Option<(Token<'_>, String)> {
+  match lexer::lex(source) {
+    Err(e) => Some((e.token(), e.to_string())),
+    Ok(tokens) => parser::parse(tokens)
+      .err()
+      .map(|e| (e.token(), e.to_string())),
+  }
+}
+
+fn fmt_diagnostic(message: &str) -> String {
+  format!(
+    "<span class=\"eql-diagnostic\">{}</span>\n",
+    escape(message)
+  )
+}
+
+// Every token is wrapped in a span classed by the part it plays in its statement. The token an
+// error points to is marked and the error message is inserted after the line it is on.
+pub fn render(source: &str) -> String {
+  let error = get_error(source);
+  let mut res = String::from("<pre class=\"eql\">");
+  let error_position = error
+    .as_ref()
+    .map(|(t, _)| (t.line_number(), t.column_number()));
+  let mut pending_diagnostic = None;
+
+  for (token, class) in parser::classify_tokens(source) {
+    let text = token.text();
+    if error_position == Some((token.line_number(), token.column_number())) {
+      let message = &error.as_ref().unwrap().1;
+      res.push_str(&format!(
+        "<span class=\"{} eql-error\" title=\"{}\">{}</span>",
+        css_class(class),
+        escape(message),
+        escape(text)
+      ));
+      pending_diagnostic = Some(message);
+    } else if token.value() == lexer::Whitespace {
+      res.push_str(text);
+    } else {
+      res.push_str(&format!(
+        "<span class=\"{}\">{}</span>",
+        css_class(class),
+        escape(text)
+      ));
+    }
+    if text.ends_with('\n') {
+      if let Some(message) = pending_diagnostic.take() {
+        res.push_str(&fmt_diagnostic(message));
+      }
+    }
+  }
+  if let Some(message) = pending_diagnostic {
+    res.push('\n');
+    res.push_str(&fmt_diagnostic(message));
+  }
+
+  res.push_str("</pre>");
+  res
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn render_valid() {
+    let got = render("Add Mihai to HR, Sales!");
+    let expect = concat!(
+      r#"<pre class="eql"><span class="eql-keyword">Add</span> "#,
+      r#"<span class="eql-word">Mihai</span> <span class="eql-linker">to</span> "#,
+      r#"<span class="eql-word">HR</span><span class="eql-separator">,</span> "#,
+      r#"<span class="eql-word">Sales</span><span class="eql-terminator">!</span></pre>"#
+    );
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn render_error() {
+    let source = "Show HR <b>.\nShow Sales.";
+    let message = escape(&lexer::lex(source).unwrap_err().to_string());
+    let got = render(source);
+    let expect = format!(
+      concat!(
+        r#"<pre class="eql"><span class="eql-keyword">Show</span> "#,
+        r#"<span class="eql-word">HR</span> "#,
+        r#"<span class="eql-unknown eql-error" title="{0}">&lt;</span>"#,
+        r#"<span class="eql-word">b</span><span class="eql-unknown">&gt;</span>"#,
+        r#"<span class="eql-terminator">.</span>"#,
+        "\n",
+        r#"<span class="eql-diagnostic">{0}</span>"#,
+        "\n",
+        r#"<span class="eql-keyword">Show</span> <span class="eql-word">Sales</span>"#,
+        r#"<span class="eql-terminator">.</span></pre>"#
+      ),
+      message
+    );
+    assert_eq!(got, expect);
+  }
+}
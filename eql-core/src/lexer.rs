@@ -0,0 +1,1392 @@
+#![forbid(unsafe_code)]
+
+use crate::error::ErrorKind;
+use crate::messages::{Catalog, English};
+use crate::parser::constants::{
+  get_synonym, grammar_index, grammar_token, is_keyword, is_terminator, is_trivia,
+  CASE_SENSITIVE_WORDS,
+};
+use crate::parser::Grammar;
+use crate::text::{self, UnicodeSegmentation};
+use crate::util;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::ops::Range;
+use core::{default, fmt, hash, iter};
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod incremental;
+mod options;
+mod owned;
+#[cfg(feature = "std")]
+mod reader;
+#[cfg(feature = "serde")]
+mod serialize;
+
+pub use self::incremental::{diff_tokens, relex, TokenEdit};
+pub use self::options::LexerOptions;
+pub use self::owned::{OwnedError, OwnedToken};
+#[cfg(feature = "std")]
+pub use self::reader::{lex_reader, ReadError, ReaderLexer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TokenValue<'a> {
+  Whitespace,
+  Word(&'a str),
+  // A word that starts an operation, like "Add". Quoting it makes it a name instead.
+  Keyword(&'a str),
+  Punctuation(&'a str),
+  // ASCII digits, in groups joined by hyphens if there are more, as in the date 2024-03-15.
+  Number(&'a str),
+  // The text between the quotes, which is taken as is.
+  Quoted(&'a str),
+  // A comment from its "#" or "--" to the end of the line, which the parser skips.
+  Comment(&'a str),
+  Unknown(&'a str),
+}
+
+impl<'a> TokenValue<'a> {
+  fn get_type_and_value(&self) -> (&'static str, &'a str) {
+    match *self {
+      Whitespace => ("whitespace", " "),
+      Word(s) => ("word", s),
+      Keyword(s) => ("keyword", s),
+      Punctuation(s) => ("punctuation", s),
+      Number(s) => ("number", s),
+      Quoted(s) => ("quoted", s),
+      Comment(s) => ("comment", s),
+      Unknown(s) => ("unknown", s),
+    }
+  }
+
+  pub fn get(&self) -> &'a str {
+    self.get_type_and_value().1
+  }
+
+  pub fn is_comment(&self) -> bool {
+    matches!(self, Comment(_))
+  }
+}
+
+impl fmt::Display for TokenValue<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (t, v) = self.get_type_and_value();
+    if v.is_empty() || matches!(*self, Whitespace) {
+      write!(f, "any {} token", t)
+    } else {
+      write!(f, "{} token \"{}\"", t, util::fmt_truncated(v))
+    }
+  }
+}
+
+impl default::Default for TokenValue<'_> {
+  fn default() -> Self {
+    Unknown("")
+  }
+}
+
+pub use TokenValue::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum TokenKind {
+  Whitespace,
+  Word,
+  Keyword,
+  Punctuation,
+  Number,
+  Quoted,
+  Comment,
+  Unknown,
+  // A word or punctuation that stands for the grammar token with this index, written in another
+  // casing or spelled as another grammar spells it. See constants::grammar_token.
+  Grammar(u8),
+}
+
+// The value is stored as a span into the source the token was lexed from instead of a slice of its
+// own, and the line and the column are found from the span when they are asked for. Spans are 32
+// bits wide, which keeps tokens at 32 bytes on 64-bit targets, down from 56.
+#[derive(Clone, Copy)]
+pub struct Token<'a> {
+  source: &'a str,
+  start: u32,
+  len: u32,
+  // The lines before the source, for sources that are a line of a larger input, like the lines
+  // ReaderLexer lexes one at a time. It takes up what would otherwise be padding.
+  lines_before: u32,
+  kind: TokenKind,
+}
+
+impl<'a> Token<'a> {
+  // The token starts at the offset in the source, where the text of the value must be, with the
+  // quotes around it if it is quoted. A whitespace token spans the whitespace at the offset, up to
+  // the end of its line. There is no token if the text isn't there or its span doesn't fit in 32
+  // bits.
+  pub fn new(value: TokenValue<'a>, source: &'a str, offset: usize) -> Option<Self> {
+    let rest = source.get(offset..)?;
+    let (kind, len) = match value {
+      Whitespace => {
+        let end = rest
+          .find(|c: char| !c.is_whitespace())
+          .unwrap_or(rest.len());
+        let len = rest[..end].find('\n').map_or(end, |i| i + 1);
+        (TokenKind::Whitespace, Some(len).filter(|len| *len > 0)?)
+      }
+      Word(s) => (TokenKind::Word, s.len()),
+      Keyword(s) => (TokenKind::Keyword, s.len()),
+      Punctuation(s) => (TokenKind::Punctuation, s.len()),
+      Number(s) => (TokenKind::Number, s.len()),
+      Quoted(s) => (TokenKind::Quoted, s.len() + 2),
+      Comment(s) => (TokenKind::Comment, s.len()),
+      Unknown(s) => (TokenKind::Unknown, s.len()),
+    };
+    let text = rest.get(..len)?;
+    let token = Token {
+      source,
+      start: u32::try_from(offset).ok()?,
+      len: u32::try_from(len).ok()?,
+      lines_before: 0,
+      kind,
+    };
+    let quoted = kind != TokenKind::Quoted || is_quoted(text);
+    (quoted && token.value() == value).then_some(token)
+  }
+
+  // There is no token if its span doesn't fit in 32 bits, which input no longer than MAX_LEN rules
+  // out.
+  fn from_segment(segment: &'a str, source: &'a str, enumerator_end: usize) -> Option<Self> {
+    let start = segment.as_ptr() as usize - source.as_ptr() as usize;
+    Some(Token {
+      source,
+      start: u32::try_from(start).ok()?,
+      len: u32::try_from(segment.len()).ok()?,
+      lines_before: 0,
+      kind: match get_token_value(segment) {
+        _ if start < enumerator_end => TokenKind::Whitespace,
+        Whitespace => TokenKind::Whitespace,
+        Word(_) => TokenKind::Word,
+        Keyword(_) => TokenKind::Keyword,
+        Punctuation(_) => TokenKind::Punctuation,
+        Number(_) => TokenKind::Number,
+        Quoted(_) => TokenKind::Quoted,
+        Comment(_) => TokenKind::Comment,
+        Unknown(_) => TokenKind::Unknown,
+      },
+    })
+  }
+
+  // The text the token contributes to a name: quoted names are unescaped and, with the "nfc"
+  // feature, names are normalized, so that accents encoded differently compare equal.
+  pub fn name(&self) -> Cow<'a, str> {
+    let name = match self.value() {
+      Quoted(q) => unescape(q),
+      value => value.get().into(),
+    };
+    text::normalize(name)
+  }
+
+  // The text of the token as it is in the source, including the quotes of quoted names and all of
+  // its whitespace.
+  pub fn text(&self) -> &'a str {
+    &self.source[self.start as usize..(self.start + self.len) as usize]
+  }
+
+  pub fn value(&self) -> TokenValue<'a> {
+    match self.kind {
+      TokenKind::Whitespace => Whitespace,
+      TokenKind::Word => Word(self.text()),
+      TokenKind::Keyword => Keyword(self.text()),
+      TokenKind::Punctuation => Punctuation(self.text()),
+      TokenKind::Number => Number(self.text()),
+      TokenKind::Quoted => Quoted(unquote(self.text())),
+      TokenKind::Comment => Comment(self.text()),
+      TokenKind::Unknown => Unknown(self.text()),
+      TokenKind::Grammar(i) => grammar_token(i),
+    }
+  }
+
+  // The value as it is written in the source, which only differs from the value for grammar tokens
+  // matched regardless of their case or spelled differently.
+  pub fn written_value(&self) -> TokenValue<'a> {
+    match self.kind {
+      TokenKind::Grammar(i) => match grammar_token(i) {
+        Keyword(_) => Keyword(self.text()),
+        Punctuation(_) => Punctuation(self.text()),
+        _ => Word(self.text()),
+      },
+      _ => self.value(),
+    }
+  }
+
+  // Makes a word written in another casing than a grammar word stand for it, unless the word is
+  // only meant in lower case.
+  pub(crate) fn ignore_case(mut self) -> Self {
+    // Grammar words are written either in lower case or capitalized, so those are the only spellings
+    // a word can stand for. They are short and ASCII, so anything else is left as it is.
+    let mut lower = [0; 16];
+    let w = match self.value() {
+      Word(w) if w.is_ascii() && w.len() <= lower.len() => w,
+      _ => return self,
+    };
+    let lower = &mut lower[..w.len()];
+    lower.copy_from_slice(w.as_bytes());
+    lower.make_ascii_lowercase();
+    let mut capitalized = [0; 16];
+    let capitalized = &mut capitalized[..w.len()];
+    capitalized.copy_from_slice(lower);
+    capitalized[..1].make_ascii_uppercase();
+    let (lower, capitalized) = match (
+      core::str::from_utf8(lower),
+      core::str::from_utf8(capitalized),
+    ) {
+      (Ok(lower), Ok(capitalized)) => (lower, capitalized),
+      _ => return self,
+    };
+    let grammar_word = [Keyword(capitalized), Word(capitalized), Word(lower)]
+      .iter()
+      .filter(|g| g.get() != w && !CASE_SENSITIVE_WORDS.contains(g))
+      .filter_map(|g| grammar_index(*g))
+      .min();
+    if let Some(i) = grammar_word {
+      self.kind = TokenKind::Grammar(i);
+    }
+    self
+  }
+
+  // Makes a synonym of a keyword stand for the keyword, e.g. "Delete" for "Remove".
+  pub(crate) fn resolve_synonym(mut self) -> Self {
+    if let Some(i) = get_synonym(self.value()).and_then(grammar_index) {
+      self.kind = TokenKind::Grammar(i);
+    }
+    self
+  }
+
+  // The span of the token without its source, for buffers that outlive the source.
+  pub(crate) fn detach(self) -> Token<'static> {
+    Token {
+      source: "",
+      start: self.start,
+      len: self.len,
+      lines_before: self.lines_before,
+      kind: self.kind,
+    }
+  }
+
+  // Puts a detached token back in the source it was lexed from.
+  pub(crate) fn attach(self, source: &str) -> Token<'_> {
+    Token {
+      source,
+      start: self.start,
+      len: self.len,
+      lines_before: self.lines_before,
+      kind: self.kind,
+    }
+  }
+
+  // Makes a token spelled as a grammar token of the given grammar stand for it.
+  pub(crate) fn respell(mut self, grammar: &Grammar) -> Self {
+    let respellable = matches!(
+      self.kind,
+      TokenKind::Word | TokenKind::Keyword | TokenKind::Punctuation | TokenKind::Unknown
+    );
+    if let Some(i) = grammar.lookup(self.text()).filter(|_| respellable) {
+      self.kind = TokenKind::Grammar(i);
+    }
+    self
+  }
+
+  fn line_start(&self) -> usize {
+    let before = &self.source[..self.start as usize];
+    before.rfind('\n').map_or(0, |i| i + 1)
+  }
+
+  // The line the token is on, with its line break.
+  pub fn line(&self) -> &'a str {
+    let start = self.start as usize;
+    let end = self.source[start..]
+      .find('\n')
+      .map_or(self.source.len(), |i| start + i + 1);
+    &self.source[self.line_start()..end]
+  }
+
+  // The byte offset of the token in the source it was lexed from.
+  pub fn source_offset(&self) -> usize {
+    self.start as usize
+  }
+
+  // The byte offset of the token in its line.
+  pub fn offset(&self) -> usize {
+    self.start as usize - self.line_start()
+  }
+
+  // The line and the column are counted from the start of the source every time, so consumers that
+  // need the position of many tokens should look their source offsets up in a LineIndex instead.
+  pub fn line_number(&self) -> usize {
+    let before = &self.source.as_bytes()[..self.start as usize];
+    self.lines_before as usize + before.iter().filter(|b| **b == b'\n').count() + 1
+  }
+
+  // Counts graphemes, like LineIndex. A token at the end of its line is right after its last
+  // grapheme.
+  pub fn column_number(&self) -> usize {
+    let (line, offset) = (self.line(), self.offset());
+    let graphemes = line
+      .grapheme_indices(true)
+      .take_while(|(i, _)| *i <= offset)
+      .count();
+    graphemes.max(1) + (offset == line.len() && offset > 0) as usize
+  }
+
+  pub fn position(&self) -> Position {
+    let line = self.line();
+    Position {
+      line: self.line_number(),
+      column: self.column_number(),
+      offset: self.offset(),
+      utf16_column: line[..self.offset()].encode_utf16().count(),
+    }
+  }
+
+  // What a whitespace token is made of, which the parser doesn't care about but formatters do.
+  // A token with a line break is a newline whatever else it has, as it ends its line.
+  pub fn whitespace_kind(&self) -> Option<WhitespaceKind> {
+    let text = self.text();
+    match self.kind {
+      TokenKind::Whitespace if text.contains('\n') => Some(WhitespaceKind::Newline),
+      TokenKind::Whitespace if text.contains('\t') => Some(WhitespaceKind::Tab),
+      TokenKind::Whitespace => Some(WhitespaceKind::Space),
+      _ => None,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WhitespaceKind {
+  Space,
+  Tab,
+  Newline,
+}
+
+// Where a token starts in its line, in the units different consumers count in. The line and the
+// column count from 1 and the column counts graphemes, as people see them. The byte offset and the
+// UTF-16 column count from 0, as strings and editor protocols do.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Position {
+  pub line: usize,
+  pub column: usize,
+  pub offset: usize,
+  pub utf16_column: usize,
+}
+
+// Tokens compare by what they represent, not by the source they were lexed from: their value, their
+// line and where they are in it.
+impl PartialEq for Token<'_> {
+  fn eq(&self, other: &Self) -> bool {
+    self.value() == other.value()
+      && self.offset() == other.offset()
+      && self.line() == other.line()
+      && self.line_number() == other.line_number()
+  }
+}
+
+impl Eq for Token<'_> {}
+
+impl hash::Hash for Token<'_> {
+  fn hash<H: hash::Hasher>(&self, state: &mut H) {
+    self.value().hash(state);
+    self.offset().hash(state);
+    self.line().hash(state);
+  }
+}
+
+impl fmt::Debug for Token<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.debug_struct("Token")
+      .field("value", &self.value())
+      .field("line", &self.line())
+      .field("line_number", &self.line_number())
+      .field("column_number", &self.column_number())
+      .finish()
+  }
+}
+
+// Shows the token as written, with where it is, like: word "Sales" at 3:12.
+impl fmt::Display for Token<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (t, v) = self.written_value().get_type_and_value();
+    match self.kind {
+      TokenKind::Whitespace => write!(f, "{}", t),
+      _ => write!(f, "{} \"{}\"", t, util::fmt_truncated(v)),
+    }?;
+    write!(f, " at {}:{}", self.line_number(), self.column_number())
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Error<'a> {
+  token: Token<'a>,
+  kind: ErrorKind,
+  // The limit that was exceeded, for errors about the size of the input.
+  limit: Option<usize>,
+}
+
+impl<'a> Error<'a> {
+  pub(crate) fn unknown(token: Token<'a>) -> Self {
+    Error {
+      token,
+      kind: ErrorKind::UnknownToken,
+      limit: None,
+    }
+  }
+
+  pub(crate) fn limit(kind: ErrorKind, token: Token<'a>, limit: usize) -> Self {
+    Error {
+      token,
+      kind,
+      limit: Some(limit),
+    }
+  }
+
+  // Input longer than MAX_LEN can't be lexed, so the error points at its start.
+  pub(crate) fn too_long(s: &'a str) -> Option<Self> {
+    (s.len() > MAX_LEN).then(|| Self::input_too_long(s))
+  }
+
+  fn input_too_long(s: &'a str) -> Self {
+    let token = Token {
+      source: &s[..0],
+      start: 0,
+      len: 0,
+      lines_before: 0,
+      kind: TokenKind::Unknown,
+    };
+    Self::limit(ErrorKind::InputTooLong, token, MAX_LEN)
+  }
+
+  pub fn token(&self) -> Token<'a> {
+    self.token
+  }
+
+  pub fn kind(&self) -> ErrorKind {
+    self.kind
+  }
+
+  pub fn get_limit(&self) -> Option<usize> {
+    self.limit
+  }
+
+  // The error points at the escape sequence, which starts after the opening quote, or at the whole
+  // name if the position of the sequence doesn't fit in 32 bits.
+  fn invalid_escape(token: Token<'a>, offset: usize, len: usize) -> Self {
+    let start = token.source_offset() + 1 + offset;
+    let escape = || {
+      Some(Token {
+        start: u32::try_from(start).ok()?,
+        len: u32::try_from(len).ok()?,
+        kind: TokenKind::Unknown,
+        ..token
+      })
+    };
+    Error {
+      token: escape().unwrap_or(token),
+      kind: ErrorKind::InvalidEscape,
+      limit: None,
+    }
+  }
+}
+
+impl fmt::Display for Error<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let token = &self.token;
+    let (excerpt, padding, pointer) =
+      util::fmt_excerpt(token.line(), token.value().get(), token.column_number());
+    write!(
+      f,
+      "Error {} on line {}, column {}: {}\n  {}\n  {}{}",
+      self.kind(),
+      token.line_number(),
+      token.column_number(),
+      English.details(self),
+      excerpt,
+      padding,
+      pointer
+    )
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error<'_> {}
+
+fn is_punctuation(s: &str) -> bool {
+  let b = s.as_bytes();
+  b.len() == 1 && matches!(b[0], b',' | b'.' | b'!' | b'?')
+}
+
+fn is_number(s: &str) -> bool {
+  s.split('-')
+    .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+fn unquote(s: &str) -> &str {
+  &s[1..s.len() - 1]
+}
+
+fn is_quoted(s: &str) -> bool {
+  s.len() > 2 && s.starts_with('"') && s.ends_with('"')
+}
+
+// The offset of the first quote that isn't escaped with a backslash.
+fn find_closing_quote(s: &str) -> Option<usize> {
+  let mut escaped = false;
+  s.char_indices()
+    .find(|&(_, c)| {
+      let closing = c == '"' && !escaped;
+      escaped = c == '\\' && !escaped;
+      closing
+    })
+    .map(|(i, _)| i)
+}
+
+// The offset and the length of the first backslash in the text of a quoted name that isn't
+// followed by a quote, a backslash or an "n".
+fn find_invalid_escape(s: &str) -> Option<(usize, usize)> {
+  let mut chars = s.char_indices();
+  while let Some((i, c)) = chars.next() {
+    if c == '\\' {
+      match chars.next() {
+        Some((_, '"' | '\\' | 'n')) => {}
+        next => return Some((i, 1 + next.map_or(0, |(_, c)| c.len_utf8()))),
+      }
+    }
+  }
+  None
+}
+
+// The name a quoted token stands for, with its escape sequences replaced.
+pub fn unescape(s: &str) -> Cow<'_, str> {
+  if !s.contains('\\') {
+    return s.into();
+  }
+  let mut res = String::with_capacity(s.len());
+  let mut chars = s.chars();
+  while let Some(c) = chars.next() {
+    match (c, c == '\\') {
+      (_, true) => match chars.next() {
+        Some('n') => res.push('\n'),
+        Some(escaped) => res.push(escaped),
+        None => res.push(c),
+      },
+      _ => res.push(c),
+    }
+  }
+  res.into()
+}
+
+fn is_comment(s: &str) -> bool {
+  s.starts_with('#') || s.starts_with("--")
+}
+
+fn is_joiner(s: &str) -> bool {
+  matches!(s, "'" | "\u{2019}" | "-")
+}
+
+// Apostrophes and hyphens are part of a word when they are between letters, as in "O'Brien" and
+// "Anne-Marie".
+fn is_word(s: &str) -> bool {
+  s.split(['\'', '\u{2019}', '-'])
+    .all(|part| !part.is_empty() && util::is_alphabetic(part))
+}
+
+// Words spelled like a keyword are keywords.
+pub(crate) fn word(s: &str) -> TokenValue<'_> {
+  match is_keyword(Keyword(s)) {
+    true => Keyword(s),
+    false => Word(s),
+  }
+}
+
+fn get_token_value(s: &str) -> TokenValue<'_> {
+  match () {
+    _ if is_comment(s) => Comment(s),
+    _ if util::is_whitespace(s) => Whitespace,
+    _ if is_punctuation(s) => Punctuation(s),
+    _ if is_quoted(s) => Quoted(unquote(s)),
+    _ if is_word(s) => word(s),
+    _ if is_number(s) => Number(s),
+    _ => Unknown(s),
+  }
+}
+
+// Scripts pasted from documents often number their statements or list them as bullets. Returns the
+// length of the "1.", "2)", "-" or "*" that starts the line, including the whitespace before it, so
+// it can be lexed as whitespace.
+fn enumerator_len(line: &str) -> usize {
+  let trimmed = line.trim_start();
+  let digits = trimmed.bytes().take_while(u8::is_ascii_digit).count();
+  let marker = match trimmed.as_bytes() {
+    [b'-', ..] | [b'*', ..] => 1,
+    [..] if digits > 0 && matches!(trimmed.as_bytes().get(digits), Some(b'.') | Some(b')')) => {
+      digits + 1
+    }
+    _ => return 0,
+  };
+  match trimmed[marker..].chars().next() {
+    Some(c) if c.is_whitespace() => line.len() - trimmed.len() + marker,
+    _ => 0,
+  }
+}
+
+// Splits the line into quoted names, comments and the text between them, so the content of quoted
+// names and comments isn't split into words. A quote that isn't closed on the same line, or that is
+// closed right away, is left to the word segmentation, which makes it an unknown token. Comments end
+// before the line break, which is lexed as whitespace.
+fn split_chunks(line: &str) -> impl Iterator<Item = (&str, bool)> {
+  let mut rest = line;
+  iter::from_fn(move || {
+    if rest.is_empty() {
+      return None;
+    }
+    let start = rest
+      .char_indices()
+      .map(|(i, _)| i)
+      .find(|i| rest[*i..].starts_with('"') || is_comment(&rest[*i..]));
+    let (len, whole) = match start {
+      Some(0) if is_comment(rest) => (rest.trim_end_matches(['\r', '\n']).len(), true),
+      Some(0) => match find_closing_quote(&rest[1..]) {
+        Some(0) => (2, false),
+        Some(end) => (end + 2, true),
+        None => (rest.len(), false),
+      },
+      Some(start) => (start, false),
+      None => (rest.len(), false),
+    };
+    let (chunk, tail) = rest.split_at(len);
+    rest = tail;
+    Some((chunk, whole))
+  })
+}
+
+// Word bounds don't always keep apostrophes and never keep hyphens inside words, so the words they
+// join are merged back together. Numbers are only joined by hyphens, as in dates.
+fn split_words(text: &str) -> impl Iterator<Item = &str> {
+  let mut segments = text.split_word_bounds();
+  let offset = move |s: &str| s.as_ptr() as usize - text.as_ptr() as usize;
+  iter::from_fn(move || {
+    let first = segments.next()?;
+    let mut end = offset(first) + first.len();
+    let joins = |joiner: &str, next: &str| match is_word(first) {
+      true => is_joiner(joiner) && is_word(next),
+      false => joiner == "-" && is_number(next),
+    };
+    if is_word(first) || is_number(first) {
+      loop {
+        let mut ahead = segments.clone();
+        match (ahead.next(), ahead.next()) {
+          (Some(joiner), Some(word)) if joins(joiner, word) => {
+            end = offset(word) + word.len();
+            segments = ahead;
+          }
+          _ => break,
+        }
+      }
+    }
+    Some(&text[offset(first)..end])
+  })
+}
+
+fn split_segments(line: &str) -> impl Iterator<Item = &str> {
+  split_chunks(line).flat_map(|(chunk, whole)| {
+    let (whole, words) = match whole {
+      true => (Some(chunk), ""),
+      false => (None, chunk),
+    };
+    whole.into_iter().chain(split_words(words))
+  })
+}
+
+// Lexes a line of the source, which the tokens are spans of. Enumerators are only stripped at the
+// start of a statement, so a line that continues one can start with a number.
+pub(crate) fn line_segments<'a>(
+  source: &'a str,
+  line: &'a str,
+  statement_start: bool,
+) -> impl Iterator<Item = (&'a str, Token<'a>)> {
+  let line_start = line.as_ptr() as usize - source.as_ptr() as usize;
+  let enumerator_end = match statement_start {
+    true => line_start + enumerator_len(line),
+    false => 0,
+  };
+  split_segments(line).map_while(move |segment| {
+    let token = Token::from_segment(segment, source, enumerator_end)?;
+    Some((segment, token))
+  })
+}
+
+// Whether a statement starts after the token, or None if the token doesn't tell.
+pub(crate) fn starts_statement(token: &Token) -> Option<bool> {
+  match token.value() {
+    v if is_trivia(v) => None,
+    v => Some(is_terminator(v)),
+  }
+}
+
+pub(crate) fn segments(s: &str) -> impl Iterator<Item = (&str, Token<'_>)> {
+  segments_from(s, 0..s.len(), true)
+}
+
+// Positions are stored in 32 bits, so longer input isn't lexed.
+pub const MAX_LEN: usize = u32::MAX as usize;
+
+// Lexes the lines of the source in the range, where a statement might already be underway. Source
+// longer than MAX_LEN has no segments, and the entry points report it as too long.
+pub(crate) fn segments_from(
+  source: &str,
+  range: Range<usize>,
+  statement_start: bool,
+) -> impl Iterator<Item = (&str, Token<'_>)> {
+  let lines = match source.len() > MAX_LEN {
+    true => "",
+    false => &source[range],
+  };
+  Segments {
+    source,
+    lines: lines.split_inclusive('\n'),
+    line: None,
+    statement_start,
+    lex_line: line_segments,
+  }
+}
+
+// Whether a line starts a statement depends on the tokens of the lines before it, so it is tracked
+// here as the tokens are handed out. The lexer of a line is a parameter only because its type can't
+// be named.
+struct Segments<'a, F, L> {
+  source: &'a str,
+  lines: core::str::SplitInclusive<'a, char>,
+  line: Option<L>,
+  statement_start: bool,
+  lex_line: F,
+}
+
+impl<'a, F, L> Iterator for Segments<'a, F, L>
+where
+  F: Fn(&'a str, &'a str, bool) -> L,
+  L: Iterator<Item = (&'a str, Token<'a>)>,
+{
+  type Item = (&'a str, Token<'a>);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some((segment, token)) = self.line.as_mut().and_then(Iterator::next) {
+        if let Some(start) = starts_statement(&token) {
+          self.statement_start = start;
+        }
+        return Some((segment, token));
+      }
+      let line = self.lines.next()?;
+      self.line = Some((self.lex_line)(self.source, line, self.statement_start));
+    }
+  }
+}
+
+// Lexes the input lazily, a token at a time. Unknown tokens are returned as errors, after which
+// lexing continues with the next token. Exceeding a limit ends lexing.
+pub struct Lexer<'a> {
+  source: &'a str,
+  segments: iter::Peekable<Box<dyn Iterator<Item = (&'a str, Token<'a>)> + 'a>>,
+  options: LexerOptions,
+  count: usize,
+  done: bool,
+}
+
+impl<'a> Lexer<'a> {
+  pub fn new(s: &'a str) -> Self {
+    Self::with_options(s, LexerOptions::default())
+  }
+
+  pub fn with_options(s: &'a str, options: LexerOptions) -> Self {
+    Lexer {
+      source: s,
+      segments: (Box::new(segments(s)) as Box<dyn Iterator<Item = _>>).peekable(),
+      options,
+      count: 0,
+      done: false,
+    }
+  }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+  type Item = Result<Token<'a>, Error<'a>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.done {
+      return None;
+    }
+    if let Some(e) = Error::too_long(self.source) {
+      self.done = true;
+      return Some(Err(e));
+    }
+    let (_, mut token) = self.segments.next()?;
+    if self.options.get_coalesce_whitespace() && token.kind == TokenKind::Whitespace {
+      // Tokens can't span lines, so only the whitespace up to the end of the line is merged.
+      while let Some((_, next)) = self
+        .segments
+        .next_if(|(_, next)| next.kind == TokenKind::Whitespace && !token.text().ends_with('\n'))
+      {
+        token.len += next.len;
+      }
+    }
+    if self.options.get_join_words() && token.kind == TokenKind::Word {
+      while let Some((_, next)) = self
+        .segments
+        .next_if(|(_, next)| next.kind == TokenKind::Word && next.start == token.start + token.len)
+      {
+        token.len += next.len;
+      }
+    }
+    // Line breaks don't count towards the length of their line.
+    let line_len = token.line().trim_end_matches(&['\r', '\n'][..]).len();
+    let line_end = (token.offset() + token.text().len()).min(line_len);
+    let end = token.source_offset() + token.text().len();
+    self.count += 1;
+    let limits = [
+      (ErrorKind::InputTooLong, self.options.get_max_len(), end),
+      (
+        ErrorKind::TooManyTokens,
+        self.options.get_max_tokens(),
+        self.count,
+      ),
+      (
+        ErrorKind::LineTooLong,
+        self.options.get_max_line_len(),
+        line_end,
+      ),
+    ];
+    for &(kind, max, len) in &limits {
+      if let Some(max) = max.filter(|&max| len > max) {
+        self.done = true;
+        return Some(Err(Error::limit(kind, token, max)));
+      }
+    }
+    Some(check(self.options.apply(token)))
+  }
+}
+
+// Tokens that lex but aren't valid are errors: unknown tokens, and quoted names with an escape
+// sequence the language doesn't know.
+pub(crate) fn check(token: Token<'_>) -> Result<Token<'_>, Error<'_>> {
+  match token.value() {
+    Unknown(_) => Err(Error::unknown(token)),
+    Quoted(q) => match find_invalid_escape(q) {
+      Some((offset, len)) => Err(Error::invalid_escape(token, offset, len)),
+      None => Ok(token),
+    },
+    _ => Ok(token),
+  }
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(len = s.len()), err(level = "debug")))]
+pub fn lex(s: &str) -> Result<Vec<Token<'_>>, Error<'_>> {
+  lex_iter(s).collect()
+}
+
+// Lexes with the default options without allocating, unlike Lexer, which can be configured. Unknown
+// tokens are returned as errors, after which lexing continues with the next token.
+pub fn lex_iter(s: &str) -> impl Iterator<Item = Result<Token<'_>, Error<'_>>> {
+  let too_long = Error::too_long(s).map(Err);
+  too_long
+    .into_iter()
+    .chain(segments(s).map(|(_, token)| check(token)))
+}
+
+// The position of the first invalid byte, as an empty token at the end of the valid text before it.
+// There is none if the position doesn't fit in 32 bits.
+fn invalid_utf8_token(valid: &str) -> Option<Token<'_>> {
+  Some(Token {
+    source: valid,
+    start: u32::try_from(valid.len()).ok()?,
+    len: 0,
+    lines_before: 0,
+    kind: TokenKind::Unknown,
+  })
+}
+
+// Lexes input that might not be valid UTF-8, such as text received over the network. Invalid input
+// is reported at its first invalid byte instead of being lexed.
+pub fn lex_bytes(s: &[u8]) -> Result<Vec<Token<'_>>, Error<'_>> {
+  match core::str::from_utf8(s) {
+    Ok(s) => lex(s),
+    Err(e) => {
+      // The prefix was just validated.
+      let valid = core::str::from_utf8(&s[..e.valid_up_to()]).unwrap_or_default();
+      Err(match invalid_utf8_token(valid) {
+        Some(token) => Error {
+          token,
+          kind: ErrorKind::InvalidUtf8,
+          limit: None,
+        },
+        None => Error::input_too_long(valid),
+      })
+    }
+  }
+}
+
+// Lexed tokens cover their input without gaps, so joining their text gives back the input
+// exactly.
+pub fn tokens_to_source(tokens: &[Token]) -> String {
+  tokens.iter().map(Token::text).collect()
+}
+
+pub fn lex_with<'a>(s: &'a str, options: &LexerOptions) -> Result<Vec<Token<'a>>, Error<'a>> {
+  Lexer::with_options(s, options.clone()).collect()
+}
+
+// Like lex, but lexes the whole input and returns every unknown token instead of only the first.
+pub fn lex_all_errors(s: &str) -> Result<Vec<Token<'_>>, Vec<Error<'_>>> {
+  let (tokens, errors): (Vec<_>, Vec<_>) = Lexer::new(s).partition(Result::is_ok);
+  match errors.is_empty() {
+    true => Ok(tokens.into_iter().map(Result::unwrap).collect()),
+    false => Err(errors.into_iter().map(Result::unwrap_err).collect()),
+  }
+}
+
+// Never fails: unknown tokens are kept in the tokens where they are, and an error for each of them
+// is returned alongside, so the whole input can be shown even when it doesn't lex.
+pub fn lex_recover(s: &str) -> (Vec<Token<'_>>, Vec<Error<'_>>) {
+  let mut errors = Vec::new();
+  let tokens = Lexer::new(s)
+    .map(|res| {
+      res.unwrap_or_else(|e| {
+        errors.push(e.clone());
+        e.token
+      })
+    })
+    .collect();
+  (tokens, errors)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Completeness {
+  // Nothing but whitespace and comments.
+  Empty,
+  // The last statement isn't terminated yet.
+  Incomplete,
+  // Every statement is terminated.
+  Complete,
+  // A quote isn't closed on its line. Quoted names can't span lines, so the input is invalid however
+  // it continues.
+  UnclosedQuote,
+}
+
+// Tells whether the input ends with a terminated statement, ignoring the whitespace and comments
+// after it, without parsing it. Terminators inside quoted names don't count.
+pub fn is_statement_complete(s: &str) -> Completeness {
+  let mut completeness = Completeness::Empty;
+  for (_, token) in segments(s) {
+    completeness = match token.value() {
+      Unknown(u) if u.starts_with('"') => return Completeness::UnclosedQuote,
+      value if is_trivia(value) => completeness,
+      value if is_terminator(value) => Completeness::Complete,
+      _ => Completeness::Incomplete,
+    };
+  }
+  completeness
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn lex_empty() {
+    let source = "";
+    let tokens = lex(source).expect("Lex must succeed");
+    assert!(tokens.is_empty());
+  }
+
+  #[test]
+  fn new_token() {
+    let line = "Add Ana to HR.";
+    let token = Token::new(Word("HR"), line, 11).unwrap();
+    assert_eq!((token.value(), token.offset()), (Word("HR"), 11));
+    assert_eq!(token.column_number(), 12);
+    let token = Token::new(Word(&line[4..7]), line, 4).unwrap();
+    assert_eq!(token.offset(), 4);
+    let token = Token::new(Quoted("HR"), "Show \"HR\".", 5).unwrap();
+    assert_eq!(token.text(), "\"HR\"");
+    assert_eq!(Token::new(Word("HR"), line, 4), None);
+    assert_eq!(Token::new(Word("Hello"), "Sales", 0), None);
+    assert_eq!(Token::new(Word("Hello"), "", 0), None);
+    assert_eq!(Token::new(Quoted("HR"), "HR", 0), None);
+    assert_eq!(Token::new(Whitespace, line, 0), None);
+  }
+
+  #[test]
+  fn positions_must_fit() {
+    let line = "Add Ana to HR.";
+    assert_eq!(Token::new(Word("HR"), line, usize::MAX), None);
+    assert!(Error::too_long(line).is_none());
+  }
+
+  #[test]
+  fn lex_whitespace() {
+    let source = " \n\r\n\t";
+    let got = lex(source).expect("Lex must succeed");
+    let expect = vec![
+      Token::new(Whitespace, source, 0).unwrap(),
+      Token::new(Whitespace, source, 1).unwrap(),
+      Token::new(Whitespace, source, 2).unwrap(),
+      Token::new(Whitespace, source, 4).unwrap(),
+    ];
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  #[cfg(feature = "unicode")]
+  fn lex_words_and_whitespace() {
+    let source = "Create\n孫德明";
+    let expect = vec![
+      Token::new(Keyword("Create"), source, 0).unwrap(),
+      Token::new(Whitespace, source, 6).unwrap(),
+      Token::new(Word("孫"), source, 7).unwrap(),
+      Token::new(Word("德"), source, 10).unwrap(),
+      Token::new(Word("明"), source, 13).unwrap(),
+    ];
+    let got = lex(source).expect("Lex must succeed");
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn lex_punctuation() {
+    let source = ".,!?";
+    let expect = vec![
+      Token::new(Punctuation("."), source, 0).unwrap(),
+      Token::new(Punctuation(","), source, 1).unwrap(),
+      Token::new(Punctuation("!"), source, 2).unwrap(),
+      Token::new(Punctuation("?"), source, 3).unwrap(),
+    ];
+    let got = lex(source).expect("Lex must succeed");
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn lex_unknown() {
+    // All Unicode Character categories that shouldn't be supported by the lexer at all or not on their own (in the case of M categories).
+    // The only exceptions are ' ', '.', ',', '!', '?', their support being tested above.
+    let chars = vec![
+      "\u{0000}", // Cc Control
+      "\u{00AD}", // Cf Format
+      "\u{0903}", // Mc Spacing Mark
+      "\u{0488}", // Me Enclosing Mark
+      "\u{0300}", // Mn Nonspacing Mark
+      "\u{0660}", // Nd Decimal Number, only ASCII digits are supported
+      "\u{16EE}", // Nl Letter Number
+      "\u{00B2}", // No Other Number
+      "\u{005F}", // Pc Connector Punctuation
+      "-",        // Pd Dash Punctuation
+      ")",        // Pe Close Punctuation
+      "\u{00BB}", // Pf Final Punctuation
+      "\u{00AB}", // Pi Initial Punctuation
+      "\"",       // Po Other Punctuation
+      "$",        // Sc Currency Symbol
+      "^",        // Sk Modifier Symbol
+      "+",        // Sm Math Symbol
+      "\u{00A6}", // So Other Symbol
+    ];
+    chars.into_iter().map(lex).for_each(|r| {
+      r.unwrap_err();
+    });
+  }
+
+  #[test]
+  #[cfg(feature = "unicode")]
+  fn error_format() {
+    let source = "孫德 12345th";
+    let expect = r#"Error E0001 on line 1, column 4: unknown token "12345th"
+  孫德 12345th
+       ^^^^^^^"#;
+    let got = format!("{}", lex(source).expect_err("Lex must fail"));
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn token_size() {
+    // A span of the source, down from the 56 bytes of a token holding its line and position. Half
+    // of that would take a thinner reference to the source than a &str.
+    assert!(core::mem::size_of::<Token>() <= 32);
+  }
+
+  #[test]
+  fn token_position() {
+    let tokens = lex("Create HR.\n  Add Ștefan E\u{301}mile to HR.").unwrap();
+    let to = tokens.iter().find(|t| t.value() == Word("to")).unwrap();
+    let expect = Position {
+      line: 2,
+      column: 20,
+      offset: 22,
+      utf16_column: 20,
+    };
+    assert_eq!(to.position(), expect);
+  }
+
+  #[test]
+  #[cfg(feature = "unicode")]
+  fn token_value_from_span() {
+    let tokens = lex("Add Ana+Maria").unwrap_err();
+    assert_eq!(tokens.token().value(), Unknown("+"));
+    let tokens = lex("Add  孫德明").unwrap();
+    assert_eq!(tokens[1].value(), Whitespace);
+    assert_eq!(tokens[3].value(), Word("德"));
+    assert_eq!(tokens[3].line(), "Add  孫德明");
+  }
+
+  #[test]
+  fn error_format_huge_token() {
+    let source = format!("Add {} to HR.", "a1".repeat(1 << 19));
+    let got = format!("{}", lex(&source).expect_err("Lex must fail"));
+    assert!(got.len() < 1024, "{}", got.len());
+    assert!(got.ends_with(&format!("{}... (1048576 characters)", "^".repeat(64))));
+  }
+
+  #[test]
+  fn lex_enumerators() {
+    let source = "1. Create HR.\n  12) Add Mihai to HR.\n- Show HR.\n* Show PR.\n-Show HR.";
+    let got = lex(source).unwrap_err();
+    assert_eq!(got.token().line_number(), 5);
+    let got: Vec<_> = lex(&source[..source.len() - 10])
+      .unwrap()
+      .into_iter()
+      .map(|t| t.value())
+      .filter(|v| *v != Whitespace)
+      .collect();
+    assert_eq!(got[0], Keyword("Create"));
+    assert_eq!(got[3], Keyword("Add"));
+    assert_eq!(got[8], Keyword("Show"));
+    assert_eq!(got[11], Keyword("Show"));
+    assert_eq!(lex("Add 1. to HR.").unwrap()[2].value(), Number("1"));
+    let got = lex("Add Mihai to\n2. HR.\n3. Show HR.").unwrap();
+    assert_eq!(got[6].value(), Number("2"));
+    assert_eq!(got[13].value(), Whitespace);
+    assert_eq!(got[15].value(), Keyword("Show"));
+  }
+
+  #[test]
+  fn lex_quoted() {
+    let source = "Add \"Mary and Sons\", \"Jean-Luc\" to Sales.";
+    let got = lex(source).unwrap();
+    assert_eq!(
+      got[2],
+      Token::new(Quoted("Mary and Sons"), source, 4).unwrap()
+    );
+    assert_eq!(got[2].offset(), 4);
+    assert_eq!(got[5].value(), Quoted("Jean-Luc"));
+    assert_eq!(got[6].column_number(), 32);
+    assert_eq!(
+      lex("Add \"Mary to HR.").unwrap_err().token().value(),
+      Unknown("\"")
+    );
+    assert_eq!(
+      lex("Add \"\" to HR.").unwrap_err().token().column_number(),
+      5
+    );
+    assert!(lex("Add \"Mary\n\" to HR.").is_err());
+  }
+
+  #[test]
+  fn lex_numbers() {
+    let source = "Create Team 42, Floor 3.";
+    let got: Vec<_> = lex(source).unwrap().iter().map(|t| t.value()).collect();
+    assert_eq!(got[4], Number("42"));
+    assert_eq!(got[9], Number("3"));
+    assert_eq!(got[10], Punctuation("."));
+    assert!(lex("Create Team42.").is_err());
+    let got = lex("Add Ana to HR starting 2024-03-15.").unwrap();
+    assert_eq!(got[10].value(), Number("2024-03-15"));
+    assert!(lex("Create Floor 3-.").is_err());
+    assert!(lex("Create Floor 3-B.").is_err());
+  }
+
+  #[test]
+  fn lex_comments() {
+    let source = "# onboarding\r\nCreate HR. -- \"March\" batch\nShow HR#!";
+    let got: Vec<_> = lex(source).unwrap().iter().map(|t| t.value()).collect();
+    assert_eq!(got[0], Comment("# onboarding"));
+    assert_eq!(got[1], Whitespace);
+    assert_eq!(got[7], Comment("-- \"March\" batch"));
+    assert_eq!(got[got.len() - 1], Comment("#!"));
+    assert!(lex("- Show HR.").is_ok());
+  }
+
+  #[test]
+  fn lex_joined_words() {
+    let source = "Add O'Brien, Anne-Marie and D\u{2019}Artagnan-Smith to HR.";
+    let got: Vec<_> = lex(source).unwrap().iter().map(|t| t.value()).collect();
+    assert_eq!(got[2], Word("O'Brien"));
+    assert_eq!(got[5], Word("Anne-Marie"));
+    assert_eq!(got[9], Word("D\u{2019}Artagnan-Smith"));
+    assert!(lex("Add Ana- to HR.").is_err());
+    assert!(lex("Add 'Ana to HR.").is_err());
+    assert!(lex("Add Ana -Maria to HR.").is_err());
+  }
+
+  #[test]
+  fn lex_keywords() {
+    let source = "Add \"Show\" to Show Business. Remove Addison.";
+    let got: Vec<_> = lex(source)
+      .unwrap()
+      .into_iter()
+      .map(|t| t.value())
+      .filter(|v| *v != Whitespace)
+      .collect();
+    assert_eq!(got[0], Keyword("Add"));
+    assert_eq!(got[1], Quoted("Show"));
+    assert_eq!(got[3], Keyword("Show"));
+    assert_eq!(got[7], Word("Addison"));
+    let operations = crate::parse(lex(source).unwrap()).unwrap();
+    assert_eq!(*operations[0].names(), ["Show"]);
+    assert_eq!(operations[0].departments(), ["Show Business"]);
+  }
+
+  #[test]
+  fn whitespace_kinds() {
+    let source = "Create HR.\r\n\n\tAdd Ana to HR.\u{3000}Show HR.";
+    let options = LexerOptions::new().set_coalesce_whitespace(true);
+    let got: Vec<_> = lex_with(source, &options)
+      .unwrap()
+      .iter()
+      .filter_map(|t| t.whitespace_kind())
+      .collect();
+    use WhitespaceKind::*;
+    assert_eq!(
+      got,
+      [Space, Newline, Newline, Tab, Space, Space, Space, Space, Space]
+    );
+    assert_eq!(lex("Show").unwrap()[0].whitespace_kind(), None);
+  }
+
+  #[test]
+  fn display_token() {
+    let tokens = lex("Create HR.\n  Add \"Ana\" to   Sales.").unwrap();
+    let shown: Vec<_> = tokens.iter().map(|t| t.to_string()).collect();
+    assert_eq!(shown[0], "keyword \"Create\" at 1:1");
+    assert_eq!(shown[1], "whitespace at 1:7");
+    assert_eq!(shown[8], "quoted \"Ana\" at 2:7");
+    assert_eq!(shown[12], "word \"Sales\" at 2:18");
+  }
+
+  #[test]
+  fn lex_iter_matches_lexer() {
+    let source = "Create HR.\n  Add Ana, \"Ana \\M\" to $ HR.";
+    let got: Vec<_> = lex_iter(source).collect();
+    assert_eq!(got, Lexer::new(source).collect::<Vec<_>>());
+    assert_eq!(got.iter().filter(|t| t.is_err()).count(), 2);
+  }
+
+  #[test]
+  fn lexer_iter() {
+    let mut lexer = Lexer::new("Show $ HR.");
+    assert_eq!(
+      lexer.next().unwrap(),
+      Ok(Token::new(Keyword("Show"), "Show $ HR.", 0).unwrap())
+    );
+    assert!(lexer.nth(1).unwrap().is_err());
+    let rest: Vec<_> = lexer.map(|t| t.unwrap().value()).collect();
+    assert_eq!(rest, vec![Whitespace, Word("HR"), Punctuation(".")]);
+  }
+
+  #[test]
+  fn lex_every_error() {
+    let source = "Add Mihai & Ana to HR.\nShow $.";
+    let got = lex_all_errors(source).unwrap_err();
+    let positions: Vec<_> = got
+      .iter()
+      .map(|e| (e.token().line_number(), e.token().column_number()))
+      .collect();
+    assert_eq!(positions, vec![(1, 11), (2, 6)]);
+    assert_eq!(lex_all_errors("Show HR."), Ok(lex("Show HR.").unwrap()));
+  }
+
+  #[test]
+  fn lex_recovering() {
+    let source = "Add Mihai & Ana to HR.\nShow $.";
+    let (tokens, errors) = lex_recover(source);
+    assert_eq!(tokens_to_source(&tokens), source);
+    assert_eq!(tokens[4].value(), Unknown("&"));
+    assert_eq!(errors, lex_all_errors(source).unwrap_err());
+    assert_eq!(lex_recover("Show HR."), (lex("Show HR.").unwrap(), vec![]));
+  }
+
+  #[test]
+  fn statement_completeness() {
+    let cases = [
+      ("", Completeness::Empty),
+      ("  # Create HR.\n", Completeness::Empty),
+      ("Create HR", Completeness::Incomplete),
+      ("Create HR.\nAdd Ana to", Completeness::Incomplete),
+      ("1. ", Completeness::Empty),
+      ("Create HR!  -- done\n\n", Completeness::Complete),
+      ("Add \"Ana M.\" to HR.", Completeness::Complete),
+      ("Add \"Ana M.\"", Completeness::Incomplete),
+      ("Add \"Ana M. to HR.", Completeness::UnclosedQuote),
+    ];
+    for (source, expect) in cases {
+      assert_eq!(is_statement_complete(source), expect, "{:?}", source);
+    }
+  }
+
+  #[test]
+  fn lex_invalid_utf8() {
+    assert_eq!(lex_bytes(b"Show HR."), Ok(lex("Show HR.").unwrap()));
+    let err = lex_bytes(b"Create HR.\nShow H\xffR.").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidUtf8);
+    assert_eq!(
+      err.to_string(),
+      "Error E0017 on line 2, column 7: invalid UTF-8\n  Show H\n        "
+    );
+  }
+
+  #[test]
+  fn lex_escapes() {
+    let source = r#"Add "Ana \"Mimi\" \\ B\nC" and "\\" to HR."#;
+    let got = lex(source).unwrap();
+    assert_eq!(got[2].value(), Quoted(r#"Ana \"Mimi\" \\ B\nC"#));
+    assert_eq!(got[6].value(), Quoted(r"\\"));
+    assert_eq!(unescape(r#"Ana \"Mimi\" \\ B\nC"#), "Ana \"Mimi\" \\ B\nC");
+    let operations = crate::parse(got).unwrap();
+    assert_eq!(
+      *operations[0].names(),
+      ["Ana \"Mimi\" \\ B\nC".to_string(), "\\".to_string()]
+    );
+    assert_eq!(operations[0].to_source(), source);
+
+    let err = lex("Create HR.\nAdd \"Ștefan \\M\" to HR.").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidEscape);
+    assert_eq!(err.token().text(), r"\M");
+    assert_eq!(err.token().line_number(), 2);
+    assert_eq!(err.token().column_number(), 13);
+  }
+
+  #[test]
+  fn lossless() {
+    let source = "1. Create HR.\r\n\t\"Add\"  O'Brien,\u{3000}Ana -- note\n\n  to HR!  ";
+    let tokens = lex(source).unwrap();
+    assert_eq!(tokens_to_source(&tokens), source);
+    assert_eq!(tokens[1].text(), ".");
+    assert_eq!(tokens[1].value(), Whitespace);
+  }
+
+  #[test]
+  fn names() {
+    let tokens = lex("Add \"Ana \\\"Mimi\\\"\" to Vânzări.").unwrap();
+    assert_eq!(tokens[2].name(), "Ana \"Mimi\"");
+    assert!(matches!(tokens[6].name(), Cow::Borrowed("Vânzări")));
+    #[cfg(feature = "nfc")]
+    assert_eq!(lex("Vie\u{302}\u{323}t").unwrap()[0].name(), "Việt");
+  }
+
+  // TODO: More tests (formatting of tokens and error messages)
+}
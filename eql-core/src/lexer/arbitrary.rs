@@ -0,0 +1,40 @@
+// Arbitrary token values borrow their text from the fuzzer input. Words are often grammar words, so
+// that fuzzers reach the parts of consumers that handle them without having to guess their spelling.
+
+use super::TokenValue;
+use crate::parser::constants::GRAMMAR_WORDS;
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+impl<'a> Arbitrary<'a> for TokenValue<'a> {
+  fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+    Ok(match u.int_in_range(0..=8)? {
+      0 => TokenValue::Whitespace,
+      1 => *u.choose(&GRAMMAR_WORDS)?,
+      2 => TokenValue::Word(u.arbitrary()?),
+      3 => TokenValue::Keyword(u.arbitrary()?),
+      4 => TokenValue::Punctuation(u.arbitrary()?),
+      5 => TokenValue::Number(u.arbitrary()?),
+      6 => TokenValue::Quoted(u.arbitrary()?),
+      7 => TokenValue::Comment(u.arbitrary()?),
+      _ => TokenValue::Unknown(u.arbitrary()?),
+    })
+  }
+
+  fn size_hint(depth: usize) -> (usize, Option<usize>) {
+    arbitrary::size_hint::and(u8::size_hint(depth), (0, None))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn token_values() {
+    let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+    let mut u = Unstructured::new(&data);
+    let values: Vec<TokenValue> = (0..64).map(|_| u.arbitrary().unwrap()).collect();
+    assert!(values.iter().any(|v| GRAMMAR_WORDS.contains(v)));
+    assert!(values.iter().any(|v| matches!(v, TokenValue::Quoted(_))));
+  }
+}
@@ -0,0 +1,185 @@
+use super::{check, lex, segments_from, starts_statement, Error, Token};
+use crate::parser::Edit;
+use alloc::vec::Vec;
+
+// Whether a statement starts after the tokens, as the lexer tracks it to strip enumerators.
+fn ends_statement(tokens: &[Token<'_>]) -> bool {
+  tokens
+    .iter()
+    .rev()
+    .find_map(starts_statement)
+    .unwrap_or(true)
+}
+
+// Lines are lexed independently of each other, except for whether they start a statement, so the
+// tokens of the lines the edit doesn't touch are reused: the ones before it as they are, and the
+// ones after it moved to their new position. Only the lines in between are lexed again, starting
+// in the statement the tokens before them end in. If the edit changes whether a statement starts
+// after them, the rest of the source is lexed again. The old tokens must be the result of lexing
+// the old source with lex, and the result is the same as lexing the new source with it.
+pub fn relex<'a>(
+  old: &[Token<'_>],
+  source: &'a str,
+  edit: &Edit,
+) -> Result<Vec<Token<'a>>, Error<'a>> {
+  if old.is_empty() {
+    return lex(source);
+  }
+  if let Some(e) = Error::too_long(source) {
+    return Err(e);
+  }
+  let line_start = |t: &Token| t.source_offset() - t.offset();
+  let line_end = |t: &Token| line_start(t) + t.line().len();
+
+  let before = old.iter().take_while(|t| line_end(t) < edit.start).count();
+  let mut after = old
+    .iter()
+    .position(|t| line_start(t) > edit.old_end)
+    .unwrap_or(old.len());
+  let shift = |i: usize| i + edit.new_end - edit.old_end;
+
+  let region_start = old[..before].last().map_or(0, line_end);
+  let region_end = old
+    .get(after)
+    .map_or(source.len(), |t| shift(line_start(t)));
+  let statement_start = ends_statement(&old[..before]);
+
+  // The tokens are spans of the source, so the ones before the edit are where they were, and the
+  // ones after it only move by as much as the edit changed the length of the source.
+  let mut tokens = Vec::with_capacity(old.len());
+  tokens.extend(old[..before].iter().map(|t| Token { source, ..*t }));
+  let mut region = segments_from(source, region_start..region_end, statement_start)
+    .map(|(_, t)| t)
+    .collect::<Vec<_>>();
+  if after < old.len() && ends_statement(&region) != ends_statement(&old[..after]) {
+    after = old.len();
+    region = segments_from(source, region_start..source.len(), statement_start)
+      .map(|(_, t)| t)
+      .collect();
+  }
+  for token in region {
+    tokens.push(check(token)?);
+  }
+  tokens.extend(old[after..].iter().map(|t| Token {
+    source,
+    // The source was checked to be no longer than MAX_LEN, so the start fits.
+    start: shift(t.source_offset()) as u32,
+    ..*t
+  }));
+
+  Ok(tokens)
+}
+
+// The tokens start..old_end of the old tokens were replaced with the tokens start..new_end of the
+// new ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TokenEdit {
+  pub start: usize,
+  pub old_end: usize,
+  pub new_end: usize,
+}
+
+// Finds the tokens that changed between two lexings of a source, as the smallest range left after
+// their common start and end. Tokens are compared by their kind and text, not their position, so
+// the tokens after an edit that moved them don't count as changed. Returns None if no token did.
+pub fn diff_tokens(old: &[Token<'_>], new: &[Token<'_>]) -> Option<TokenEdit> {
+  let same = |(a, b): &(&Token, &Token)| a.kind == b.kind && a.text() == b.text();
+  let start = old.iter().zip(new).take_while(same).count();
+  if start == old.len() && start == new.len() {
+    return None;
+  }
+  let end = old
+    .iter()
+    .rev()
+    .zip(new.iter().rev())
+    .take(old.len().min(new.len()) - start)
+    .take_while(same)
+    .count();
+  Some(TokenEdit {
+    start,
+    old_end: old.len() - end,
+    new_end: new.len() - end,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn relex_edits() {
+    let source = "Create HR.\nAdd Mihai to HR.\n\nRemove Ana from IT.\nCreate PR.";
+    let old = lex(source).unwrap();
+    let edits = [
+      (15, 20, "Ana, Maria"),
+      (11, 11, "Create IT.\nAdd Ana to IT.\n"),
+      (5, 30, ""),
+      (0, 0, "\n"),
+      (source.len(), source.len(), " Add Mihai to PR."),
+      (28, 29, "\n"),
+      (15, 20, "Ana,\n1. Maria"),
+      (25, 27, "HR\n2. PR"),
+    ];
+    for (start, end, text) in edits {
+      let edited = format!("{}{}{}", &source[..start], text, &source[end..]);
+      let edit = Edit {
+        start,
+        old_end: end,
+        new_end: start + text.len(),
+      };
+      assert_eq!(relex(&old, &edited, &edit), lex(&edited), "{:?}", edited);
+    }
+
+    let edited = "Create HR.\nAdd $ to HR.\n\nRemove Ana from IT.\nCreate PR.";
+    let edit = Edit {
+      start: 15,
+      old_end: 20,
+      new_end: 16,
+    };
+    assert_eq!(relex(&old, edited, &edit), lex(edited));
+
+    // Whether the line after the edit starts a statement depends on the lines before it.
+    let source = "Add Mihai to\n2. HR.\nShow HR.";
+    let old = lex(source).unwrap();
+    let edit = Edit {
+      start: 14,
+      old_end: 14,
+      new_end: 14,
+    };
+    assert_eq!(relex(&old, source, &edit), lex(source));
+    let edited = "Add Mihai to HR.\n2. HR.\nShow HR.";
+    let edit = Edit {
+      start: 12,
+      old_end: 12,
+      new_end: 16,
+    };
+    assert_eq!(relex(&old, edited, &edit), lex(edited));
+  }
+
+  #[test]
+  fn diff() {
+    fn texts<'a>(tokens: &[Token<'a>]) -> Vec<&'a str> {
+      tokens.iter().map(|t| t.text()).collect()
+    }
+    let old = lex("Create HR.\nAdd Ana to HR.").unwrap();
+    let new = lex("Create HR.\nAdd Ana, Mihai to HR.").unwrap();
+    let edit = diff_tokens(&old, &new).unwrap();
+    assert_eq!(edit.start, edit.old_end);
+    assert_eq!(texts(&new[edit.start..edit.new_end]), [",", " ", "Mihai"]);
+
+    // Tokens that moved to another line are still the same.
+    let moved = lex("\nCreate HR.\nAdd Ana to HR.").unwrap();
+    let edit = diff_tokens(&old, &moved).unwrap();
+    assert_eq!((edit.start, edit.old_end, edit.new_end), (0, 0, 1));
+
+    assert_eq!(diff_tokens(&old, &old), None);
+    assert_eq!(
+      diff_tokens(&old, &old[..3]),
+      Some(TokenEdit {
+        start: 3,
+        old_end: old.len(),
+        new_end: 3
+      })
+    );
+  }
+}
@@ -0,0 +1,285 @@
+use super::{Token, TokenKind};
+use crate::parser::Grammar;
+use crate::text::{self, UnicodeSegmentation};
+use alloc::borrow::Cow;
+
+const PUNCTUATION: [char; 4] = [',', '.', '!', '?'];
+
+// Every grapheme starts with a symbol, which emoji modifiers and joiners then follow.
+fn is_symbolic(s: &str) -> bool {
+  s.graphemes(true)
+    .all(|g| g.chars().next().is_some_and(text::is_symbol))
+}
+
+// Tunes which tokens the lexer accepts. The parser only knows the default punctuation, so other
+// sets are for embedders that consume the tokens themselves.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LexerOptions {
+  punctuation: Cow<'static, [char]>,
+  digits: bool,
+  case_insensitive: bool,
+  coalesce_whitespace: bool,
+  join_words: bool,
+  symbols: bool,
+  max_len: Option<usize>,
+  max_tokens: Option<usize>,
+  max_line_len: Option<usize>,
+  grammar: Grammar,
+}
+
+impl Default for LexerOptions {
+  fn default() -> Self {
+    LexerOptions {
+      punctuation: Cow::Borrowed(&PUNCTUATION),
+      digits: true,
+      case_insensitive: false,
+      coalesce_whitespace: false,
+      join_words: false,
+      symbols: false,
+      max_len: None,
+      max_tokens: None,
+      max_line_len: None,
+      grammar: Grammar::default(),
+    }
+  }
+}
+
+impl LexerOptions {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn get_punctuation(&self) -> &[char] {
+    &self.punctuation
+  }
+
+  pub fn set_punctuation(self, punctuation: impl Into<Cow<'static, [char]>>) -> Self {
+    LexerOptions {
+      punctuation: punctuation.into(),
+      ..self
+    }
+  }
+
+  pub fn get_digits(&self) -> bool {
+    self.digits
+  }
+
+  pub fn set_digits(self, digits: bool) -> Self {
+    LexerOptions { digits, ..self }
+  }
+
+  pub fn get_case_insensitive(&self) -> bool {
+    self.case_insensitive
+  }
+
+  pub fn set_case_insensitive(self, case_insensitive: bool) -> Self {
+    LexerOptions {
+      case_insensitive,
+      ..self
+    }
+  }
+
+  // Consecutive whitespace on a line is lexed as a single token, which spares the parser from
+  // skipping every space of indented and aligned scripts.
+  pub fn get_coalesce_whitespace(&self) -> bool {
+    self.coalesce_whitespace
+  }
+
+  pub fn set_coalesce_whitespace(self, coalesce_whitespace: bool) -> Self {
+    LexerOptions {
+      coalesce_whitespace,
+      ..self
+    }
+  }
+
+  // Words that aren't separated by anything are lexed as a single word. Word boundaries fall between
+  // every character of scripts written without spaces, like Chinese and Japanese, so this keeps
+  // names in them whole.
+  pub fn get_join_words(&self) -> bool {
+    self.join_words
+  }
+
+  pub fn set_join_words(self, join_words: bool) -> Self {
+    LexerOptions { join_words, ..self }
+  }
+
+  // Symbols like emoji are lexed as words instead of unknown tokens, so names can include them.
+  // Builds without the "unicode" feature don't know which characters are symbols.
+  pub fn get_symbols(&self) -> bool {
+    self.symbols
+  }
+
+  pub fn set_symbols(self, symbols: bool) -> Self {
+    LexerOptions { symbols, ..self }
+  }
+
+  // The maximum length of the input in bytes.
+  pub fn get_max_len(&self) -> Option<usize> {
+    self.max_len
+  }
+
+  pub fn set_max_len(self, max_len: Option<usize>) -> Self {
+    LexerOptions { max_len, ..self }
+  }
+
+  // The maximum number of tokens, whitespace included.
+  pub fn get_max_tokens(&self) -> Option<usize> {
+    self.max_tokens
+  }
+
+  pub fn set_max_tokens(self, max_tokens: Option<usize>) -> Self {
+    LexerOptions { max_tokens, ..self }
+  }
+
+  // The maximum length of a line in bytes, without its line break.
+  pub fn get_max_line_len(&self) -> Option<usize> {
+    self.max_line_len
+  }
+
+  pub fn set_max_line_len(self, max_line_len: Option<usize>) -> Self {
+    LexerOptions {
+      max_line_len,
+      ..self
+    }
+  }
+
+  // Tokens spelled as the grammar spells a word or punctuation stand for it, so punctuation the
+  // default rules don't know lexes too.
+  pub fn get_grammar(&self) -> Grammar {
+    self.grammar
+  }
+
+  pub fn set_grammar(self, grammar: Grammar) -> Self {
+    LexerOptions { grammar, ..self }
+  }
+
+  // Lexing always uses the default rules, which the options then adjust token by token.
+  pub(crate) fn apply<'a>(&self, mut token: Token<'a>) -> Token<'a> {
+    let mut chars = token.text().chars();
+    let single = chars.next().filter(|_| chars.next().is_none());
+    token.kind = match (token.kind, single) {
+      (TokenKind::Punctuation | TokenKind::Unknown, Some(c)) if self.punctuation.contains(&c) => {
+        TokenKind::Punctuation
+      }
+      (TokenKind::Punctuation, _) => TokenKind::Unknown,
+      (TokenKind::Number, _) if !self.digits => TokenKind::Unknown,
+      (TokenKind::Unknown, _) if self.symbols && is_symbolic(token.text()) => TokenKind::Word,
+      (kind, _) => kind,
+    };
+    let token = token.respell(&self.grammar);
+    match self.case_insensitive {
+      true => token.ignore_case(),
+      false => token,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::ErrorKind;
+  #[cfg(feature = "unicode")]
+  use crate::lexer::Word;
+  use crate::lexer::{lex, lex_with, tokens_to_source, Keyword, Punctuation};
+
+  #[test]
+  fn apply_options() {
+    let source = "add Ana; Team 42.";
+    assert!(lex(source).is_err());
+    let options = LexerOptions::new()
+      .set_punctuation(&[';', '.'][..])
+      .set_case_insensitive(true);
+    let got = lex_with(source, &options).unwrap();
+    assert_eq!(got[0].value(), Keyword("Add"));
+    assert_eq!(got[0].written_value(), Keyword("add"));
+    assert_eq!(got[3].value(), Punctuation(";"));
+    let options = options.set_digits(false);
+    assert_eq!(
+      lex_with(source, &options)
+        .unwrap_err()
+        .token()
+        .column_number(),
+      15
+    );
+    let options = LexerOptions::new().set_punctuation(vec!['.']);
+    assert!(lex_with("Add Ana, Ioan.", &options).is_err());
+  }
+
+  #[test]
+  fn coalesce_whitespace() {
+    let source = "1.  Create \t HR,\n   PR.  \r\n";
+    let options = LexerOptions::new().set_coalesce_whitespace(true);
+    let got = lex_with(source, &options).unwrap();
+    let texts: Vec<_> = got.iter().map(|t| t.text()).collect();
+    assert_eq!(
+      texts,
+      vec!["1.  ", "Create", " \t ", "HR", ",", "\n", "   ", "PR", ".", "  \r\n"]
+    );
+    assert_eq!(tokens_to_source(&got), source);
+    let options = options.set_max_len(Some(6));
+    assert!(lex_with("Show   ", &options).is_err());
+  }
+
+  #[cfg(feature = "unicode")]
+  #[test]
+  fn join_words() {
+    let source = "Add 孫德明, 山田 太郎 to HR.";
+    let options = LexerOptions::new().set_join_words(true);
+    let got = lex_with(source, &options).unwrap();
+    assert_eq!(got[2].value(), Word("孫德明"));
+    assert_eq!(got[5].value(), Word("山田"));
+    assert_eq!(got[6].text(), " ");
+    assert_eq!(tokens_to_source(&got), source);
+    let ops = crate::parser::parse(got).unwrap();
+    assert_eq!(*ops[0].names(), ["孫德明", "山田 太郎"]);
+  }
+
+  #[cfg(feature = "unicode")]
+  #[test]
+  fn symbols() {
+    let source = "Add Ana to 🚀 Launch Team, 👩🏽‍💻.";
+    assert!(lex(source).is_err());
+    let options = LexerOptions::new().set_symbols(true);
+    let got = lex_with(source, &options).unwrap();
+    assert_eq!(got[6].value(), Word("🚀"));
+    let ops = crate::parser::parse(got).unwrap();
+    assert_eq!(ops[0].departments(), ["🚀 Launch Team", "👩🏽‍💻"]);
+    assert!(lex_with("Add Ana to $.", &options).is_err());
+  }
+
+  #[test]
+  fn max_len() {
+    let options = LexerOptions::new().set_max_len(Some(8));
+    assert!(lex_with("Show HR.", &options).is_ok());
+    let err = lex_with("Create HR.", &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InputTooLong);
+    assert_eq!(err.get_limit(), Some(8));
+    assert_eq!(err.token().column_number(), 8);
+    assert!(err
+      .to_string()
+      .starts_with("Error E0016 on line 1, column 8: the input is longer than 8 bytes"));
+  }
+
+  #[test]
+  fn max_tokens_and_line_len() {
+    let options = LexerOptions::new().set_max_tokens(Some(4));
+    assert!(lex_with("Show HR.", &options).is_ok());
+    let err = lex_with("Show HR, IT.", &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::TooManyTokens);
+    assert_eq!(err.get_limit(), Some(4));
+    assert_eq!(err.token().column_number(), 9);
+    assert!(err
+      .to_string()
+      .starts_with("Error E0019 on line 1, column 9: the input has more than 4 tokens"));
+
+    let options = LexerOptions::new().set_max_line_len(Some(10));
+    assert!(lex_with("Create HR.\nShow HR.\n", &options).is_ok());
+    let err = lex_with("Show HR.\nRemove Mihai from HR.", &options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LineTooLong);
+    assert_eq!(err.token().line_number(), 2);
+    assert_eq!(err.token().text(), "Mihai");
+    assert!(err
+      .to_string()
+      .starts_with("Error E0020 on line 2, column 8: the line is longer than 10 bytes"));
+  }
+}
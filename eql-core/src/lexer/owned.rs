@@ -0,0 +1,127 @@
+use super::{Error, Position, Token, TokenKind, TokenValue};
+use crate::error::ErrorKind;
+use alloc::rc::Rc;
+use core::convert::TryFrom;
+use core::fmt;
+
+// A token that owns the line it was lexed from. The tokens of a line share it, so only the lines
+// that still have tokens in use are kept in memory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OwnedToken {
+  line: Rc<str>,
+  // The span of the token in its line.
+  start: u32,
+  len: u32,
+  line_number: u32,
+  kind: TokenKind,
+}
+
+impl OwnedToken {
+  // Shares the line with the other tokens lexed from it. The offset of the token in its line is no
+  // larger than its offset in its source, which fits in 32 bits.
+  pub(super) fn new(line: Rc<str>, token: Token, line_number: u32) -> Self {
+    OwnedToken {
+      line,
+      start: token.offset() as u32,
+      len: token.len,
+      line_number,
+      kind: token.kind,
+    }
+  }
+
+  // The token is a span of its line, which is preceded by the lines before it.
+  pub fn as_token(&self) -> Token<'_> {
+    Token {
+      source: &self.line,
+      start: self.start,
+      len: self.len,
+      lines_before: self.line_number.saturating_sub(1),
+      kind: self.kind,
+    }
+  }
+
+  pub fn value(&self) -> TokenValue<'_> {
+    self.as_token().value()
+  }
+
+  pub fn text(&self) -> &str {
+    self.as_token().text()
+  }
+
+  pub fn line(&self) -> &str {
+    &self.line
+  }
+
+  pub fn offset(&self) -> usize {
+    self.start as usize
+  }
+
+  pub fn line_number(&self) -> usize {
+    self.line_number as usize
+  }
+
+  pub fn column_number(&self) -> usize {
+    self.as_token().column_number()
+  }
+
+  pub fn position(&self) -> Position {
+    self.as_token().position()
+  }
+}
+
+impl From<Token<'_>> for OwnedToken {
+  fn from(token: Token<'_>) -> Self {
+    let line_number = u32::try_from(token.line_number()).unwrap_or(u32::MAX);
+    OwnedToken::new(token.line().into(), token, line_number)
+  }
+}
+
+// An error that owns the line of its token, so it can be kept after the source is gone.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename = "Error"))]
+pub struct OwnedError {
+  kind: ErrorKind,
+  token: OwnedToken,
+  limit: Option<usize>,
+}
+
+impl OwnedError {
+  pub fn as_error(&self) -> Error<'_> {
+    Error {
+      token: self.token.as_token(),
+      kind: self.kind,
+      limit: self.limit,
+    }
+  }
+
+  pub fn token(&self) -> &OwnedToken {
+    &self.token
+  }
+
+  pub fn kind(&self) -> ErrorKind {
+    self.kind
+  }
+
+  pub fn get_limit(&self) -> Option<usize> {
+    self.limit
+  }
+}
+
+impl From<Error<'_>> for OwnedError {
+  fn from(e: Error<'_>) -> Self {
+    OwnedError {
+      kind: e.kind,
+      token: e.token.into(),
+      limit: e.limit,
+    }
+  }
+}
+
+impl fmt::Display for OwnedError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.as_error().fmt(f)
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OwnedError {}
@@ -0,0 +1,145 @@
+use super::{line_segments, starts_statement, Error, OwnedToken, TokenKind, MAX_LEN};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::io::{self, BufRead};
+use std::rc::Rc;
+use std::{error, fmt};
+
+#[derive(Debug)]
+pub enum ReadError {
+  Io(io::Error),
+  UnknownToken(OwnedToken),
+}
+
+impl fmt::Display for ReadError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ReadError::Io(e) => e.fmt(f),
+      ReadError::UnknownToken(token) => Error::unknown(token.as_token()).fmt(f),
+    }
+  }
+}
+
+impl error::Error for ReadError {}
+
+impl From<io::Error> for ReadError {
+  fn from(e: io::Error) -> Self {
+    ReadError::Io(e)
+  }
+}
+
+// Reads and lexes a line at a time. Like Lexer, it continues after unknown tokens, but it stops
+// after the first read error.
+pub struct ReaderLexer<R> {
+  reader: R,
+  line_number: usize,
+  statement_start: bool,
+  pending: VecDeque<OwnedToken>,
+  done: bool,
+}
+
+impl<R: BufRead> ReaderLexer<R> {
+  fn read_line(&mut self) -> io::Result<bool> {
+    let mut line = String::new();
+    if self.reader.read_line(&mut line)? == 0 {
+      return Ok(false);
+    }
+    self.line_number += 1;
+    // Positions are stored in 32 bits, like the lexer's.
+    let line_number = u32::try_from(self.line_number)
+      .ok()
+      .filter(|_| line.len() <= MAX_LEN)
+      .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "the input is too long to lex"))?;
+    let line: Rc<str> = line.into();
+    for (_, token) in line_segments(&line, &line, self.statement_start) {
+      if let Some(start) = starts_statement(&token) {
+        self.statement_start = start;
+      }
+      let token = OwnedToken::new(Rc::clone(&line), token, line_number);
+      self.pending.push_back(token);
+    }
+    Ok(true)
+  }
+}
+
+impl<R: BufRead> Iterator for ReaderLexer<R> {
+  type Item = Result<OwnedToken, ReadError>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    while self.pending.is_empty() && !self.done {
+      match self.read_line() {
+        Ok(read) => self.done = !read,
+        Err(e) => {
+          self.done = true;
+          return Some(Err(e.into()));
+        }
+      }
+    }
+    let token = self.pending.pop_front()?;
+    Some(match token.as_token().kind {
+      TokenKind::Unknown => Err(ReadError::UnknownToken(token)),
+      _ => Ok(token),
+    })
+  }
+}
+
+pub fn lex_reader<R: BufRead>(reader: R) -> ReaderLexer<R> {
+  ReaderLexer {
+    reader,
+    line_number: 0,
+    statement_start: true,
+    pending: VecDeque::new(),
+    done: false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer::{self, Keyword, OwnedError, Punctuation, Whitespace, Word};
+
+  #[test]
+  fn lex_lines() {
+    let source = "Create HR.\n  Add $ to HR.";
+    let got: Vec<_> = lex_reader(source.as_bytes()).collect();
+    let values: Vec<_> = got
+      .iter()
+      .filter_map(|t| t.as_ref().ok())
+      .map(|t| t.value())
+      .collect();
+    assert_eq!(
+      values[..5],
+      [
+        Keyword("Create"),
+        Whitespace,
+        Word("HR"),
+        Punctuation("."),
+        Whitespace
+      ]
+    );
+    let err = got.iter().find_map(|t| t.as_ref().err()).unwrap();
+    let expect = lexer::lex(source).unwrap_err();
+    assert_eq!(err.to_string(), expect.to_string());
+    let owned = OwnedError::from(expect.clone());
+    assert_eq!(owned.as_error(), expect);
+    assert_eq!(owned.to_string(), expect.to_string());
+    match err {
+      ReadError::UnknownToken(token) => assert_eq!(token.as_token(), expect.token()),
+      ReadError::Io(e) => panic!("{}", e),
+    }
+  }
+
+  #[test]
+  fn lex_enumerators() {
+    let source = "1. Add Mihai to\n2. HR.\n3. Show HR.";
+    let got: Vec<_> = lex_reader(source.as_bytes())
+      .map(|t| format!("{:?}", t.unwrap().value()))
+      .collect();
+    let expect: Vec<_> = lexer::lex(source)
+      .unwrap()
+      .iter()
+      .map(|t| format!("{:?}", t.value()))
+      .collect();
+    assert_eq!(got, expect);
+  }
+}
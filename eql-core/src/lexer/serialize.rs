@@ -0,0 +1,123 @@
+// Tokens are serialized with their value, their text as written and their position, and errors
+// with their message too, so consumers don't have to lex or format anything themselves. Tokens and
+// errors borrow the source, so they are deserialized as OwnedToken and OwnedError.
+
+use super::{Error, OwnedError, OwnedToken, Token, TokenKind, TokenValue};
+use crate::parser::constants::grammar_index;
+use alloc::string::{String, ToString};
+use core::convert::TryFrom;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+impl Serialize for Token<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("Token", 6)?;
+    s.serialize_field("value", &self.value())?;
+    s.serialize_field("text", self.text())?;
+    s.serialize_field("line", self.line())?;
+    s.serialize_field("offset", &self.offset())?;
+    s.serialize_field("line_number", &self.line_number())?;
+    s.serialize_field("column_number", &self.column_number())?;
+    s.end()
+  }
+}
+
+impl Serialize for OwnedToken {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.as_token().serialize(serializer)
+  }
+}
+
+// TokenValue with owned strings, as deserializing borrowed ones fails for escaped text. Only the
+// value of words and punctuation can differ from the text of the token, so the others are skipped.
+#[derive(serde::Deserialize)]
+#[serde(rename = "TokenValue")]
+enum Value {
+  Whitespace,
+  Word(String),
+  Keyword(String),
+  Punctuation(String),
+  Number(de::IgnoredAny),
+  Quoted(de::IgnoredAny),
+  Comment(de::IgnoredAny),
+  Unknown(de::IgnoredAny),
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename = "Token")]
+struct TokenData {
+  value: Value,
+  text: String,
+  line: String,
+  offset: usize,
+  line_number: usize,
+  column_number: usize,
+}
+
+impl<'de> Deserialize<'de> for OwnedToken {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let data = TokenData::deserialize(deserializer)?;
+    let end = data.offset.checked_add(data.text.len());
+    if end.and_then(|end| data.line.get(data.offset..end)) != Some(data.text.as_str()) {
+      return Err(de::Error::custom(
+        "the text of the token isn't at its offset in the line",
+      ));
+    }
+    // A token whose value differs from its text is a grammar token written in another casing or
+    // spelled as another grammar spells it.
+    let grammar = |value| {
+      grammar_index(value)
+        .map(TokenKind::Grammar)
+        .ok_or_else(|| de::Error::custom("the value of the token differs from its text"))
+    };
+    let kind = match data.value {
+      Value::Whitespace => TokenKind::Whitespace,
+      Value::Word(w) if w != data.text => grammar(TokenValue::Word(&w))?,
+      Value::Keyword(k) if k != data.text => grammar(TokenValue::Keyword(&k))?,
+      Value::Punctuation(p) if p != data.text => grammar(TokenValue::Punctuation(&p))?,
+      Value::Word(_) => TokenKind::Word,
+      Value::Keyword(_) => TokenKind::Keyword,
+      Value::Punctuation(_) => TokenKind::Punctuation,
+      Value::Number(_) => TokenKind::Number,
+      Value::Quoted(_) => TokenKind::Quoted,
+      Value::Comment(_) => TokenKind::Comment,
+      Value::Unknown(_) => TokenKind::Unknown,
+    };
+    let lines_before = data
+      .line_number
+      .checked_sub(1)
+      .and_then(|n| u32::try_from(n).ok())
+      .ok_or_else(|| de::Error::custom("the line number of the token is out of range"))?;
+    let token = Token {
+      source: &data.line,
+      start: data.offset as u32,
+      len: data.text.len() as u32,
+      lines_before,
+      kind,
+    };
+    // The column is found from the line, so it is only checked.
+    if token.column_number() != data.column_number {
+      return Err(de::Error::custom(
+        "the column number of the token doesn't match its offset in the line",
+      ));
+    }
+    Ok(token.into())
+  }
+}
+
+impl Serialize for Error<'_> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_struct("Error", 4)?;
+    s.serialize_field("kind", &self.kind)?;
+    s.serialize_field("token", &self.token)?;
+    s.serialize_field("limit", &self.limit)?;
+    s.serialize_field("message", &self.to_string())?;
+    s.end()
+  }
+}
+
+impl Serialize for OwnedError {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    self.as_error().serialize(serializer)
+  }
+}
@@ -0,0 +1,221 @@
+// Lexing and parsing are total: no input makes them panic, and the lexer and the parser forbid
+// unsafe code. This is checked by the no_panics test below and by the fuzz target in fuzz/.
+//
+// Without the "std" feature only the lexer, the parser and the message catalogs are built, with
+// no_std.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod anonymize;
+pub mod collation;
+#[cfg(feature = "std")]
+pub mod completion;
+#[cfg(feature = "std")]
+pub mod diff;
+mod error;
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "std")]
+pub mod fix;
+#[cfg(feature = "std")]
+pub mod generate;
+#[cfg(feature = "std")]
+pub mod handler;
+#[cfg(feature = "std")]
+pub mod html;
+pub mod lexer;
+#[cfg(feature = "std")]
+pub mod line_index;
+#[cfg(feature = "std")]
+pub mod lint;
+pub mod messages;
+pub mod operation;
+#[cfg(feature = "std")]
+pub mod optimize;
+pub mod parser;
+#[cfg(feature = "std")]
+pub mod session;
+#[cfg(feature = "std")]
+pub mod spelling;
+#[cfg(feature = "std")]
+pub mod stats;
+#[cfg(feature = "async")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod table;
+mod text;
+#[cfg(feature = "std")]
+pub mod tree;
+mod util;
+
+pub use error::{Error, ErrorKind, Severity};
+pub use lexer::lex;
+pub use operation::Operation;
+pub use parser::{parse, split_statements};
+#[cfg(feature = "std")]
+pub use session::ParseSession;
+
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicBool;
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::path::Path;
+
+#[cfg(feature = "std")]
+pub fn lex_parse<'a>(s: &'a str) -> Result<Vec<Operation>, Box<dyn std::error::Error + 'a>> {
+  let operations: Result<_, _> = parser::parse_iter(lexer::lex_iter(s)).collect();
+  Ok(operations?)
+}
+
+// Lexes and parses in a single pass, one statement at a time. The returned iterator can be
+// configured further, e.g. with parser options or a cancellation flag.
+pub fn lex_parse_iter(s: &str) -> parser::ParseIter<'_, lexer::Lexer<'_>> {
+  let options = lexer::LexerOptions::new().set_coalesce_whitespace(true);
+  parser::parse_iter(lexer::Lexer::with_options(s, options))
+}
+
+// Like lex_parse_iter(s).collect(), but gives up with Error::Cancelled as soon as the flag is set,
+// for example by another thread when a user aborts.
+#[allow(clippy::result_large_err)]
+pub fn lex_parse_cancellable<'a>(
+  s: &'a str,
+  cancelled: &'a AtomicBool,
+) -> Result<Vec<Operation>, Error<'a>> {
+  lex_parse_iter(s).cancel_on(cancelled).collect()
+}
+
+// The file is memory-mapped instead of read into a String. Errors borrow the mapped source, so
+// they are returned as messages.
+#[cfg(feature = "std")]
+pub fn parse_file(path: impl AsRef<Path>) -> Result<Vec<Operation>, Box<dyn std::error::Error>> {
+  let file = File::open(path)?;
+  if file.metadata()?.len() == 0 {
+    return Ok(Vec::new());
+  }
+  // SAFETY: The map is only read while it's alive. Modifying the file concurrently is undefined
+  // behaviour, as with any memory-mapped file.
+  let map = unsafe { memmap2::Mmap::map(&file)? };
+  let source = std::str::from_utf8(&map)?;
+  lex_parse_iter(source)
+    .collect::<Result<_, _>>()
+    .map_err(|e| e.to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[cfg(feature = "std")]
+  #[test]
+  fn test_parse_file() {
+    use std::{env, fs, process};
+
+    let path = env::temp_dir().join(format!("eql-parse-file-{}.eql", process::id()));
+    let source = "Create HR.\nAdd Mihai to HR!\n";
+    fs::write(&path, source).unwrap();
+    let got = parse_file(&path);
+    fs::write(&path, "Show").unwrap();
+    let err = parse_file(&path);
+    fs::write(&path, "").unwrap();
+    let empty = parse_file(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(got.unwrap(), lex_parse(source).unwrap());
+    assert!(err.is_err());
+    assert!(empty.unwrap().is_empty());
+  }
+
+  const FRAGMENTS: &[&str] = &[
+    "Add",
+    "Create",
+    "Show",
+    "Remove",
+    "Define",
+    "team",
+    "to",
+    "from",
+    "and",
+    "as",
+    " ",
+    "Mihai",
+    ",",
+    ".",
+    "!",
+    "?",
+    "\n",
+    "1",
+    "ă\u{0300}",
+  ];
+
+  fn parse_everything(source: &str) {
+    let _ = lex(source).map(parse);
+    let _ = lex_parse_iter(source).count();
+    let _ = parser::parse_statements(source);
+    let _ = parser::Parser::new().parse(source);
+  }
+
+  #[test]
+  fn cancellation() {
+    let source = "Create HR. Add Mihai to HR.";
+    let cancelled = AtomicBool::new(false);
+    assert_eq!(
+      lex_parse_cancellable(source, &cancelled),
+      Ok(parse(lex(source).unwrap()).unwrap())
+    );
+    cancelled.store(true, std::sync::atomic::Ordering::Relaxed);
+    assert_eq!(
+      lex_parse_cancellable(source, &cancelled),
+      Err(Error::Cancelled)
+    );
+  }
+
+  // Truncated or concatenated input must not be accepted silently by any of the entry points.
+  #[cfg(feature = "std")]
+  #[test]
+  fn trailing_garbage() {
+    let sources = [
+      "Create HR. foo",
+      "Create HR.. Show HR.",
+      "Create HR. , Show HR.",
+      "Create HR. Show",
+      "Create HR. Show HR. Add",
+    ];
+    for source in sources {
+      assert!(lex_parse(source).is_err(), "{:?}", source);
+      assert!(lex_parse_iter(source).any(|r| r.is_err()), "{:?}", source);
+      assert!(parser::parse_statements(source).is_err(), "{:?}", source);
+      assert!(parser::Parser::new().parse(source).is_err(), "{:?}", source);
+    }
+  }
+
+  #[test]
+  fn no_panics() {
+    let n = FRAGMENTS.len();
+    for mut i in 0..n.pow(4) {
+      let mut source = String::new();
+      while i > 0 {
+        source.push_str(FRAGMENTS[i % n]);
+        i /= n;
+      }
+      parse_everything(&source);
+    }
+
+    let mut state = 1u64;
+    let mut next = || {
+      state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+      (state >> 33) as usize
+    };
+    for _ in 0..20000 {
+      let len = next() % 16;
+      let source: String = (0..len).map(|_| FRAGMENTS[next() % n]).collect();
+      parse_everything(&source);
+    }
+  }
+}
@@ -0,0 +1,109 @@
+use crate::text::UnicodeSegmentation;
+use std::cell::RefCell;
+
+// Maps byte offsets in a source to the line and grapheme column tokens report, and back. Line starts
+// are found once, when the index is built. The graphemes of a line are only counted the first time
+// a position on it is requested, and are cached afterwards.
+#[derive(Debug)]
+pub struct LineIndex<'a> {
+  source: &'a str,
+  line_starts: Vec<usize>,
+  graphemes: RefCell<Vec<Option<Vec<usize>>>>,
+}
+
+impl<'a> LineIndex<'a> {
+  pub fn new(source: &'a str) -> Self {
+    let line_starts: Vec<usize> = std::iter::once(0)
+      .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+      .filter(|i| *i < source.len() || *i == 0)
+      .collect();
+    let graphemes = RefCell::new(vec![None; line_starts.len()]);
+    LineIndex {
+      source,
+      line_starts,
+      graphemes,
+    }
+  }
+
+  pub fn line_count(&self) -> usize {
+    self.line_starts.len()
+  }
+
+  // Lines are numbered from 1 and keep their terminator.
+  pub fn line(&self, line_number: usize) -> Option<&'a str> {
+    let start = *self.line_starts.get(line_number.checked_sub(1)?)?;
+    let end = self
+      .line_starts
+      .get(line_number)
+      .copied()
+      .unwrap_or(self.source.len());
+    Some(&self.source[start..end])
+  }
+
+  fn with_graphemes<T>(&self, line_number: usize, f: impl FnOnce(&[usize]) -> T) -> Option<T> {
+    let line = self.line(line_number)?;
+    let mut graphemes = self.graphemes.borrow_mut();
+    let starts = graphemes[line_number - 1]
+      .get_or_insert_with(|| line.grapheme_indices(true).map(|(i, _)| i).collect());
+    Some(f(starts))
+  }
+
+  // Returns the line and column of the grapheme the offset falls into. The end of the source is
+  // positioned right after its last grapheme.
+  pub fn position(&self, offset: usize) -> Option<(usize, usize)> {
+    if offset > self.source.len() {
+      return None;
+    }
+    let line_number = self.line_starts.partition_point(|start| *start <= offset);
+    let offset = offset - self.line_starts[line_number - 1];
+    let column_number = self.with_graphemes(line_number, |starts| {
+      starts.partition_point(|start| *start <= offset).max(1)
+    })?;
+    let past_end = offset == self.line(line_number)?.len() && offset > 0;
+    Some((line_number, column_number + past_end as usize))
+  }
+
+  pub fn offset(&self, line_number: usize, column_number: usize) -> Option<usize> {
+    let start = *self.line_starts.get(line_number.checked_sub(1)?)?;
+    let len = self.line(line_number)?.len();
+    let offset = self.with_graphemes(line_number, |starts| {
+      match column_number.checked_sub(1)? {
+        i if i == starts.len() => Some(len),
+        i => starts.get(i).copied(),
+      }
+    })??;
+    Some(start + offset)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer;
+
+  #[test]
+  fn positions_match_tokens() {
+    let source = "Create\n孫德明 and e\u{0301}a!\r\nShow HR?";
+    let index = LineIndex::new(source);
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.line(2), Some("孫德明 and e\u{0301}a!\r\n"));
+    assert_eq!(index.line(4), None);
+
+    for (segment, token) in lexer::segments(source) {
+      let offset = segment.as_ptr() as usize - source.as_ptr() as usize;
+      let position = (token.line_number(), token.column_number());
+      assert_eq!(index.position(offset), Some(position), "{:?}", segment);
+      assert_eq!(index.offset(position.0, position.1), Some(offset));
+    }
+    assert_eq!(index.position(source.len()), Some((3, 9)));
+    assert_eq!(index.position(source.len() + 1), None);
+  }
+
+  #[test]
+  fn empty_source() {
+    let index = LineIndex::new("");
+    assert_eq!(index.line_count(), 1);
+    assert_eq!(index.position(0), Some((1, 1)));
+    assert_eq!(index.offset(1, 1), Some(0));
+  }
+}
@@ -0,0 +1,334 @@
+use crate::analysis::{self, Conflict};
+use crate::error::{Error, Severity};
+use crate::lexer;
+use crate::operation::{NameList, Operation};
+use crate::parser;
+use crate::spelling;
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Lint {
+  pub rule: &'static str,
+  pub severity: Severity,
+  pub operation_index: usize,
+  pub message: String,
+  // The byte ranges of the elements the lint is about, or of the whole statement if it isn't about
+  // any. Lints of operations without their source have none.
+  pub spans: Vec<Range<usize>>,
+}
+
+impl fmt::Display for Lint {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} on operation {}: {} [{}]",
+      self.severity,
+      self.operation_index + 1,
+      self.message,
+      self.rule
+    )
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Finding {
+  pub operation_index: usize,
+  pub message: String,
+  // The names or departments of the operation the finding is about, which are located in the
+  // source when linting it.
+  pub elements: Vec<String>,
+}
+
+pub trait LintRule {
+  fn name(&self) -> &'static str;
+
+  fn severity(&self) -> Severity {
+    Severity::Warning
+  }
+
+  fn check(&self, ops: &[Operation]) -> Vec<Finding>;
+}
+
+#[derive(Default)]
+pub struct Registry {
+  rules: Vec<Box<dyn LintRule>>,
+}
+
+impl Registry {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn register(&mut self, rule: impl LintRule + 'static) -> &mut Self {
+    self.rules.push(Box::new(rule));
+    self
+  }
+
+  pub fn run(&self, ops: &[Operation]) -> Vec<Lint> {
+    self.run_with_spans(ops, |_, _| Vec::new())
+  }
+
+  // Parses the source and lints its operations, with the spans of what each lint is about.
+  #[allow(clippy::result_large_err)]
+  pub fn run_source<'a>(&self, source: &'a str) -> Result<Vec<Lint>, Error<'a>> {
+    self.run_source_with(source, &parser::ParserOptions::default())
+  }
+
+  #[allow(clippy::result_large_err)]
+  pub fn run_source_with<'a>(
+    &self,
+    source: &'a str,
+    options: &parser::ParserOptions,
+  ) -> Result<Vec<Lint>, Error<'a>> {
+    let statements = parser::parse_statements_with(source, options)?.statements;
+    let ops: Vec<Operation> = statements.iter().map(|s| s.operation.clone()).collect();
+    Ok(self.run_with_spans(&ops, |finding, i| {
+      let range = statements[i].range.clone();
+      let spans = locate(source, range.clone(), &finding.elements);
+      match spans.is_empty() {
+        true => vec![range],
+        false => spans,
+      }
+    }))
+  }
+
+  fn run_with_spans(
+    &self,
+    ops: &[Operation],
+    spans: impl Fn(&Finding, usize) -> Vec<Range<usize>>,
+  ) -> Vec<Lint> {
+    let mut lints: Vec<Lint> = self
+      .rules
+      .iter()
+      .flat_map(|rule| {
+        let spans = &spans;
+        rule.check(ops).into_iter().map(move |finding| Lint {
+          rule: rule.name(),
+          severity: rule.severity(),
+          operation_index: finding.operation_index,
+          spans: spans(&finding, finding.operation_index),
+          message: finding.message,
+        })
+      })
+      .collect();
+    lints.sort_by_key(|l| l.operation_index);
+    lints
+  }
+}
+
+fn is_name_part(value: lexer::TokenValue) -> bool {
+  matches!(
+    value,
+    lexer::Word(_) | lexer::Keyword(_) | lexer::Number(_) | lexer::Quoted(_) | lexer::Whitespace
+  )
+}
+
+// The byte ranges of every run of tokens in the range of the source that spells one of the
+// elements.
+fn locate(source: &str, range: Range<usize>, elements: &[String]) -> Vec<Range<usize>> {
+  let tokens: Vec<(usize, lexer::Token)> = lexer::segments(&source[range.clone()])
+    .map(|(segment, token)| (segment.as_ptr() as usize - source.as_ptr() as usize, token))
+    .collect();
+  let mut spans = Vec::new();
+  let mut i = 0;
+  while i < tokens.len() {
+    let mut name = String::new();
+    let found = tokens[i..]
+      .iter()
+      .take_while(|(_, t)| is_name_part(t.value()))
+      .enumerate()
+      .filter_map(|(len, (offset, t))| {
+        name.push_str(&t.name());
+        let end = offset + t.text().len();
+        (t.value() != lexer::Whitespace && elements.contains(&name)).then_some((len, end))
+      })
+      .last();
+    match found.filter(|_| tokens[i].1.value() != lexer::Whitespace) {
+      Some((len, end)) => {
+        spans.push(tokens[i].0..end);
+        i += len + 1;
+      }
+      None => i += 1,
+    }
+  }
+  spans
+}
+
+pub struct ApprovedDepartments(pub Vec<String>);
+
+impl LintRule for ApprovedDepartments {
+  fn name(&self) -> &'static str {
+    "approved-departments"
+  }
+
+  fn check(&self, ops: &[Operation]) -> Vec<Finding> {
+    ops
+      .iter()
+      .enumerate()
+      .flat_map(|(i, op)| {
+        op.get_departments()
+          .unwrap_or_default()
+          .iter()
+          .filter(|d| !self.0.contains(d))
+          .map(move |d| Finding {
+            operation_index: i,
+            message: format!("department \"{}\" is not approved", d),
+            elements: vec![d.clone()],
+          })
+      })
+      .collect()
+  }
+}
+
+pub struct Spelling(pub Vec<String>);
+
+impl LintRule for Spelling {
+  fn name(&self) -> &'static str {
+    "spelling"
+  }
+
+  fn check(&self, ops: &[Operation]) -> Vec<Finding> {
+    let dictionary: Vec<&str> = self.0.iter().map(String::as_str).collect();
+    spelling::check_departments(ops, &dictionary)
+      .into_iter()
+      .map(|m| Finding {
+        operation_index: m.operation_index,
+        message: format!(
+          "department \"{}\" might be misspelled, did you mean \"{}\"?",
+          m.department, m.suggestion
+        ),
+        elements: vec![m.department],
+      })
+      .collect()
+  }
+}
+
+// The same element twice in one list is almost always a copy-paste mistake.
+pub struct DuplicateElements;
+
+fn duplicates(list: &[String]) -> impl Iterator<Item = (&str, usize)> {
+  list.iter().enumerate().filter_map(move |(i, element)| {
+    let first = list.iter().position(|e| e == element)?;
+    let count = list.iter().filter(|e| *e == element).count();
+    (first == i && count > 1).then_some((element.as_str(), count))
+  })
+}
+
+impl LintRule for DuplicateElements {
+  fn name(&self) -> &'static str {
+    "duplicate-elements"
+  }
+
+  fn check(&self, ops: &[Operation]) -> Vec<Finding> {
+    ops
+      .iter()
+      .enumerate()
+      .flat_map(|(i, op)| {
+        let names = duplicates(
+          op.get_names()
+            .and_then(NameList::listed)
+            .unwrap_or_default(),
+        )
+        .map(|e| ("name", e));
+        let departments =
+          duplicates(op.get_departments().unwrap_or_default()).map(|e| ("department", e));
+        names
+          .chain(departments)
+          .map(move |(kind, (element, count))| Finding {
+            operation_index: i,
+            message: format!("{} \"{}\" is listed {} times", kind, element, count),
+            elements: vec![element.into()],
+          })
+          .collect::<Vec<_>>()
+      })
+      .collect()
+  }
+}
+
+pub struct Conflicts;
+
+impl LintRule for Conflicts {
+  fn name(&self) -> &'static str {
+    "conflicts"
+  }
+
+  fn check(&self, ops: &[Operation]) -> Vec<Finding> {
+    analysis::analyze(ops)
+      .into_iter()
+      .map(|c| Finding {
+        operation_index: c.operation_index(),
+        message: c.to_string(),
+        elements: match &c {
+          Conflict::CreatedThenRemoved(_, _, department) => vec![department.clone()],
+          Conflict::AddedThenRemoved(_, _, name, _) => vec![name.clone()],
+          Conflict::Duplicate(..) => Vec::new(),
+        },
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{lex_parse, util};
+
+  #[test]
+  fn run_registry() {
+    let ops = lex_parse("Show Sales. Add Mihai to Sciense. Create Physics.").unwrap();
+    let approved = util::to_string_vec(vec!["Science", "Sales"]);
+    let mut registry = Registry::new();
+    registry
+      .register(ApprovedDepartments(approved.clone()))
+      .register(Spelling(approved));
+    let got: Vec<String> = registry.run(&ops).iter().map(Lint::to_string).collect();
+    let expect = vec![
+      r#"Warning on operation 2: department "Sciense" is not approved [approved-departments]"#,
+      r#"Warning on operation 2: department "Sciense" might be misspelled, did you mean "Science"? [spelling]"#,
+      r#"Warning on operation 3: department "Physics" is not approved [approved-departments]"#,
+    ];
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn duplicate_elements() {
+    let ops =
+      lex_parse("Add Mihai, Ana and Mihai to HR and HR. Create HR, PR. Add Ana to HR.").unwrap();
+    let mut registry = Registry::new();
+    registry.register(DuplicateElements);
+    let got: Vec<String> = registry.run(&ops).iter().map(Lint::to_string).collect();
+    let expect = vec![
+      r#"Warning on operation 1: name "Mihai" is listed 2 times [duplicate-elements]"#,
+      r#"Warning on operation 1: department "HR" is listed 2 times [duplicate-elements]"#,
+    ];
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn spans() {
+    let source = "Create HR.\nAdd Mihai, Ana and  Mihai to HR and \"HR\". Dissolve HR.";
+    let mut registry = Registry::new();
+    registry.register(DuplicateElements).register(Conflicts);
+    let lints = registry.run_source(source).unwrap();
+    let spanned: Vec<_> = lints
+      .iter()
+      .map(|l| {
+        let spans: Vec<_> = l.spans.iter().map(|s| &source[s.clone()]).collect();
+        (l.severity, l.operation_index, spans)
+      })
+      .collect();
+    assert_eq!(
+      spanned,
+      vec![
+        (Severity::Warning, 1, vec!["Mihai", "Mihai"]),
+        (Severity::Warning, 1, vec!["HR", "\"HR\""]),
+        (Severity::Warning, 2, vec!["HR"]),
+      ]
+    );
+    assert_eq!(lints[0].spans[1], 31..36);
+    assert!(registry.run(&lex_parse(source).unwrap())[0]
+      .spans
+      .is_empty());
+  }
+}
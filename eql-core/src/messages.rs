@@ -0,0 +1,370 @@
+use crate::error::{Error, ErrorKind};
+use crate::lexer::{self, Token, TokenValue};
+use crate::parser::{Edition, Element, Help, Syntax};
+use crate::text::UnicodeSegmentation;
+use crate::util;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+
+// Message catalogs keep the wording of diagnostics apart from their structure, so errors can be
+// shown in other languages. A catalog only provides text: the excerpt and the pointer under the
+// offending token are rendered the same way for every language.
+pub trait Catalog {
+  fn message(&self, kind: ErrorKind) -> &str;
+
+  fn help(&self, help: &Help) -> String;
+
+  fn location(&self, kind: ErrorKind, line: usize, column: usize) -> String {
+    format!("Error {} on line {}, column {}", kind, line, column)
+  }
+
+  fn cancelled(&self) -> &str {
+    "Parsing was cancelled"
+  }
+
+  // What exactly went wrong while lexing, such as the limit that was exceeded.
+  fn details(&self, error: &lexer::Error) -> String {
+    match (error.kind(), error.get_limit()) {
+      (ErrorKind::InputTooLong, Some(limit)) => {
+        format!("the input is longer than {} bytes", limit)
+      }
+      (ErrorKind::TooManyTokens, Some(limit)) => {
+        format!("the input has more than {} tokens", limit)
+      }
+      (ErrorKind::LineTooLong, Some(limit)) => {
+        format!("the line is longer than {} bytes", limit)
+      }
+      (ErrorKind::InvalidUtf8, _) => "invalid UTF-8".into(),
+      (ErrorKind::InvalidEscape, _) => {
+        format!("invalid escape sequence \"{}\"", error.token().text())
+      }
+      _ => error.token().value().to_string(),
+    }
+  }
+
+  fn unexpected(&self, token: Token) -> String {
+    format!("Unexpected {}", token)
+  }
+
+  // The tokens that would have been valid, in place of the unexpected token if there is one.
+  fn expected(&self, tokens: &[TokenValue], instead: bool) -> String {
+    let instead = if instead { " instead" } else { "" };
+    format!("Expected {}{}", util::fmt_list(tokens, ", ", "or"), instead)
+  }
+
+  // Says in words where the error is, given the word before its token, if any, on the same line.
+  fn describe(
+    &self,
+    kind: ErrorKind,
+    token: Token,
+    unexpected: bool,
+    after: Option<&str>,
+  ) -> String {
+    let position = match after {
+      Some(word) => format!("after \"{}\" on line {}", word, token.line_number()),
+      None => format!("at the start of line {}", token.line_number()),
+    };
+    format!(
+      "Error {}: {} The problem is the {}{} {}.",
+      kind,
+      self.message(kind),
+      if unexpected { "unexpected " } else { "" },
+      describe_token(token),
+      position,
+    )
+  }
+}
+
+pub struct English;
+
+impl Catalog for English {
+  fn message(&self, kind: ErrorKind) -> &str {
+    match kind {
+      ErrorKind::UnknownToken => "This is not a word or punctuation the language knows.",
+      ErrorKind::MissingOperation => "You must input an operation!",
+      ErrorKind::Unterminated => "You didn't terminate your operation!",
+      ErrorKind::InvalidTerminator => "This is not a valid terminator for the operation.",
+      ErrorKind::TrailingTokens => "There is something after the end of your operation!",
+      ErrorKind::EmptyList => "You must specify at least one element in the list.",
+      ErrorKind::UnterminatedList => "The list you entered is not terminated!",
+      ErrorKind::ReservedWord => "Reserved words can't be used in lists.",
+      ErrorKind::MultipleElements => "You can only specify one element here!",
+      ErrorKind::MissingVerb => "You must ask where someone is!",
+      ErrorKind::MissingNoun => "You must say what you are renaming!",
+      ErrorKind::InvalidCondition => "The condition is not valid.",
+      ErrorKind::UndefinedTeam => "The team is not defined!",
+      ErrorKind::InvalidDefinition => "The definition is not valid.",
+      ErrorKind::UnsupportedSyntax => "This syntax is not available in the selected edition.",
+      ErrorKind::InputTooLong => "The input is longer than allowed.",
+      ErrorKind::InvalidUtf8 => "The input is not valid UTF-8 text.",
+      ErrorKind::InvalidEscape => "This is not an escape sequence the language knows.",
+      ErrorKind::TooManyTokens => "The input has more tokens than allowed.",
+      ErrorKind::LineTooLong => "The line is longer than allowed.",
+      ErrorKind::InvalidLimit => "The limit is not valid.",
+      ErrorKind::DuplicateClause => "This clause is given more than once.",
+      ErrorKind::MisplacedAlias => "Only people can have aliases.",
+      ErrorKind::MisplacedClause => "This clause can't be given to this operation.",
+      ErrorKind::InvalidEffective => "This is not a day or a date the operation can start on.",
+    }
+  }
+
+  fn help(&self, help: &Help) -> String {
+    let element = |element: &Element| match element {
+      Element::Name => "name",
+      Element::Department => "department",
+      Element::NameOrDepartment => "name or department",
+    };
+    match help {
+      Help::MissingOperation => "You must input an operation!".into(),
+      Help::Unterminated => "You didn't terminate your operation!".into(),
+      Help::InvalidTerminator(t) => format!("{} is not a valid terminator for the operation", t),
+      Help::TrailingTokens => "There is something after the end of your operation!".into(),
+      Help::EmptyList(e, t) => format!(
+        "You must specify at least one {} before list terminator{}",
+        element(e),
+        t.as_ref().map(|t| format!(" {}", t)).unwrap_or_default(),
+      ),
+      Help::UnterminatedList => "The list you entered is not terminated!".into(),
+      Help::ReservedWord(w, None) => format!("Can't use {} in lists, it's reserved!", w),
+      Help::ReservedWord(w, Some(quoted)) => format!(
+        "Can't use {} in lists, it's reserved! Write {} to use it as a name.",
+        w, quoted
+      ),
+      Help::MultipleElements(e) => format!("You can only specify one {}!", element(e)),
+      Help::MissingVerb => "You must ask where someone is!".into(),
+      Help::MissingNoun => "You must say what you are renaming!".into(),
+      Help::DuplicateClause => "Each clause can only be given once!".into(),
+      Help::LimitTooLarge => format!("The limit can be at most {}!", usize::MAX),
+      Help::LimitZero => "The limit must be at least 1!".into(),
+      Help::ExceptWithoutDepartments => {
+        "You must say who is removed, and from where, to exclude anyone!".into()
+      }
+      Help::MisplacedAlias => "Only people can have aliases!".into(),
+      Help::MisplacedLimit(kind) => format!("{} operations can't be limited!", kind),
+      Help::InvalidEffective(when) => format!(
+        "{} is not a day of the week or a date written like 2024-03-15!",
+        when
+      ),
+      Help::UnsupportedSyntax(syntax) => {
+        let syntax = match syntax {
+          Syntax::QuotedNames => "Quoted names".into(),
+          Syntax::Numbers => "Numbers".into(),
+          Syntax::Comments => "Comments".into(),
+          Syntax::JoinedNames => "Apostrophes and hyphens in names".into(),
+          Syntax::Operation(kind) => format!("{} operations", kind),
+        };
+        format!("{} need edition {} or later!", syntax, Edition::V2)
+      }
+      Help::ConditionNotExistence => "You can only check if departments exist or not!".into(),
+      Help::ConditionMissingVerb => "You must say whether the departments should exist!".into(),
+      Help::ConditionMissingComma => {
+        "You must separate the condition from the operation with a comma!".into()
+      }
+      Help::ConditionMissingOperation => "You must input an operation after the condition!".into(),
+      Help::UnnamedTeam => "You must specify the name of the team!".into(),
+      Help::UndefinedTeam(name) => format!("Team {} is not defined!", name),
+      Help::DefinitionNotTeam => "You can only define teams!".into(),
+      Help::EmptyTeam => "You must specify who is part of the team!".into(),
+      Help::UnterminatedDefinition => "You didn't terminate your definition!".into(),
+    }
+  }
+}
+
+pub struct Localized<'e, 'a> {
+  error: &'e Error<'a>,
+  catalog: &'e dyn Catalog,
+}
+
+impl fmt::Display for Localized<'_, '_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let (kind, token) = match self.error {
+      Error::Lex(e) => (e.kind(), e.token()),
+      Error::Parse(e) => (e.kind(), e.token()),
+      Error::Cancelled => return f.write_str(self.catalog.cancelled()),
+    };
+    let message = self.catalog.message(kind);
+    // Lex errors say what exactly went wrong, parse errors what was expected and why. Help that
+    // only repeats the message isn't shown twice.
+    let notes = match self.error {
+      Error::Lex(e) => [Some(self.catalog.details(e)), None],
+      Error::Parse(e) => [
+        e.expected_tokens()
+          .filter(|tokens| !tokens.is_empty())
+          .map(|tokens| {
+            self
+              .catalog
+              .expected(tokens, e.unexpected_token().is_some())
+          }),
+        e.help()
+          .map(|help| self.catalog.help(help))
+          .filter(|help| help != message),
+      ],
+      Error::Cancelled => [None, None],
+    };
+    let (excerpt, padding, pointer) =
+      util::fmt_excerpt(token.line(), token.value().get(), token.column_number());
+    write!(
+      f,
+      "{}: {}\n  {}\n  {}{}",
+      self
+        .catalog
+        .location(kind, token.line_number(), token.column_number()),
+      message,
+      excerpt,
+      padding,
+      pointer,
+    )?;
+    notes
+      .iter()
+      .flatten()
+      .try_for_each(|note| write!(f, "\n{}", note))
+  }
+}
+
+impl Localized<'_, '_> {
+  // Describes where the error is in words instead of drawing a pointer under the line, which
+  // screen readers can't relate to the excerpt above it.
+  pub fn describe(&self) -> String {
+    let (kind, token, unexpected) = match self.error {
+      Error::Lex(e) => (e.kind(), e.token(), true),
+      Error::Parse(e) => (e.kind(), e.token(), e.unexpected_token().is_some()),
+      Error::Cancelled => return self.catalog.cancelled().into(),
+    };
+    self
+      .catalog
+      .describe(kind, token, unexpected, previous_word(token))
+  }
+}
+
+impl<'a> Error<'a> {
+  // Renders the error with the wording of the catalog instead of the built-in English details.
+  pub fn localize<'e>(&'e self, catalog: &'e dyn Catalog) -> Localized<'e, 'a> {
+    Localized {
+      error: self,
+      catalog,
+    }
+  }
+}
+
+fn describe_token(token: Token) -> String {
+  match token.written_value() {
+    lexer::Whitespace => "space".into(),
+    lexer::Word(w) => format!("word \"{}\"", w),
+    lexer::Keyword(k) => format!("keyword \"{}\"", k),
+    lexer::Punctuation(",") => "comma".into(),
+    lexer::Punctuation(".") => "full stop".into(),
+    lexer::Punctuation("!") => "exclamation mark".into(),
+    lexer::Punctuation("?") => "question mark".into(),
+    lexer::Comment(_) => "comment".into(),
+    lexer::Number(n) => format!("number {}", n),
+    lexer::Quoted(q) => format!("quoted name \"{}\"", q),
+    lexer::Punctuation(p) | lexer::Unknown(p) => format!("character \"{}\"", p),
+  }
+}
+
+fn previous_word(token: Token<'_>) -> Option<&str> {
+  token.line()[..token.offset()]
+    .split_word_bounds()
+    .rev()
+    .find(|s| util::is_alphabetic(s))
+}
+
+impl Error<'_> {
+  pub fn describe(&self) -> String {
+    self.localize(&English).describe()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::{self, ParserOptions};
+
+  struct Romanian;
+
+  impl Catalog for Romanian {
+    fn message(&self, kind: ErrorKind) -> &str {
+      match kind {
+        ErrorKind::MissingOperation => "Trebuie să introduci o operație!",
+        _ => "Eroare.",
+      }
+    }
+
+    fn help(&self, help: &Help) -> String {
+      match help {
+        Help::MissingOperation => "Trebuie să introduci o operație!".into(),
+        Help::UnsupportedSyntax(Syntax::QuotedNames) => {
+          "Numele între ghilimele necesită ediția 2!".into()
+        }
+        _ => English.help(help),
+      }
+    }
+
+    fn details(&self, error: &lexer::Error) -> String {
+      format!("Caracterul \"{}\"", error.token().text())
+    }
+
+    fn expected(&self, tokens: &[TokenValue], _: bool) -> String {
+      format!("Se aștepta unul din {} cuvinte", tokens.len())
+    }
+
+    fn describe(&self, kind: ErrorKind, _: Token, _: bool, _: Option<&str>) -> String {
+      format!("Eroare {}: {}", kind, self.message(kind))
+    }
+
+    fn location(&self, kind: ErrorKind, line: usize, column: usize) -> String {
+      format!("Eroare {} pe linia {}, coloana {}", kind, line, column)
+    }
+  }
+
+  #[test]
+  fn localize() {
+    let err = parser::parse_statements("Create HR.\nMihai to HR.").unwrap_err();
+    let shown = err.localize(&English).to_string();
+    assert!(shown.starts_with(
+      "Error E0002 on line 2, column 1: You must input an operation!\n  Mihai to HR.\n  ^^^^^\nExpected keyword token \"Add\""
+    ));
+    assert_eq!(shown.matches("You must input an operation!").count(), 1);
+    assert_eq!(
+      err.localize(&Romanian).to_string(),
+      "Eroare E0002 pe linia 2, coloana 1: Trebuie să introduci o operație!\n  Mihai to HR.\n  ^^^^^\nSe aștepta unul din 14 cuvinte"
+    );
+    assert_eq!(
+      err.localize(&Romanian).describe(),
+      "Eroare E0002: Trebuie să introduci o operație!"
+    );
+
+    let err = parser::Parser::with_options(ParserOptions::new().set_edition(Edition::V1))
+      .parse("Add \"Mary and Sons\" to HR.")
+      .unwrap_err();
+    assert!(err
+      .localize(&Romanian)
+      .to_string()
+      .ends_with("\nNumele între ghilimele necesită ediția 2!"));
+
+    let err = Error::Lex(lexer::lex("Add $ to HR.").unwrap_err());
+    assert_eq!(
+      err.localize(&Romanian).to_string(),
+      "Eroare E0001 pe linia 1, coloana 5: Eroare.\n  Add $ to HR.\n      ^\nCaracterul \"$\""
+    );
+    assert_eq!(
+      Error::Cancelled.localize(&English).to_string(),
+      "Parsing was cancelled"
+    );
+  }
+
+  #[test]
+  fn describe() {
+    let err = parser::parse_statements("Create HR.\nAdd Mihai, , Ana to HR.").unwrap_err();
+    assert_eq!(
+      err.describe(),
+      r#"Error E0007: The list you entered is not terminated! The problem is the unexpected comma after "Mihai" on line 2."#
+    );
+    let err = parser::parse_statements("Create HR.\nMihai to HR.").unwrap_err();
+    assert_eq!(
+      err.describe(),
+      r#"Error E0002: You must input an operation! The problem is the unexpected word "Mihai" at the start of line 2."#
+    );
+  }
+}
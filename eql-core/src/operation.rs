@@ -0,0 +1,1207 @@
+use crate::collation::Collate;
+use crate::parser::constants;
+use crate::{lexer, util};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use smallvec::SmallVec;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OperationKind {
+  Unknown,
+  Create,
+  Remove,
+  Add,
+  Show,
+  Define,
+  Where,
+  RenameEmployee,
+  RenameDepartment,
+  Dissolve,
+  Move,
+  Find,
+}
+
+impl fmt::Display for OperationKind {
+  fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+    fmt.write_str(match *self {
+      Unknown => "Unknown",
+      Create => "Create",
+      Remove => "Remove",
+      Add => "Add",
+      Show => "Show",
+      Define => "Define",
+      Where => "Where",
+      RenameEmployee => "Rename employee",
+      RenameDepartment => "Rename department",
+      Dissolve => "Dissolve",
+      Move => "Move",
+      Find => "Find",
+    })
+  }
+}
+
+// Most statements list only a few names or departments, so they are stored inline.
+pub type List<S = String> = SmallVec<[S; 3]>;
+
+// The people an operation is about. All stands for everyone in its departments, as in "Remove
+// everyone from Sales.", and is kept apart from an empty list, which Remove takes as removing the
+// departments themselves. The departments of Show are listed the same way, with All standing for
+// every department, as in "Show all departments.".
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum NameList<T = String> {
+  Listed(SmallVec<[T; 3]>),
+  All,
+}
+
+impl<T> NameList<T> {
+  pub fn listed(&self) -> Option<&[T]> {
+    match self {
+      NameList::Listed(names) => Some(names),
+      NameList::All => None,
+    }
+  }
+
+  pub fn is_all(&self) -> bool {
+    matches!(self, NameList::All)
+  }
+
+  // All isn't empty, even though it lists no one.
+  pub fn is_empty(&self) -> bool {
+    self.listed().is_some_and(|names| names.is_empty())
+  }
+}
+
+impl<T> From<SmallVec<[T; 3]>> for NameList<T> {
+  fn from(names: SmallVec<[T; 3]>) -> Self {
+    NameList::Listed(names)
+  }
+}
+
+impl<T> From<Vec<T>> for NameList<T> {
+  fn from(names: Vec<T>) -> Self {
+    NameList::Listed(names.into())
+  }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for NameList<T> {
+  fn eq(&self, other: &[U; N]) -> bool {
+    self.listed().is_some_and(|names| names == other)
+  }
+}
+
+// The operation only runs if all the departments exist, or if none of them do.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Condition<T = String> {
+  pub departments: SmallVec<[T; 3]>,
+  pub exists: bool,
+}
+
+impl fmt::Display for Condition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let verb = match (self.exists, self.departments.len()) {
+      (true, 1) => "exists",
+      (true, _) => "exist",
+      (false, 1) => "does not exist",
+      (false, _) => "do not exist",
+    };
+    write!(f, "If {} {}", fmt_source_list(&self.departments), verb)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Weekday {
+  Monday,
+  Tuesday,
+  Wednesday,
+  Thursday,
+  Friday,
+  Saturday,
+  Sunday,
+}
+
+impl fmt::Display for Weekday {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Debug::fmt(self, f)
+  }
+}
+
+// When the operation takes effect: on the next given day of the week, or on a calendar date, which
+// is always a valid one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Effective {
+  Day(Weekday),
+  Date { year: u16, month: u8, day: u8 },
+}
+
+impl fmt::Display for Effective {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Effective::Day(day) => write!(f, "starting {}", day),
+      Effective::Date { year, month, day } => {
+        write!(f, "starting {:04}-{:02}-{:02}", year, month, day)
+      }
+    }
+  }
+}
+
+// The order Show lists the members of the departments in, so frontends don't sort them again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Ordering {
+  Name { descending: bool },
+}
+
+impl Ordering {
+  // Sorts the members the way Show lists them, comparing their names with the collator.
+  pub fn sort<S: AsRef<str>>(self, names: &mut [S], collator: &dyn Collate) {
+    match self {
+      Ordering::Name { descending } => names.sort_by(|a, b| {
+        let order = collator.compare(a.as_ref(), b.as_ref());
+        if descending {
+          order.reverse()
+        } else {
+          order
+        }
+      }),
+    }
+  }
+}
+
+impl fmt::Display for Ordering {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Ordering::Name { descending: false } => f.write_str("ordered by name"),
+      Ordering::Name { descending: true } => f.write_str("ordered by name descending"),
+    }
+  }
+}
+
+// The clauses Show can end with, so it can be built with them at once.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ShowClauses<S = String> {
+  pub detailed: bool,
+  // The people left out of the departments shown.
+  pub except: List<S>,
+  pub ordering: Option<Ordering>,
+  pub limit: Option<usize>,
+}
+
+// Derived, it would need the strings to have a default too.
+impl<S> Default for ShowClauses<S> {
+  fn default() -> Self {
+    Self {
+      detailed: false,
+      except: List::new(),
+      ordering: None,
+      limit: None,
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Alias<T = String> {
+  pub name: T,
+  pub alias: T,
+}
+
+// Operations own their strings, but can be mapped to store them another way, see Operation::map.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Operation<S = String> {
+  kind: OperationKind,
+  condition: Option<Condition<S>>,
+  effective: Option<Effective>,
+  aliases: Vec<Alias<S>>,
+  departments: Option<NameList<S>>,
+  fail_silently: Option<bool>,
+  names: Option<NameList<S>>,
+  overwrite: Option<bool>,
+  detailed: Option<bool>,
+  new_name: Option<S>,
+  reassign_to: Option<S>,
+  except: Option<SmallVec<[S; 3]>>,
+  ordering: Option<Ordering>,
+  limit: Option<usize>,
+}
+
+pub use self::OperationKind::*;
+
+// Operations are built with the strings they will store, so the parser can build them with names
+// that borrow from the source.
+impl<S> Operation<S> {
+  pub fn unknown() -> Self {
+    Self {
+      kind: Unknown,
+      condition: None,
+      effective: None,
+      aliases: Vec::new(),
+      departments: None,
+      fail_silently: None,
+      names: None,
+      overwrite: None,
+      detailed: None,
+      new_name: None,
+      reassign_to: None,
+      except: None,
+      ordering: None,
+      limit: None,
+    }
+  }
+
+  pub fn create(departments: impl Into<List<S>>, fail_silently: bool, overwrite: bool) -> Self {
+    Self {
+      kind: Create,
+      departments: Some(NameList::Listed(departments.into())),
+      fail_silently: Some(fail_silently),
+      overwrite: Some(overwrite),
+      ..Self::unknown()
+    }
+  }
+
+  pub fn remove(
+    departments: impl Into<List<S>>,
+    fail_silently: bool,
+    names: impl Into<List<S>>,
+  ) -> Self {
+    let names = NameList::Listed(names.into());
+    Self::remove_except(departments, fail_silently, names, List::new())
+  }
+
+  // Removes all the members of the departments, but not the departments themselves.
+  pub fn remove_everyone(departments: impl Into<List<S>>, fail_silently: bool) -> Self {
+    Self::remove_except(departments, fail_silently, NameList::All, List::new())
+  }
+
+  // Leaves the people in except out, as in "Remove everyone except Mihai from Sales.".
+  pub fn remove_except(
+    departments: impl Into<List<S>>,
+    fail_silently: bool,
+    names: impl Into<NameList<S>>,
+    except: impl Into<List<S>>,
+  ) -> Self {
+    Self {
+      kind: Remove,
+      departments: Some(NameList::Listed(departments.into())),
+      fail_silently: Some(fail_silently),
+      names: Some(names.into()),
+      except: Some(except.into()),
+      ..Self::unknown()
+    }
+  }
+
+  pub fn add(
+    departments: impl Into<List<S>>,
+    fail_silently: bool,
+    names: impl Into<List<S>>,
+    overwrite: bool,
+  ) -> Self {
+    Self::add_except(departments, fail_silently, names, overwrite, List::new())
+  }
+
+  pub fn add_except(
+    departments: impl Into<List<S>>,
+    fail_silently: bool,
+    names: impl Into<List<S>>,
+    overwrite: bool,
+    except: impl Into<List<S>>,
+  ) -> Self {
+    Self {
+      kind: Add,
+      departments: Some(NameList::Listed(departments.into())),
+      fail_silently: Some(fail_silently),
+      names: Some(NameList::Listed(names.into())),
+      overwrite: Some(overwrite),
+      except: Some(except.into()),
+      ..Self::unknown()
+    }
+  }
+
+  pub fn show(departments: impl Into<List<S>>, fail_silently: bool) -> Self {
+    let departments = NameList::Listed(departments.into());
+    Self::show_with(departments, fail_silently, ShowClauses::default())
+  }
+
+  // The departments can be All, to show every department.
+  pub fn show_with(
+    departments: impl Into<NameList<S>>,
+    fail_silently: bool,
+    clauses: ShowClauses<S>,
+  ) -> Self {
+    Self {
+      kind: Show,
+      departments: Some(departments.into()),
+      fail_silently: Some(fail_silently),
+      detailed: Some(clauses.detailed),
+      except: Some(clauses.except),
+      ordering: clauses.ordering,
+      limit: clauses.limit,
+      ..Self::unknown()
+    }
+  }
+
+  pub fn show_all(fail_silently: bool) -> Self {
+    Self::show_with(NameList::All, fail_silently, ShowClauses::default())
+  }
+
+  pub fn where_is(names: impl Into<List<S>>) -> Self {
+    Self {
+      kind: Where,
+      names: Some(NameList::Listed(names.into())),
+      ..Self::unknown()
+    }
+  }
+
+  // Asks which departments the people are in, the inverse of Show.
+  pub fn find(names: impl Into<List<S>>, fail_silently: bool) -> Self {
+    Self::find_first(names, fail_silently, None)
+  }
+
+  pub fn find_first(names: impl Into<List<S>>, fail_silently: bool, limit: Option<usize>) -> Self {
+    Self {
+      kind: Find,
+      names: Some(NameList::Listed(names.into())),
+      fail_silently: Some(fail_silently),
+      limit,
+      ..Self::unknown()
+    }
+  }
+
+  pub fn rename_employee(name: S, new_name: S, fail_silently: bool) -> Self {
+    Self {
+      kind: RenameEmployee,
+      names: Some(NameList::Listed(core::iter::once(name).collect())),
+      new_name: Some(new_name),
+      fail_silently: Some(fail_silently),
+      ..Self::unknown()
+    }
+  }
+
+  pub fn rename_department(department: S, new_name: S, fail_silently: bool) -> Self {
+    Self {
+      kind: RenameDepartment,
+      departments: Some(NameList::Listed(core::iter::once(department).collect())),
+      new_name: Some(new_name),
+      fail_silently: Some(fail_silently),
+      ..Self::unknown()
+    }
+  }
+
+  // The members of the dissolved departments are moved to the department they are reassigned to,
+  // if there is one.
+  pub fn dissolve(
+    departments: impl Into<List<S>>,
+    reassign_to: Option<S>,
+    fail_silently: bool,
+  ) -> Self {
+    Self {
+      kind: Dissolve,
+      departments: Some(NameList::Listed(departments.into())),
+      reassign_to,
+      fail_silently: Some(fail_silently),
+      ..Self::unknown()
+    }
+  }
+
+  // The people are moved from one department to the other, which is stored as the department they
+  // are reassigned to.
+  pub fn move_between(names: impl Into<List<S>>, from: S, to: S, fail_silently: bool) -> Self {
+    let names = NameList::Listed(names.into());
+    Self::move_except(names, from, to, fail_silently, List::new())
+  }
+
+  pub fn move_except(
+    names: impl Into<NameList<S>>,
+    from: S,
+    to: S,
+    fail_silently: bool,
+    except: impl Into<List<S>>,
+  ) -> Self {
+    Self {
+      kind: Move,
+      departments: Some(NameList::Listed(core::iter::once(from).collect())),
+      names: Some(names.into()),
+      reassign_to: Some(to),
+      fail_silently: Some(fail_silently),
+      except: Some(except.into()),
+      ..Self::unknown()
+    }
+  }
+
+  pub fn set_condition(self, condition: Option<Condition<S>>) -> Self {
+    Self { condition, ..self }
+  }
+
+  pub fn set_effective(self, effective: Option<Effective>) -> Self {
+    Self { effective, ..self }
+  }
+
+  // Only Show lists people, so only it can order them.
+  pub fn set_ordering(self, ordering: Option<Ordering>) -> Option<Self> {
+    match self.kind {
+      Show => Some(Self { ordering, ..self }),
+      _ => None,
+    }
+  }
+
+  // Only the operations that list results can limit how many there are.
+  pub fn set_limit(self, limit: Option<usize>) -> Option<Self> {
+    match self.kind {
+      Show | Find => Some(Self { limit, ..self }),
+      _ => None,
+    }
+  }
+
+  // Only operations with names can have aliases.
+  pub fn set_aliases(self, aliases: Vec<Alias<S>>) -> Option<Self> {
+    self.names.as_ref()?;
+    Some(Self { aliases, ..self })
+  }
+
+  pub fn set_detailed(self, detailed: bool) -> Option<Self> {
+    self.detailed.and(Some(Self {
+      detailed: Some(detailed),
+      ..self
+    }))
+  }
+
+  pub fn set_new_name(self, new_name: S) -> Option<Self> {
+    self.new_name.as_ref()?;
+    Some(Self {
+      new_name: Some(new_name),
+      ..self
+    })
+  }
+
+  pub fn set_except(self, except: impl Into<List<S>>) -> Option<Self> {
+    self.except.as_ref()?;
+    Some(Self {
+      except: Some(except.into()),
+      ..self
+    })
+  }
+
+  pub fn set_departments(self, departments: impl Into<List<S>>) -> Option<Self> {
+    self.departments.and(Some(Self {
+      departments: Some(NameList::Listed(departments.into())),
+      ..self
+    }))
+  }
+
+  pub fn set_fail_silently(self, fail_silently: bool) -> Option<Self> {
+    self.fail_silently.and(Some(Self {
+      fail_silently: Some(fail_silently),
+      ..self
+    }))
+  }
+
+  // Only Remove and Move can be about everyone in their departments.
+  pub fn set_names(self, names: impl Into<NameList<S>>) -> Option<Self> {
+    let names = names.into();
+    if names.is_all() && !matches!(self.kind, Remove | Move) {
+      return None;
+    }
+    self.names.as_ref()?;
+    Some(Self {
+      names: Some(names),
+      ..self
+    })
+  }
+
+  pub fn set_overwrite(self, overwrite: bool) -> Option<Self> {
+    self.overwrite.and(Some(Self {
+      overwrite: Some(overwrite),
+      ..self
+    }))
+  }
+}
+
+impl Operation {
+  pub fn aliases_of<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+    self
+      .aliases
+      .iter()
+      .filter(move |a| a.name == name)
+      .map(|a| a.alias.as_str())
+  }
+
+  pub fn get_new_name(&self) -> Option<&str> {
+    self.new_name.as_deref()
+  }
+
+  pub fn new_name(&self) -> &str {
+    self.get_new_name().unwrap()
+  }
+
+  pub fn get_reassign_to(&self) -> Option<&str> {
+    self.reassign_to.as_deref()
+  }
+
+  pub fn to_source(&self) -> String {
+    let departments = match self.shows_all() {
+      true => "all departments".into(),
+      false => fmt_source_list(self.get_departments().unwrap_or_default()),
+    };
+    let terminator = format!(
+      "{}{}{}",
+      fmt_detailed(self),
+      fmt_effective(self),
+      fmt_terminator(self)
+    );
+    let statement = match self.kind() {
+      Unknown | Define => "".into(),
+      Create => format!("{} {}{}", self.kind(), departments, terminator),
+      Show => format!(
+        "{} {}{}{}{}{}",
+        self.kind(),
+        departments,
+        fmt_except(self, fmt_source_list),
+        fmt_ordering(self),
+        fmt_limit(self),
+        terminator
+      ),
+      Dissolve => format!(
+        "{} {}{}{}",
+        self.kind(),
+        departments,
+        fmt_reassign_to(self),
+        terminator
+      ),
+      RenameEmployee => format!(
+        "{} {} to {}{}",
+        self.kind(),
+        fmt_name_list(self, fmt_source_list),
+        fmt_source_name(self.new_name()),
+        terminator
+      ),
+      Find => format!(
+        "{} {}{}{}",
+        self.kind(),
+        fmt_name_list(self, fmt_source_list),
+        fmt_limit(self),
+        terminator
+      ),
+      Move => format!(
+        "{} {}{} from {} to {}{}",
+        self.kind(),
+        fmt_name_list(self, fmt_source_list),
+        fmt_except(self, fmt_source_list),
+        departments,
+        fmt_source_name(self.get_reassign_to().unwrap_or_default()),
+        terminator
+      ),
+      // Departments are renamed without a noun.
+      RenameDepartment => format!(
+        "Rename {} to {}{}",
+        departments,
+        fmt_source_name(self.new_name()),
+        terminator
+      ),
+      Where => format!(
+        "{} {} {}?",
+        self.kind(),
+        fmt_verb(self),
+        fmt_name_list(self, fmt_source_list)
+      ),
+      Remove if self.removes_departments() => {
+        format!("{} {}{}", self.kind(), departments, terminator)
+      }
+      _ => format!(
+        "{} {}{} {} {}{}",
+        self.kind(),
+        fmt_name_list(self, |names| fmt_source_names(names, &self.aliases)),
+        fmt_except(self, fmt_source_list),
+        if self.kind() == Add { "to" } else { "from" },
+        departments,
+        terminator
+      ),
+    };
+    match self.get_condition() {
+      Some(condition) => format!("{}, {}", condition, fmt_lowercase_keyword(&statement)),
+      None => statement,
+    }
+  }
+}
+
+// The parts of an operation that don't depend on how its strings are stored.
+impl<S> Operation<S> {
+  pub fn kind(&self) -> OperationKind {
+    self.kind
+  }
+
+  // Removals without names remove the departments. Removing everyone leaves them empty instead.
+  pub fn removes_departments(&self) -> bool {
+    self.kind == Remove && self.names().is_empty()
+  }
+
+  pub fn shows_all(&self) -> bool {
+    self.kind == Show && self.departments.as_ref().is_some_and(NameList::is_all)
+  }
+
+  pub fn get_condition(&self) -> Option<&Condition<S>> {
+    self.condition.as_ref()
+  }
+
+  pub fn get_effective(&self) -> Option<Effective> {
+    self.effective
+  }
+
+  pub fn get_ordering(&self) -> Option<Ordering> {
+    self.ordering
+  }
+
+  pub fn get_limit(&self) -> Option<usize> {
+    self.limit
+  }
+
+  pub fn aliases(&self) -> &[Alias<S>] {
+    &self.aliases
+  }
+
+  // The departments listed by the operation, which are none if it shows all of them.
+  pub fn get_departments(&self) -> Option<&[S]> {
+    self.departments.as_ref().and_then(NameList::listed)
+  }
+
+  // Showing all departments lists none in particular.
+  pub fn departments(&self) -> &[S] {
+    self
+      .departments
+      .as_ref()
+      .unwrap()
+      .listed()
+      .unwrap_or_default()
+  }
+
+  pub fn get_fail_silently(&self) -> Option<bool> {
+    self.fail_silently
+  }
+
+  pub fn fail_silently(&self) -> bool {
+    self.get_fail_silently().unwrap()
+  }
+
+  pub fn get_names(&self) -> Option<&NameList<S>> {
+    self.names.as_ref()
+  }
+
+  pub fn names(&self) -> &NameList<S> {
+    self.get_names().unwrap()
+  }
+
+  pub fn get_overwrite(&self) -> Option<bool> {
+    self.overwrite
+  }
+
+  pub fn overwrite(&self) -> bool {
+    self.get_overwrite().unwrap()
+  }
+
+  pub fn get_detailed(&self) -> Option<bool> {
+    self.detailed
+  }
+
+  pub fn detailed(&self) -> bool {
+    self.get_detailed().unwrap()
+  }
+
+  // The people left out of the operation, as in "Remove everyone except Mihai from Sales.".
+  pub fn get_except(&self) -> Option<&[S]> {
+    self.except.as_deref()
+  }
+
+  pub fn except(&self) -> &[S] {
+    self.get_except().unwrap()
+  }
+
+  // Replaces every string of the operation, e.g. with a symbol interned for it, keeping everything
+  // else as it is.
+  pub fn map<T>(&self, mut f: impl FnMut(&S) -> T) -> Operation<T> {
+    let mut list = |list: &SmallVec<[S; 3]>| list.iter().map(&mut f).collect::<SmallVec<_>>();
+    let condition = self.condition.as_ref().map(|c| Condition {
+      departments: list(&c.departments),
+      exists: c.exists,
+    });
+    let mut name_list = |names: &NameList<S>| match names {
+      NameList::Listed(names) => NameList::Listed(list(names)),
+      NameList::All => NameList::All,
+    };
+    let departments = self.departments.as_ref().map(&mut name_list);
+    let names = self.names.as_ref().map(&mut name_list);
+    let except = self.except.as_ref().map(&mut list);
+    let aliases = self
+      .aliases
+      .iter()
+      .map(|a| Alias {
+        name: f(&a.name),
+        alias: f(&a.alias),
+      })
+      .collect();
+    Operation {
+      kind: self.kind,
+      condition,
+      effective: self.effective,
+      aliases,
+      departments,
+      fail_silently: self.fail_silently,
+      names,
+      overwrite: self.overwrite,
+      detailed: self.detailed,
+      new_name: self.new_name.as_ref().map(&mut f),
+      reassign_to: self.reassign_to.as_ref().map(&mut f),
+      except,
+      ordering: self.ordering,
+      limit: self.limit,
+    }
+  }
+
+  // Like map, but moves the strings out of the operation, e.g. to own the names that the parser
+  // borrowed from the source.
+  pub fn into_map<T>(self, mut f: impl FnMut(S) -> T) -> Operation<T> {
+    let mut list = |list: SmallVec<[S; 3]>| list.into_iter().map(&mut f).collect::<SmallVec<_>>();
+    let condition = self.condition.map(|c| Condition {
+      departments: list(c.departments),
+      exists: c.exists,
+    });
+    let mut name_list = |names: NameList<S>| match names {
+      NameList::Listed(names) => NameList::Listed(list(names)),
+      NameList::All => NameList::All,
+    };
+    let departments = self.departments.map(&mut name_list);
+    let names = self.names.map(&mut name_list);
+    let except = self.except.map(&mut list);
+    let aliases = self
+      .aliases
+      .into_iter()
+      .map(|a| Alias {
+        name: f(a.name),
+        alias: f(a.alias),
+      })
+      .collect();
+    Operation {
+      kind: self.kind,
+      condition,
+      effective: self.effective,
+      aliases,
+      departments,
+      fail_silently: self.fail_silently,
+      names,
+      overwrite: self.overwrite,
+      detailed: self.detailed,
+      new_name: self.new_name.map(&mut f),
+      reassign_to: self.reassign_to.map(&mut f),
+      except,
+      ordering: self.ordering,
+      limit: self.limit,
+    }
+  }
+}
+
+// Operations that follow a condition start in the middle of the sentence.
+fn fmt_lowercase_keyword(statement: &str) -> String {
+  let mut chars = statement.chars();
+  chars
+    .next()
+    .map(|c| c.to_lowercase().chain(chars).collect())
+    .unwrap_or_default()
+}
+
+// Names that aren't plain words, or that contain a word the grammar gives a meaning to, are quoted
+// so they parse back into the same name instead of a keyword, linker or clause, like the "silently"
+// of "Create Sales silently.".
+fn fmt_source_name(name: &str) -> Cow<'_, str> {
+  let is_plain = lexer::lex(name).is_ok_and(|tokens| {
+    tokens.iter().all(|t| match t.value() {
+      // Leading enumerators and bullets are lexed as whitespace too.
+      lexer::Whitespace => t.text().trim().is_empty(),
+      lexer::Word(_) => constants::grammar_index(t.value()).is_none(),
+      lexer::Number(_) => true,
+      _ => false,
+    })
+  });
+  match is_plain {
+    true => name.into(),
+    false => {
+      let escaped = name
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+      format!("\"{}\"", escaped).into()
+    }
+  }
+}
+
+fn fmt_source_list(elems: &[String]) -> String {
+  let elems: Vec<Cow<str>> = elems.iter().map(|e| fmt_source_name(e)).collect();
+  match elems.as_slice() {
+    [rest @ .., last] if !rest.is_empty() => format!("{} and {}", rest.join(", "), last),
+    _ => elems.join(""),
+  }
+}
+
+fn fmt_terminator(op: &Operation) -> &'static str {
+  match (
+    op.get_fail_silently().unwrap_or_default(),
+    op.get_overwrite().unwrap_or_default(),
+  ) {
+    (true, true) => " silently!",
+    (true, false) => "?",
+    (false, true) => "!",
+    (false, false) => ".",
+  }
+}
+
+fn fmt_reassign_to(op: &Operation) -> String {
+  op.get_reassign_to().map_or("".into(), |department| {
+    format!(" and move everyone to {}", fmt_source_name(department))
+  })
+}
+
+fn fmt_ordering(op: &Operation) -> String {
+  op.get_ordering()
+    .map_or("".into(), |ordering| format!(" {}", ordering))
+}
+
+fn fmt_limit(op: &Operation) -> String {
+  op.get_limit()
+    .map_or("".into(), |limit| format!(", first {}", limit))
+}
+
+fn fmt_detailed(op: &Operation) -> &'static str {
+  if op.get_detailed().unwrap_or_default() {
+    " in detail"
+  } else {
+    ""
+  }
+}
+
+fn fmt_effective(op: &Operation) -> String {
+  op.get_effective()
+    .map_or("".into(), |effective| format!(" {}", effective))
+}
+
+fn fmt_modifier(op: &Operation) -> &'static str {
+  match (
+    op.get_fail_silently().unwrap_or_default(),
+    op.get_overwrite().unwrap_or_default(),
+  ) {
+    (true, true) => " (overwrite if existing, fail silently)",
+    (true, false) => " (fail silently)",
+    (false, true) => " (overwrite if existing)",
+    (false, false) => "",
+  }
+}
+
+// An alias clause ends with a comma, unless "and" or the linker follows it.
+fn fmt_source_names(names: &[String], aliases: &[Alias]) -> String {
+  let mut res = String::new();
+  for (i, name) in names.iter().enumerate() {
+    res.push_str(&fmt_source_name(name));
+    let separator = match names.len() - i {
+      1 => "",
+      2 => " and ",
+      _ => ", ",
+    };
+    let mut aliases = aliases.iter().filter(|a| a.name == *name).peekable();
+    let aliased = aliases.peek().is_some();
+    aliases.for_each(|a| {
+      res.push_str(", also known as ");
+      res.push_str(&fmt_source_name(&a.alias));
+    });
+    if aliased && separator != ", " {
+      res.push(',');
+    }
+    res.push_str(separator);
+  }
+  res
+}
+
+fn fmt_name_list(op: &Operation, fmt_names: impl Fn(&[String]) -> String) -> String {
+  match op.names() {
+    NameList::Listed(names) => fmt_names(names),
+    NameList::All => "everyone".into(),
+  }
+}
+
+fn fmt_except(op: &Operation, fmt_list: impl Fn(&[String]) -> String) -> String {
+  match op.get_except().unwrap_or_default() {
+    [] => "".into(),
+    except => format!(" except {}", fmt_list(except)),
+  }
+}
+
+fn fmt_names(elems: &[String], aliases: &[Alias], linker: &str) -> String {
+  let elems: Vec<String> = elems
+    .iter()
+    .map(|name| {
+      let aliases: Vec<&str> = aliases
+        .iter()
+        .filter(|a| a.name == *name)
+        .map(|a| a.alias.as_str())
+        .collect();
+      if aliases.is_empty() {
+        name.clone()
+      } else {
+        format!("{} (also known as {})", name, aliases.join(", "))
+      }
+    })
+    .collect();
+  let names = util::fmt_list(&elems, ", ", "and");
+  if names.is_empty() {
+    names
+  } else {
+    format!(" {}{}", names, linker)
+  }
+}
+
+fn fmt_display_list(elems: &[String]) -> String {
+  util::fmt_list(elems, ", ", "and")
+}
+
+fn fmt_verb(op: &Operation) -> &'static str {
+  if op.names().listed().unwrap_or_default().len() == 1 {
+    "is"
+  } else {
+    "are"
+  }
+}
+
+impl fmt::Display for Operation {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let everyone = [String::from("everyone")];
+    let statement = match self.kind() {
+      Unknown | Define => return self.kind().fmt(f),
+      RenameEmployee => {
+        return write!(
+          f,
+          "{} {} to {}{}",
+          self.kind(),
+          fmt_name_list(self, fmt_display_list),
+          self.new_name(),
+          fmt_modifier(self)
+        )
+      }
+      RenameDepartment => {
+        return write!(
+          f,
+          "{} {} to {}{}",
+          self.kind(),
+          util::fmt_list(self.departments(), ", ", "and"),
+          self.new_name(),
+          fmt_modifier(self)
+        )
+      }
+      Find => {
+        return write!(
+          f,
+          "{} {}{}{}",
+          self.kind(),
+          fmt_name_list(self, fmt_display_list),
+          fmt_limit(self),
+          fmt_modifier(self)
+        )
+      }
+      Move => {
+        return write!(
+          f,
+          "{} {}{} from {} to {}{}",
+          self.kind(),
+          fmt_name_list(self, fmt_display_list),
+          fmt_except(self, fmt_display_list),
+          util::fmt_list(self.departments(), ", ", "and"),
+          self.get_reassign_to().unwrap_or_default(),
+          fmt_modifier(self)
+        )
+      }
+      Where => {
+        return write!(
+          f,
+          "{} {} {}",
+          self.kind(),
+          fmt_verb(self),
+          fmt_name_list(self, fmt_display_list)
+        )
+      }
+      Create | Show | Dissolve => self.kind().to_string(),
+      _ => format!(
+        "{}{}",
+        self.kind(),
+        fmt_names(
+          match self.names() {
+            NameList::Listed(names) => names,
+            NameList::All => &everyone[..],
+          },
+          &self.aliases,
+          &format!(
+            "{}{}",
+            fmt_except(self, fmt_display_list),
+            match self.kind() {
+              Add => " to",
+              Remove => " from",
+              _ => "",
+            }
+          )
+        )
+      ),
+    };
+    let departments = match self.shows_all() {
+      true => "all departments".into(),
+      false => util::fmt_list(self.departments(), ", ", "and"),
+    };
+    // The exclusions of additions and removals are part of their names.
+    let except = match self.kind() {
+      Show => fmt_except(self, fmt_display_list) + &fmt_ordering(self) + &fmt_limit(self),
+      _ => "".into(),
+    };
+    let statement = format!(
+      "{} {}{}{}{}{}{}",
+      statement,
+      departments,
+      except,
+      fmt_reassign_to(self),
+      fmt_detailed(self),
+      fmt_effective(self),
+      fmt_modifier(self)
+    );
+    match self.get_condition() {
+      Some(condition) => write!(f, "{}, {}", condition, fmt_lowercase_keyword(&statement)),
+      None => f.write_str(&statement),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn format() {
+    let ops = &[
+      (Operation::unknown(), "Unknown"),
+      (
+        Operation::add(
+          util::to_string_vec(vec!["Science", "Engineering"]),
+          false,
+          vec!["Mama".into(), "Tata".into(), "Bunica Miha".into()],
+          false,
+        ),
+        "Add Mama, Tata, and Bunica Miha to Science and Engineering",
+      ),
+      (
+        Operation::remove(
+          util::to_string_vec(vec!["Engineering"]),
+          true,
+          vec!["Sally".into()],
+        ),
+        "Remove Sally from Engineering (fail silently)",
+      ),
+      (
+        Operation::create(util::to_string_vec(vec!["Sales"]), false, true),
+        "Create Sales (overwrite if existing)",
+      ),
+      (
+        Operation::create(util::to_string_vec(vec!["Sales"]), true, true),
+        "Create Sales (overwrite if existing, fail silently)",
+      ),
+      (
+        Operation::show(util::to_string_vec(vec!["HR"]), false),
+        "Show HR",
+      ),
+      (
+        Operation::show_all(true),
+        "Show all departments (fail silently)",
+      ),
+      (
+        Operation::find(util::to_string_vec(vec!["Mihai", "Andrei"]), true),
+        "Find Mihai and Andrei (fail silently)",
+      ),
+      (
+        Operation::remove_everyone(util::to_string_vec(vec!["Sales"]), false)
+          .set_except(util::to_string_vec(vec!["Mihai", "Ana"]))
+          .unwrap(),
+        "Remove everyone except Mihai and Ana from Sales",
+      ),
+      (
+        Operation::where_is(util::to_string_vec(vec!["Mihai", "Ana"])),
+        "Where are Mihai and Ana",
+      ),
+      (
+        Operation::rename_department("Sales".into(), "Business Development".into(), true),
+        "Rename department Sales to Business Development (fail silently)",
+      ),
+      (
+        Operation::move_between(
+          util::to_string_vec(vec!["Mihai", "Ioan"]),
+          "Sales".into(),
+          "Engineering".into(),
+          false,
+        ),
+        "Move Mihai and Ioan from Sales to Engineering",
+      ),
+    ];
+
+    ops
+      .iter()
+      .for_each(|(op, expect)| assert_eq!(format!("{}", op), *expect));
+  }
+
+  #[test]
+  fn to_source() {
+    let sources = &[
+      "Add Mama, Tata and Bunica Miha to Science and Engineering.",
+      "Remove Sally from Engineering?",
+      "Remove Science, Physics and Engineering.",
+      "Create Sales!",
+      "Add Mihai to HR starting Monday silently!",
+      "Show HR.",
+      "Show all departments in detail.",
+      "Show \"all\".",
+      "If Science exists, add Mihai to Science.",
+      "If HR and PR do not exist, create HR and PR!",
+      "Add Mihai to HR starting Monday.",
+      "Add Mihai, also known as Mike, Ana and Ioan, also known as John, to HR.",
+      "Remove Ana and Mihai, also known as Mike, from HR.",
+      "Remove everyone except Mihai and Ana from Sales.",
+      "Move everyone except Ana from HR to PR?",
+      "Show HR except \"and\" in detail?",
+      "Show Sales ordered by name.",
+      "Show Engineering ordered by name, first 10 in detail.",
+      "Find Mihai and Ana, first 1?",
+      "Show all departments except Ana ordered by name descending in detail starting Monday?",
+      "Show HR and PR in detail starting Friday?",
+      "Rename employee Mihai to Mihai Popescu?",
+      "Dissolve HR and PR and move everyone to General?",
+      "Dissolve Sales.",
+      "Where is Mihai?",
+      "Find Mihai.",
+      "Find Mihai, Andrei and \"Ana and Ioan\"?",
+      "Where are Mihai, Ana and Ioan?",
+      "Add \"Mary and Sons\", Jean-Luc and Ana to \"Show\".",
+      "Rename employee Mihai to \"Mihai Popescu Jr.\".",
+      "Rename Sales to Business Development.",
+      "Move Mihai and Ioan from Sales to Engineering.",
+      "Move Ana from \"R and D\" to Research starting Friday?",
+      "Rename \"R and D\" to Research starting Monday?",
+      "Create \"Sales silently\".",
+      "Show \"HR ordered by name\".",
+      "Create \"HR starting Monday\".",
+      "Create \"1. HR\".",
+      "Add \"Ana in detail\" to \"it\".",
+    ];
+    sources.iter().for_each(|source| {
+      let ops = crate::parse(crate::lex(source).unwrap()).unwrap();
+      assert_eq!(ops[0].to_source(), *source);
+      assert_eq!(
+        crate::parse(crate::lex(&ops[0].to_source()).unwrap()).unwrap(),
+        ops
+      );
+    });
+    // Clauses are written back in a fixed order, so only the operation is kept.
+    let ops = crate::parse(crate::lex("Show HR, first 2 ordered by name.").unwrap()).unwrap();
+    assert_eq!(ops[0].to_source(), "Show HR ordered by name, first 2.");
+    assert_eq!(
+      crate::parse(crate::lex(&ops[0].to_source()).unwrap()).unwrap(),
+      ops
+    );
+  }
+}
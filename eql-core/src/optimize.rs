@@ -0,0 +1,182 @@
+use crate::operation::{self, NameList, Operation};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Elimination {
+  pub operation_index: usize,
+  // The operation that undoes every effect of the eliminated one.
+  pub overwritten_by: usize,
+}
+
+impl fmt::Display for Elimination {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "operation {} was eliminated, its effects are undone by operation {}",
+      self.operation_index + 1,
+      self.overwritten_by + 1
+    )
+  }
+}
+
+fn is_unconditional(op: &Operation) -> bool {
+  op.get_condition().is_none() && op.get_effective().is_none()
+}
+
+// The departments are gone after the operation, together with everyone in them.
+fn removes_department(op: &Operation, department: &str) -> bool {
+  let removes = match op.kind() {
+    operation::Remove => op.removes_departments(),
+    operation::Dissolve => op.get_reassign_to().is_none(),
+    _ => false,
+  };
+  removes
+    && is_unconditional(op)
+    && op
+      .get_departments()
+      .unwrap_or_default()
+      .iter()
+      .any(|d| d == department)
+}
+
+fn mentions(op: &Operation, department: &str, names: &[String]) -> bool {
+  let departments = op.get_departments().unwrap_or_default().iter();
+  let conditions = op
+    .get_condition()
+    .map(|c| &c.departments[..])
+    .unwrap_or_default()
+    .iter();
+  op.shows_all()
+    || departments.chain(conditions).any(|d| d == department)
+    || op.get_reassign_to() == Some(department)
+    || op
+      .get_names()
+      .and_then(NameList::listed)
+      .unwrap_or_default()
+      .iter()
+      .any(|n| names.contains(n))
+    || op
+      .get_new_name()
+      .is_some_and(|n| n == department || names.iter().any(|name| name == n))
+}
+
+// Removing the operation must not hide an error it would have raised. Fail-silent additions never
+// raise any, and overwriting additions can only fail if a department is missing, which can't
+// happen if the script created it before.
+fn can_fail(ops: &[Operation], i: usize) -> bool {
+  let add = &ops[i];
+  if add.get_fail_silently() == Some(true) {
+    return false;
+  }
+  // The last operation before the addition that could create or remove the department must be an
+  // unconditional Create.
+  let created = |department: &String| {
+    ops[..i]
+      .iter()
+      .rev()
+      .find(|op| {
+        matches!(
+          op.kind(),
+          operation::Create | operation::Remove | operation::Dissolve | operation::RenameDepartment
+        ) && op
+          .get_departments()
+          .unwrap_or_default()
+          .contains(department)
+      })
+      .is_some_and(|op| op.kind() == operation::Create && is_unconditional(op))
+  };
+  add.get_overwrite() != Some(true)
+    || !add
+      .get_departments()
+      .unwrap_or_default()
+      .iter()
+      .all(created)
+}
+
+// Returns the index of the operation that undoes every effect of the addition at i, if there is one
+// and nothing reads what the addition did before that.
+fn overwritten_by(ops: &[Operation], i: usize) -> Option<usize> {
+  let add = &ops[i];
+  if add.kind() != operation::Add || !is_unconditional(add) || !add.aliases().is_empty() {
+    return None;
+  }
+  if can_fail(ops, i) {
+    return None;
+  }
+  let names = add
+    .get_names()
+    .and_then(NameList::listed)
+    .unwrap_or_default();
+  let departments = add.get_departments().unwrap_or_default();
+  departments.iter().try_fold(i, |last, department| {
+    let j = i
+      + 1
+      + ops[i + 1..]
+        .iter()
+        .position(|op| mentions(op, department, names))?;
+    removes_department(&ops[j], department).then_some(last.max(j))
+  })
+}
+
+// Removes the additions whose effects are undone later in the script before anything observes
+// them. The eliminations are returned so callers can report what was removed.
+pub fn eliminate_dead(ops: &[Operation]) -> (Vec<Operation>, Vec<Elimination>) {
+  let mut res = Vec::with_capacity(ops.len());
+  let mut eliminations = Vec::new();
+  for (i, op) in ops.iter().enumerate() {
+    match overwritten_by(ops, i) {
+      Some(j) => eliminations.push(Elimination {
+        operation_index: i,
+        overwritten_by: j,
+      }),
+      None => res.push(op.clone()),
+    }
+  }
+  (res, eliminations)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[test]
+  fn eliminate() {
+    let ops = lex_parse(
+      "Create HR, PR. Add Mihai to HR! Add Ana to PR? Create Sales. Dissolve HR. Remove PR.",
+    )
+    .unwrap();
+    let (got, eliminations) = eliminate_dead(&ops);
+    let expect = vec![
+      Elimination {
+        operation_index: 1,
+        overwritten_by: 4,
+      },
+      Elimination {
+        operation_index: 2,
+        overwritten_by: 5,
+      },
+    ];
+    assert_eq!(eliminations, expect);
+    assert_eq!(got, [&ops[..1], &ops[3..]].concat());
+  }
+
+  #[test]
+  fn keep_observable() {
+    let sources = [
+      // The department might not exist, so the addition might fail.
+      "Add Mihai to HR. Dissolve HR.",
+      "Create HR. Add Mihai to HR! Show HR. Dissolve HR.",
+      "Create HR. Add Mihai to HR! Show everything. Dissolve HR.",
+      "Create HR. Add Mihai to HR! Where is Mihai? Dissolve HR.",
+      "Create HR. Add Mihai to HR! Dissolve HR and move everyone to PR.",
+      "Create HR. Add Mihai to HR! If HR exists, remove HR.",
+      "Create HR. Dissolve HR. Add Mihai to HR! Dissolve HR.",
+      "Create HR. Add Mihai to HR!",
+    ];
+    for source in sources {
+      let ops = lex_parse(source).unwrap();
+      assert!(eliminate_dead(&ops).1.is_empty(), "{}", source);
+    }
+  }
+}
@@ -0,0 +1,124 @@
+use super::constants::*;
+use super::util::*;
+use crate::lexer;
+use crate::operation::Alias;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+// Returns the words at the end of the tokens, without the whitespace around them.
+fn trailing_words<'a, 'b>(tokens: &'b [lexer::Token<'a>]) -> &'b [lexer::Token<'a>] {
+  let end = tokens
+    .iter()
+    .rposition(|t| t.value() != lexer::Whitespace)
+    .map_or(0, |i| i + 1);
+  let start = tokens[..end]
+    .iter()
+    .rposition(|t| {
+      !matches!(
+        t.value(),
+        lexer::Word(_)
+          | lexer::Keyword(_)
+          | lexer::Number(_)
+          | lexer::Quoted(_)
+          | lexer::Whitespace
+      ) || is_reserved(t.value())
+    })
+    .map_or(0, |i| i + 1);
+  let start = next_non_whitespace(&tokens[..end], start).unwrap_or(end);
+  &tokens[start..end]
+}
+
+// Returns the words at the start of the tokens and the index after them.
+fn leading_words(tokens: &[lexer::Token], from: usize) -> (usize, usize) {
+  let start = next_non_whitespace(tokens, from).unwrap_or(tokens.len());
+  let end = tokens[start..]
+    .iter()
+    .position(|t| {
+      !matches!(
+        t.value(),
+        lexer::Word(_)
+          | lexer::Keyword(_)
+          | lexer::Number(_)
+          | lexer::Quoted(_)
+          | lexer::Whitespace
+      ) || is_reserved(t.value())
+    })
+    .map_or(tokens.len(), |i| start + i);
+  let end = tokens[start..end]
+    .iter()
+    .rposition(|t| t.value() != lexer::Whitespace)
+    .map_or(start, |i| start + i + 1);
+  (start, end)
+}
+
+// Returns the index after "also known as" if the tokens continue with it after the given index.
+fn find_also_known_as(tokens: &[lexer::Token], from: usize) -> Option<usize> {
+  let also = next_non_whitespace(tokens, from).filter(|i| tokens[*i].value() == ALIAS_ALSO)?;
+  let known =
+    next_non_whitespace(tokens, also + 1).filter(|i| tokens[*i].value() == ALIAS_KNOWN)?;
+  let as_ = next_non_whitespace(tokens, known + 1).filter(|i| tokens[*i].value() == LINKER_AS)?;
+  Some(as_ + 1)
+}
+
+type ParsedAliases<'a> = Vec<Alias<Cow<'a, str>>>;
+
+// Removes the ", also known as <alias>" clauses that follow names, returning the aliases separately.
+// The comma that closes a clause is kept if another name follows it, so the list stays well formed.
+// Only people have aliases, so a clause after the "to" or "from" before the departments, or in a
+// Remove that lists only departments, is returned as an error at its "also".
+pub fn strip_aliases<'a, 'b>(
+  tokens: &'b [lexer::Token<'a>],
+) -> Result<(Cow<'b, [lexer::Token<'a>]>, ParsedAliases<'a>), lexer::Token<'a>> {
+  let names_end = tokens
+    .iter()
+    .position(|t| matches!(t.value(), LINKER_TO | LINKER_FROM))
+    .unwrap_or(0);
+  let misplaced = (names_end..tokens.len())
+    .filter(|i| tokens[*i].value() == SEPARATOR_VALUES)
+    .filter(|i| find_also_known_as(tokens, i + 1).is_some())
+    .find_map(|i| next_non_whitespace(tokens, i + 1));
+  if let Some(also) = misplaced {
+    return Err(tokens[also]);
+  }
+  if !tokens[..names_end].iter().any(|t| t.value() == ALIAS_ALSO) {
+    return Ok((tokens.into(), Vec::new()));
+  }
+
+  let mut res = Vec::with_capacity(tokens.len());
+  let mut aliases = Vec::new();
+  let mut i = 0;
+  while i < names_end {
+    let token = tokens[i];
+    let clause = Some(token)
+      .filter(|t| t.value() == SEPARATOR_VALUES)
+      .and_then(|_| find_also_known_as(tokens, i + 1))
+      .map(|after| leading_words(tokens, after))
+      .filter(|(start, end)| start < end);
+    let name = trailing_words(&res);
+    let (start, end) = match clause {
+      Some(clause) if !name.is_empty() => clause,
+      _ => {
+        res.push(token);
+        i += 1;
+        continue;
+      }
+    };
+
+    aliases.push(Alias {
+      name: get_string_from_tokens(name),
+      alias: get_string_from_tokens(&tokens[start..end]),
+    });
+    i = match next_non_whitespace(tokens, end) {
+      Some(comma) if tokens[comma].value() == SEPARATOR_VALUES => {
+        match next_non_whitespace(tokens, comma + 1) {
+          Some(next) if !is_reserved(tokens[next].value()) => comma,
+          _ => comma + 1,
+        }
+      }
+      _ => end,
+    };
+  }
+
+  res.extend_from_slice(&tokens[i..]);
+  Ok((res.into(), aliases))
+}
@@ -0,0 +1,120 @@
+use super::constants::{is_terminator, is_trivia};
+use super::definitions::Definitions;
+use super::options::ParserOptions;
+use super::parse_into;
+use crate::error::Error;
+use crate::lexer::{self, Token};
+use crate::operation::Operation;
+use alloc::vec::Vec;
+use smallvec::SmallVec;
+
+// A parser keeps its token buffer between calls, so parsing many short statements doesn't allocate
+// and free a buffer for each of them. The buffer can't keep the lifetime of the source it was last
+// filled from, so it holds the spans of the tokens, which are put back in the source one statement
+// at a time. Statements short enough for the statement buffer don't allocate at all.
+#[derive(Debug, Default)]
+pub struct Parser {
+  spans: Vec<Token<'static>>,
+  options: ParserOptions,
+}
+
+impl Parser {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_options(options: ParserOptions) -> Self {
+    Parser {
+      spans: Vec::new(),
+      options,
+    }
+  }
+
+  pub fn options(&self) -> ParserOptions {
+    self.options
+  }
+
+  pub fn capacity(&self) -> usize {
+    self.spans.capacity()
+  }
+
+  // The operations are appended to res, so its allocation can be reused as well. The whole input is
+  // lexed first, so res is left untouched if it has unknown tokens.
+  pub fn parse_into<'a>(&mut self, s: &'a str, res: &mut Vec<Operation>) -> Result<(), Error<'a>> {
+    self.spans.clear();
+    let options = self.options;
+    let lexer_options = lexer::LexerOptions::new()
+      .set_coalesce_whitespace(true)
+      .set_grammar(options.get_grammar());
+    for token in lexer::Lexer::with_options(s, lexer_options) {
+      self.spans.push(options.prepare(token?)?.detach());
+    }
+    let mut definitions = Definitions::new();
+    let mut statement: SmallVec<[Token<'a>; 32]> = SmallVec::new();
+    for span in &self.spans {
+      let token = span.attach(s);
+      if statement.is_empty() && is_trivia(token.value()) {
+        continue;
+      }
+      statement.push(token);
+      if is_terminator(token.value()) {
+        parse_into(&statement, &mut definitions, options.get_edition(), res)?;
+        statement.clear();
+      }
+    }
+    Ok(parse_into(
+      &statement,
+      &mut definitions,
+      options.get_edition(),
+      res,
+    )?)
+  }
+
+  pub fn parse<'a>(&mut self, s: &'a str) -> Result<Vec<Operation>, Error<'a>> {
+    let mut res = Vec::new();
+    self.parse_into(s, &mut res)?;
+    Ok(res)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reuse_buffer() {
+    let mut parser = Parser::new();
+    let mut res = Vec::new();
+    parser
+      .parse_into("Create HR. Add Mihai to HR.", &mut res)
+      .unwrap();
+    assert_eq!(res.len(), 2);
+    let capacity = parser.capacity();
+    assert!(capacity > 0);
+
+    for _ in 0..3 {
+      let source = String::from("Show HR?");
+      res.clear();
+      parser.parse_into(&source, &mut res).unwrap();
+      assert_eq!(res, crate::parse(lexer::lex(&source).unwrap()).unwrap());
+    }
+    assert!(parser.parse("Add $ to HR.").is_err());
+    assert_eq!(parser.parse("Show HR.").unwrap().len(), 1);
+  }
+
+  #[test]
+  fn parse_statements() {
+    let mut parser = Parser::new();
+    let source = "Create HR, then add Mihai to it.\n  Show HR? Remove Mihai from HR.";
+    let got = parser.parse(source).unwrap();
+    assert_eq!(got, crate::parse(lexer::lex(source).unwrap()).unwrap());
+    assert_eq!(got.len(), 4);
+    assert!(parser.parse("Show HR? Show Sales").is_err());
+
+    let mut res = Vec::new();
+    assert!(parser
+      .parse_into("Create HR. Add $ to HR.", &mut res)
+      .is_err());
+    assert!(res.is_empty());
+  }
+}
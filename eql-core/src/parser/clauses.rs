@@ -0,0 +1,192 @@
+use super::constants::*;
+use super::tokens::TokenStream;
+use super::util::next_non_whitespace;
+use crate::lexer;
+use crate::operation::{Effective, Ordering};
+
+// Finds a trailing "starting <day>" or "starting <date>" clause, returning where it starts and ends
+// and when the operation takes effect, or the token after "starting" if it is neither a day nor a
+// date. The clause must be the last thing before the terminator, so "starting" can still be part of
+// a name elsewhere.
+pub fn find_effective<'a>(
+  tokens: &[lexer::Token<'a>],
+) -> Option<(usize, usize, Result<Effective, lexer::Token<'a>>)> {
+  let start = tokens
+    .iter()
+    .rposition(|t| t.value() == EFFECTIVE_STARTING)?;
+  let mut stream = TokenStream::new(&tokens[start + 1..]);
+  let when = stream.next().filter(|t| !is_terminator(t.value()))?;
+  let end = start + 1 + stream.position();
+  let effective = match when.value() {
+    lexer::Number(n) => parse_date(n),
+    value => get_weekday(value).map(Effective::Day),
+  };
+  match stream.next() {
+    Some(t) if !is_terminator(t.value()) => None,
+    _ => Some((start, end, effective.ok_or(when))),
+  }
+}
+
+// Reads a date in the calendar format of ISO 8601, as in 2024-03-15. Days the month doesn't have,
+// such as 2023-02-29, are not dates.
+fn parse_date(s: &str) -> Option<Effective> {
+  let field = |s: &str, len| match s.len() == len && s.bytes().all(|b| b.is_ascii_digit()) {
+    true => s.parse::<u16>().ok(),
+    false => None,
+  };
+  let (year, rest) = s.split_once('-')?;
+  let (month, day) = rest.split_once('-')?;
+  let (year, month, day) = (
+    field(year, 4)?,
+    field(month, 2)? as u8,
+    field(day, 2)? as u8,
+  );
+  let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+  let days = match month {
+    2 => 28 + leap as u8,
+    4 | 6 | 9 | 11 => 30,
+    1..=12 => 31,
+    _ => return None,
+  };
+  (1..=days)
+    .contains(&day)
+    .then_some(Effective::Date { year, month, day })
+}
+
+// Finds a trailing "silently", which makes the operation fail silently whatever its terminator, so
+// "silently!" both overwrites and fails silently. Returns where it starts and ends.
+pub fn find_silently(tokens: &[lexer::Token]) -> Option<(usize, usize)> {
+  let start = tokens
+    .iter()
+    .rposition(|t| t.value() == MODIFIER_SILENTLY)?;
+  let mut stream = TokenStream::new(&tokens[start + 1..]);
+  match stream.next() {
+    Some(t) if !is_terminator(t.value()) => None,
+    _ => Some((start, start + 1)),
+  }
+}
+
+// Finds a trailing "first 10" or "limit 10" clause and the comma before it, if any, returning where
+// they start and end and the number token, which is left to the caller to convert.
+pub fn find_limit<'a>(tokens: &[lexer::Token<'a>]) -> Option<(usize, usize, lexer::Token<'a>)> {
+  let keyword = tokens
+    .iter()
+    .rposition(|t| matches!(t.value(), LIMIT_FIRST | LIMIT))?;
+  let mut stream = TokenStream::new(&tokens[keyword + 1..]);
+  let limit = stream
+    .next()
+    .filter(|t| matches!(t.value(), lexer::Number(_)))?;
+  let end = keyword + 1 + stream.position();
+  match stream.next() {
+    Some(t) if !is_terminator(t.value()) => None,
+    _ => Some((with_separator(tokens, keyword), end, limit)),
+  }
+}
+
+// Where a clause starting at the keyword starts together with the comma before it, if any.
+pub fn with_separator(tokens: &[lexer::Token], keyword: usize) -> usize {
+  tokens[..keyword]
+    .iter()
+    .rposition(|t| t.value() != lexer::Whitespace)
+    .filter(|i| tokens[*i].value() == SEPARATOR_VALUES)
+    .unwrap_or(keyword)
+}
+
+// Finds a trailing "ordered by name" clause, optionally followed by "ascending" or "descending",
+// and the comma before it, if any, returning where they start and end.
+pub fn find_ordering(tokens: &[lexer::Token]) -> Option<(usize, usize, Ordering)> {
+  let start = tokens.iter().rposition(|t| t.value() == ORDERING_ORDERED)?;
+  let mut stream = TokenStream::new(&tokens[start + 1..]);
+  for expected in &[ORDERING_BY, ORDERING_NAME] {
+    stream.expect(*expected).ok()?;
+  }
+  let direction = stream.next_if(|t| matches!(t.value(), ORDERING_ASCENDING | ORDERING_DESCENDING));
+  let descending = direction.is_some_and(|t| t.value() == ORDERING_DESCENDING);
+  let end = start + 1 + stream.position();
+  match stream.next() {
+    Some(t) if !is_terminator(t.value()) => None,
+    _ => Some((
+      with_separator(tokens, start),
+      end,
+      Ordering::Name { descending },
+    )),
+  }
+}
+
+// Finds a trailing "in detail" clause and the comma before it, if any, returning where they start
+// and end.
+pub fn find_detail(tokens: &[lexer::Token]) -> Option<(usize, usize)> {
+  let start = tokens.iter().rposition(|t| t.value() == DETAIL_IN)?;
+  let mut stream = TokenStream::new(&tokens[start + 1..]);
+  stream.expect(DETAIL).ok()?;
+  let end = start + 1 + stream.position();
+  match stream.next() {
+    Some(t) if !is_terminator(t.value()) => None,
+    _ => Some((with_separator(tokens, start), end)),
+  }
+}
+
+// Finds a leading "all departments" or "everything", returning where the terminator after it is.
+// The wildcard must be all there is before the terminator.
+pub fn find_wildcard(tokens: &[lexer::Token]) -> Option<usize> {
+  let mut stream = TokenStream::new(tokens);
+  stream.next_if(|t| is_wildcard(t.value()))?;
+  stream.next_if(|t| t.value() == lexer::Word("departments"));
+  match stream.peek(0) {
+    Some(t) if !is_terminator(t.value()) => None,
+    _ => Some(next_non_whitespace(tokens, stream.position()).unwrap_or(tokens.len())),
+  }
+}
+
+// Whether the names start with an "everyone" that is the whole list, as in "Remove everyone from
+// Sales.", which stands for all the members of the departments.
+pub fn is_everyone(tokens: &[lexer::Token]) -> bool {
+  let mut stream = TokenStream::new(tokens);
+  stream.expect(REASSIGN_EVERYONE).is_ok()
+    && stream.peek(0).is_some_and(|t| t.value() == LINKER_FROM)
+}
+
+// Finds the "and move everyone to" clause of a Dissolve operation, returning where it starts and
+// where the department after it starts.
+pub fn find_reassignment(tokens: &[lexer::Token]) -> Option<(usize, usize)> {
+  tokens
+    .iter()
+    .enumerate()
+    .filter(|(_, t)| t.value() == LINKER_AND)
+    .find_map(|(start, _)| {
+      let mut stream = TokenStream::new(&tokens[start + 1..]);
+      for expected in &[REASSIGN_MOVE, REASSIGN_EVERYONE, LINKER_TO] {
+        stream.expect(*expected).ok()?;
+      }
+      Some((start, start + 1 + stream.position()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn dates() {
+    let date = |year, month, day| Some(Effective::Date { year, month, day });
+    assert_eq!(parse_date("2024-03-15"), date(2024, 3, 15));
+    assert_eq!(parse_date("2024-02-29"), date(2024, 2, 29));
+    assert_eq!(parse_date("2000-02-29"), date(2000, 2, 29));
+    assert_eq!(parse_date("0001-12-31"), date(1, 12, 31));
+    for s in &[
+      "2023-02-29",
+      "1900-02-29",
+      "2024-04-31",
+      "2024-13-01",
+      "2024-00-10",
+      "2024-01-00",
+      "2024-3-15",
+      "24-03-15",
+      "2024-03-15-01",
+      "20240315",
+      "+024-03-15",
+    ] {
+      assert_eq!(parse_date(s), None, "{}", s);
+    }
+  }
+}
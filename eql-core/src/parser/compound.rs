@@ -0,0 +1,88 @@
+use super::clauses::with_separator;
+use super::constants::*;
+use super::error::Error;
+use super::options::Edition;
+use super::util::next_non_whitespace;
+use super::{parse_operation, ParsedList, ParsedOperation};
+use crate::lexer;
+use crate::operation::{Condition, Operation};
+use alloc::borrow::Cow;
+use alloc::vec;
+use alloc::vec::Vec;
+use smallvec::SmallVec;
+
+// Returns where the current operation ends and the index of the keyword of the next one, if the
+// tokens continue with ", then <keyword>". The comma is often left out, and a name is unlikely to
+// have "then" followed by a keyword in it, so "then <keyword>" ends the operation too.
+fn find_then(tokens: &[lexer::Token]) -> Option<(usize, usize)> {
+  tokens
+    .iter()
+    .enumerate()
+    .filter(|(_, t)| t.value() == COMPOUND_THEN)
+    .find_map(|(then, _)| {
+      let keyword = next_non_whitespace(tokens, then + 1)
+        .filter(|k| get_compound_keyword(tokens[*k].value()).is_some())?;
+      Some((with_separator(tokens, then), keyword))
+    })
+}
+
+// "it" refers to the departments of the operation before it in the same sentence.
+fn resolve_it<'a>(
+  op: ParsedOperation<'a>,
+  previous: Option<&ParsedList<'a>>,
+) -> ParsedOperation<'a> {
+  match (op.get_departments(), previous) {
+    (Some(departments), Some(previous)) if departments.iter().any(|d| d == COMPOUND_IT) => {
+      let departments: ParsedList = departments
+        .iter()
+        .flat_map(|d| match d.as_ref() {
+          COMPOUND_IT => previous.to_vec(),
+          _ => vec![d.clone()],
+        })
+        .collect();
+      op.clone().set_departments(departments).unwrap_or(op)
+    }
+    _ => op,
+  }
+}
+
+// "Create HR, then add Mihai to it." is parsed as "Create HR. Add Mihai to HR.": every operation in
+// the sentence gets its terminator, and its condition if it has one. "it" in the first operation
+// refers to the departments of the condition. The names of each operation are passed to f.
+pub fn parse_compound<'a, S>(
+  mut op_token: lexer::Token<'a>,
+  mut tokens: &[lexer::Token<'a>],
+  condition: Option<Condition<Cow<'a, str>>>,
+  edition: Edition,
+  res: &mut Vec<Operation<S>>,
+  f: &mut impl FnMut(Cow<'a, str>) -> S,
+) -> Result<(), Error<'a>> {
+  let terminator = tokens.last().filter(|t| is_terminator(t.value())).copied();
+  let mut previous = condition.as_ref().map(|c| c.departments.clone());
+
+  loop {
+    let next = find_then(tokens);
+    let op = match next {
+      Some((end, _)) => {
+        let mut op_tokens: SmallVec<[lexer::Token; 16]> = tokens[..end].into();
+        op_tokens.extend(terminator);
+        parse_operation(op_token, &op_tokens, edition)?
+      }
+      None => parse_operation(op_token, tokens, edition)?,
+    };
+    let op = resolve_it(op, previous.as_ref()).set_condition(condition.clone());
+    previous = op.get_departments().map(ParsedList::from);
+    #[cfg(feature = "tracing")]
+    tracing::debug!(line = op_token.line_number(), operation = %op.map(|s| s.to_string()), "parsed operation");
+    // The names are only copied here, if at all, once the operation is complete.
+    res.push(op.into_map(&mut *f));
+
+    match next {
+      Some((_, keyword)) => {
+        op_token = tokens[keyword];
+        tokens = &tokens[keyword + 1..];
+      }
+      None => return Ok(()),
+    }
+  }
+}
@@ -0,0 +1,98 @@
+use super::compound::parse_compound;
+use super::constants::*;
+use super::error::{Element, Error, Help};
+use super::options::Edition;
+use super::util::*;
+use crate::error::ErrorKind;
+use crate::lexer;
+use crate::operation::{self, Condition, Operation};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+// Parses what follows "If" in "If Science exists, add Mihai to Science." The condition applies to
+// every operation in the sentence.
+pub fn parse_conditional<'a, S>(
+  if_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  edition: Edition,
+  res: &mut Vec<Operation<S>>,
+  f: &mut impl FnMut(Cow<'a, str>) -> S,
+) -> Result<(), Error<'a>> {
+  const VERBS: [lexer::TokenValue; 4] = [
+    CONDITION_EXISTS,
+    CONDITION_EXIST,
+    CONDITION_DOES,
+    CONDITION_DO,
+  ];
+  let error =
+    |unexpected: Option<usize>, expected: &'static [lexer::TokenValue<'static>], help: Help| {
+      Error::new(
+        ErrorKind::InvalidCondition,
+        operation::Unknown,
+        if_token,
+        unexpected.and_then(|i| tokens.get(i)).copied(),
+        Some(expected.into()),
+        Some(help),
+      )
+    };
+
+  let error_handler = get_parse_list_error_handler_generator(operation::Unknown, if_token, edition);
+  let (departments, i) =
+    parse_list(tokens, &VERBS).map_err(error_handler(&VERBS, Element::Department))?;
+  let (exists, verb_end) = match tokens.get(i).map(|t| t.value()) {
+    Some(CONDITION_EXISTS) | Some(CONDITION_EXIST) => (true, i),
+    Some(CONDITION_DOES) | Some(CONDITION_DO) => {
+      let not = next_non_whitespace(tokens, i + 1)
+        .filter(|j| tokens[*j].value() == CONDITION_NOT)
+        .ok_or_else(|| {
+          error(
+            next_non_whitespace(tokens, i + 1),
+            &[CONDITION_NOT],
+            Help::ConditionNotExistence,
+          )
+        })?;
+      let exist = next_non_whitespace(tokens, not + 1)
+        .filter(|j| tokens[*j].value() == CONDITION_EXIST)
+        .ok_or_else(|| {
+          error(
+            next_non_whitespace(tokens, not + 1),
+            &[CONDITION_EXIST],
+            Help::ConditionNotExistence,
+          )
+        })?;
+      (false, exist)
+    }
+    _ => return Err(error(Some(i), &VERBS, Help::ConditionMissingVerb)),
+  };
+
+  let comma = next_non_whitespace(tokens, verb_end + 1)
+    .filter(|j| tokens[*j].value() == SEPARATOR_VALUES)
+    .ok_or_else(|| {
+      error(
+        next_non_whitespace(tokens, verb_end + 1),
+        &[SEPARATOR_VALUES],
+        Help::ConditionMissingComma,
+      )
+    })?;
+  let keyword = next_non_whitespace(tokens, comma + 1)
+    .filter(|j| get_compound_keyword(tokens[*j].value()).is_some())
+    .ok_or_else(|| {
+      error(
+        next_non_whitespace(tokens, comma + 1),
+        &KEYWORDS[..4],
+        Help::ConditionMissingOperation,
+      )
+    })?;
+
+  parse_compound(
+    tokens[keyword],
+    &tokens[keyword + 1..],
+    Some(Condition {
+      departments,
+      exists,
+    }),
+    edition,
+    res,
+    f,
+  )
+}
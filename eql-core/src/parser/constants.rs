@@ -0,0 +1,368 @@
+use crate::lexer::*;
+use crate::operation::{self, OperationKind, Weekday};
+
+pub const KEYWORD_ADD: TokenValue = Keyword("Add");
+pub const KEYWORD_CREATE: TokenValue = Keyword("Create");
+pub const KEYWORD_REMOVE: TokenValue = Keyword("Remove");
+pub const KEYWORD_SHOW: TokenValue = Keyword("Show");
+pub const KEYWORD_DEFINE: TokenValue = Keyword("Define");
+pub const KEYWORD_WHERE: TokenValue = Keyword("Where");
+pub const KEYWORD_RENAME: TokenValue = Keyword("Rename");
+pub const KEYWORD_DISSOLVE: TokenValue = Keyword("Dissolve");
+pub const KEYWORD_MOVE: TokenValue = Keyword("Move");
+pub const KEYWORD_FIND: TokenValue = Keyword("Find");
+pub const KEYWORDS: [TokenValue; 10] = [
+  KEYWORD_ADD,
+  KEYWORD_CREATE,
+  KEYWORD_REMOVE,
+  KEYWORD_SHOW,
+  KEYWORD_DEFINE,
+  KEYWORD_WHERE,
+  KEYWORD_RENAME,
+  KEYWORD_DISSOLVE,
+  KEYWORD_MOVE,
+  KEYWORD_FIND,
+];
+
+// What can start an operation, for errors that expect one.
+pub const KEYWORDS_AND_SYNONYMS: [TokenValue; 14] = [
+  KEYWORD_ADD,
+  KEYWORD_CREATE,
+  KEYWORD_REMOVE,
+  KEYWORD_SHOW,
+  KEYWORD_DEFINE,
+  KEYWORD_WHERE,
+  KEYWORD_RENAME,
+  KEYWORD_DISSOLVE,
+  KEYWORD_MOVE,
+  KEYWORD_FIND,
+  Keyword("Insert"),
+  Keyword("Delete"),
+  Keyword("List"),
+  Keyword("Display"),
+];
+
+pub const LINKER_AND: TokenValue = Word("and");
+pub const LINKER_TO: TokenValue = Word("to");
+pub const LINKER_FROM: TokenValue = Word("from");
+pub const LINKER_AS: TokenValue = Word("as");
+pub const LINKER_IS: TokenValue = Word("is");
+pub const LINKER_ARE: TokenValue = Word("are");
+pub const LINKER_EXCEPT: TokenValue = Word("except");
+
+pub const COMPOUND_THEN: TokenValue = Word("then");
+pub const COMPOUND_IT: &str = "it";
+
+pub const CONDITION_IF: TokenValue = Word("If");
+pub const CONDITION_EXISTS: TokenValue = Word("exists");
+pub const CONDITION_EXIST: TokenValue = Word("exist");
+pub const CONDITION_DOES: TokenValue = Word("does");
+pub const CONDITION_DO: TokenValue = Word("do");
+pub const CONDITION_NOT: TokenValue = Word("not");
+
+pub const NOUN_THE: TokenValue = Word("the");
+pub const NOUN_EMPLOYEE: TokenValue = Word("employee");
+pub const NOUN_PERSON: TokenValue = Word("person");
+pub const NOUN_DEPARTMENT: TokenValue = Word("department");
+
+pub const ALIAS_ALSO: TokenValue = Word("also");
+pub const ALIAS_KNOWN: TokenValue = Word("known");
+
+pub const DETAIL_IN: TokenValue = Word("in");
+pub const DETAIL: TokenValue = Word("detail");
+
+pub const REASSIGN_MOVE: TokenValue = Word("move");
+pub const REASSIGN_EVERYONE: TokenValue = Word("everyone");
+
+pub const WILDCARD_ALL: TokenValue = Word("all");
+pub const WILDCARD_EVERYTHING: TokenValue = Word("everything");
+
+pub const MODIFIER_SILENTLY: TokenValue = Word("silently");
+
+pub const ORDERING_ORDERED: TokenValue = Word("ordered");
+pub const ORDERING_BY: TokenValue = Word("by");
+pub const ORDERING_NAME: TokenValue = Word("name");
+pub const ORDERING_ASCENDING: TokenValue = Word("ascending");
+pub const ORDERING_DESCENDING: TokenValue = Word("descending");
+
+pub const LIMIT_FIRST: TokenValue = Word("first");
+pub const LIMIT: TokenValue = Word("limit");
+
+pub const EFFECTIVE_STARTING: TokenValue = Word("starting");
+
+pub const DEFINITION_TEAM: TokenValue = Word("team");
+
+pub const SEPARATOR: TokenValue = Punctuation(".");
+pub const SEPARATOR_OVERWRITE: TokenValue = Punctuation("!");
+pub const SEPARATOR_FAIL_SILENTLY: TokenValue = Punctuation("?");
+pub const SEPARATOR_VALUES: TokenValue = Punctuation(",");
+pub const TERMINATORS: [TokenValue; 3] = [SEPARATOR, SEPARATOR_OVERWRITE, SEPARATOR_FAIL_SILENTLY];
+
+// Every word the grammar gives a meaning to, in the casing it is written in.
+pub const GRAMMAR_WORDS: [TokenValue; 57] = [
+  KEYWORD_ADD,
+  KEYWORD_CREATE,
+  KEYWORD_REMOVE,
+  KEYWORD_SHOW,
+  KEYWORD_DEFINE,
+  KEYWORD_WHERE,
+  KEYWORD_RENAME,
+  KEYWORD_DISSOLVE,
+  KEYWORD_MOVE,
+  KEYWORD_FIND,
+  LINKER_AND,
+  LINKER_TO,
+  LINKER_FROM,
+  LINKER_AS,
+  LINKER_IS,
+  LINKER_ARE,
+  LINKER_EXCEPT,
+  COMPOUND_THEN,
+  Word(COMPOUND_IT),
+  CONDITION_IF,
+  CONDITION_EXISTS,
+  CONDITION_EXIST,
+  CONDITION_DOES,
+  CONDITION_DO,
+  CONDITION_NOT,
+  NOUN_THE,
+  NOUN_EMPLOYEE,
+  NOUN_PERSON,
+  NOUN_DEPARTMENT,
+  Word("departments"),
+  Word("employees"),
+  Word("people"),
+  ALIAS_ALSO,
+  ALIAS_KNOWN,
+  DETAIL_IN,
+  DETAIL,
+  REASSIGN_MOVE,
+  REASSIGN_EVERYONE,
+  WILDCARD_ALL,
+  WILDCARD_EVERYTHING,
+  MODIFIER_SILENTLY,
+  ORDERING_ORDERED,
+  ORDERING_BY,
+  ORDERING_NAME,
+  ORDERING_ASCENDING,
+  ORDERING_DESCENDING,
+  LIMIT_FIRST,
+  LIMIT,
+  EFFECTIVE_STARTING,
+  DEFINITION_TEAM,
+  Word("Monday"),
+  Word("Tuesday"),
+  Word("Wednesday"),
+  Word("Thursday"),
+  Word("Friday"),
+  Word("Saturday"),
+  Word("Sunday"),
+];
+
+// Grammar words that keep their meaning only in lower case, even when case is ignored, since written
+// otherwise they are more likely names: "IT" is a department, not the pronoun.
+pub const CASE_SENSITIVE_WORDS: [TokenValue; 5] = [
+  Word(COMPOUND_IT),
+  MODIFIER_SILENTLY,
+  ORDERING_ORDERED,
+  LIMIT_FIRST,
+  LIMIT,
+];
+
+// The punctuation the grammar gives a meaning to, which other grammars can spell differently.
+pub const GRAMMAR_PUNCTUATION: [TokenValue; 4] = [
+  SEPARATOR,
+  SEPARATOR_OVERWRITE,
+  SEPARATOR_FAIL_SILENTLY,
+  SEPARATOR_VALUES,
+];
+
+// Grammar tokens are numbered with the words first, then the punctuation, in the order of the
+// arrays above. Every token is looked up here when it is serialized or spelled by a grammar, so this
+// is a match instead of a scan over the arrays.
+pub fn grammar_index(value: TokenValue) -> Option<u8> {
+  Some(match value {
+    KEYWORD_ADD => 0,
+    KEYWORD_CREATE => 1,
+    KEYWORD_REMOVE => 2,
+    KEYWORD_SHOW => 3,
+    KEYWORD_DEFINE => 4,
+    KEYWORD_WHERE => 5,
+    KEYWORD_RENAME => 6,
+    KEYWORD_DISSOLVE => 7,
+    KEYWORD_MOVE => 8,
+    KEYWORD_FIND => 9,
+    LINKER_AND => 10,
+    LINKER_TO => 11,
+    LINKER_FROM => 12,
+    LINKER_AS => 13,
+    LINKER_IS => 14,
+    LINKER_ARE => 15,
+    LINKER_EXCEPT => 16,
+    COMPOUND_THEN => 17,
+    Word(COMPOUND_IT) => 18,
+    CONDITION_IF => 19,
+    CONDITION_EXISTS => 20,
+    CONDITION_EXIST => 21,
+    CONDITION_DOES => 22,
+    CONDITION_DO => 23,
+    CONDITION_NOT => 24,
+    NOUN_THE => 25,
+    NOUN_EMPLOYEE => 26,
+    NOUN_PERSON => 27,
+    NOUN_DEPARTMENT => 28,
+    Word("departments") => 29,
+    Word("employees") => 30,
+    Word("people") => 31,
+    ALIAS_ALSO => 32,
+    ALIAS_KNOWN => 33,
+    DETAIL_IN => 34,
+    DETAIL => 35,
+    REASSIGN_MOVE => 36,
+    REASSIGN_EVERYONE => 37,
+    WILDCARD_ALL => 38,
+    WILDCARD_EVERYTHING => 39,
+    MODIFIER_SILENTLY => 40,
+    ORDERING_ORDERED => 41,
+    ORDERING_BY => 42,
+    ORDERING_NAME => 43,
+    ORDERING_ASCENDING => 44,
+    ORDERING_DESCENDING => 45,
+    LIMIT_FIRST => 46,
+    LIMIT => 47,
+    EFFECTIVE_STARTING => 48,
+    DEFINITION_TEAM => 49,
+    Word("Monday") => 50,
+    Word("Tuesday") => 51,
+    Word("Wednesday") => 52,
+    Word("Thursday") => 53,
+    Word("Friday") => 54,
+    Word("Saturday") => 55,
+    Word("Sunday") => 56,
+    SEPARATOR => 57,
+    SEPARATOR_OVERWRITE => 58,
+    SEPARATOR_FAIL_SILENTLY => 59,
+    SEPARATOR_VALUES => 60,
+    _ => return None,
+  })
+}
+
+pub fn grammar_token(i: u8) -> TokenValue<'static> {
+  let i = i as usize;
+  match GRAMMAR_WORDS.get(i) {
+    Some(word) => *word,
+    None => GRAMMAR_PUNCTUATION[i - GRAMMAR_WORDS.len()],
+  }
+}
+
+// Lookups are matches instead of scans over the arrays above, so they stay cheap as the number of
+// reserved words grows.
+pub fn get_keyword(value: TokenValue) -> Option<OperationKind> {
+  match value {
+    KEYWORD_ADD => Some(operation::Add),
+    KEYWORD_CREATE => Some(operation::Create),
+    KEYWORD_REMOVE => Some(operation::Remove),
+    KEYWORD_SHOW => Some(operation::Show),
+    KEYWORD_DEFINE => Some(operation::Define),
+    KEYWORD_WHERE => Some(operation::Where),
+    KEYWORD_RENAME => Some(operation::RenameEmployee),
+    KEYWORD_DISSOLVE => Some(operation::Dissolve),
+    KEYWORD_MOVE => Some(operation::Move),
+    KEYWORD_FIND => Some(operation::Find),
+    _ => None,
+  }
+}
+
+// Other words for the keywords, which non-native speakers often reach for. They are parsed as the
+// keyword they stand for.
+pub fn get_synonym(value: TokenValue) -> Option<TokenValue<'static>> {
+  match value {
+    Word("Insert") => Some(KEYWORD_ADD),
+    Word("Delete") => Some(KEYWORD_REMOVE),
+    Word("List") | Word("Display") => Some(KEYWORD_SHOW),
+    _ => None,
+  }
+}
+
+// Operations after "then" start in the middle of a sentence, so their keyword isn't capitalized.
+pub fn get_compound_keyword(value: TokenValue) -> Option<OperationKind> {
+  match value {
+    Word(w) | Keyword(w) => [KEYWORD_ADD, KEYWORD_CREATE, KEYWORD_REMOVE, KEYWORD_SHOW]
+      .iter()
+      .find(|k| k.get().eq_ignore_ascii_case(w))
+      .and_then(|k| get_keyword(*k)),
+    _ => None,
+  }
+}
+
+pub fn get_weekday(value: TokenValue) -> Option<Weekday> {
+  match value {
+    Word("Monday") => Some(Weekday::Monday),
+    Word("Tuesday") => Some(Weekday::Tuesday),
+    Word("Wednesday") => Some(Weekday::Wednesday),
+    Word("Thursday") => Some(Weekday::Thursday),
+    Word("Friday") => Some(Weekday::Friday),
+    Word("Saturday") => Some(Weekday::Saturday),
+    Word("Sunday") => Some(Weekday::Sunday),
+    _ => None,
+  }
+}
+
+pub fn is_noun(value: TokenValue) -> bool {
+  matches!(
+    value,
+    NOUN_DEPARTMENT
+      | Word("departments")
+      | NOUN_EMPLOYEE
+      | Word("employees")
+      | NOUN_PERSON
+      | Word("people")
+  )
+}
+
+// "Show all departments." and "Show everything." list every department.
+pub fn is_wildcard(value: TokenValue) -> bool {
+  matches!(value, WILDCARD_ALL | WILDCARD_EVERYTHING)
+}
+
+pub fn is_keyword(value: TokenValue) -> bool {
+  get_keyword(value).is_some()
+}
+
+pub fn is_linker(value: TokenValue) -> bool {
+  matches!(
+    value,
+    LINKER_AND | LINKER_TO | LINKER_FROM | LINKER_AS | LINKER_EXCEPT | DEFINITION_TEAM
+  )
+}
+
+pub fn is_reserved(value: TokenValue) -> bool {
+  is_keyword(value) || is_linker(value)
+}
+
+pub fn is_trivia(value: TokenValue) -> bool {
+  matches!(value, Whitespace | Comment(_))
+}
+
+pub fn is_terminator(value: TokenValue) -> bool {
+  matches!(
+    value,
+    SEPARATOR | SEPARATOR_OVERWRITE | SEPARATOR_FAIL_SILENTLY
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn grammar_indices() {
+    let tokens = GRAMMAR_WORDS.iter().chain(&GRAMMAR_PUNCTUATION);
+    for (i, token) in tokens.enumerate() {
+      assert_eq!(grammar_index(*token), Some(i as u8), "{}", token);
+      assert_eq!(grammar_token(i as u8), *token);
+    }
+    assert_eq!(grammar_index(Word("Add")), None);
+    assert_eq!(grammar_index(Keyword("Insert")), None);
+  }
+}
@@ -0,0 +1,197 @@
+use super::constants::*;
+use super::error::{Element, Error, Help};
+use super::options::Edition;
+use super::util::*;
+use crate::error::ErrorKind;
+use crate::lexer;
+use crate::operation;
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use smallvec::SmallVec;
+
+type TokenBuffer<'a> = SmallVec<[lexer::Token<'a>; 16]>;
+type Teams<'a> = BTreeMap<String, TokenBuffer<'a>>;
+
+// Returns the name made of the words at the start of the tokens, ignoring leading whitespace, and
+// the number of tokens it spans. Reserved words end the name.
+fn get_name(tokens: &[lexer::Token]) -> (String, usize) {
+  let mut words = Vec::new();
+  let mut end = 0;
+  for (i, token) in tokens.iter().enumerate() {
+    match token.value() {
+      lexer::Whitespace => {}
+      lexer::Word(w) | lexer::Number(w) if !is_reserved(token.value()) => {
+        words.push(w);
+        end = i + 1;
+      }
+      _ => break,
+    }
+  }
+  (words.join(" "), end)
+}
+
+fn expand_references<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  teams: &Teams<'a>,
+) -> Result<TokenBuffer<'a>, Error<'a>> {
+  let mut res = TokenBuffer::with_capacity(tokens.len());
+
+  let mut i = 0;
+  while i < tokens.len() {
+    let token = tokens[i];
+    if token.value() != DEFINITION_TEAM {
+      res.push(token);
+      i += 1;
+      continue;
+    }
+    let (name, len) = get_name(&tokens[i + 1..]);
+    let definition = teams.get(&name).ok_or_else(|| {
+      Error::new(
+        ErrorKind::UndefinedTeam,
+        get_keyword(op_token.value()).unwrap_or(operation::Unknown),
+        op_token,
+        Some(token),
+        None,
+        Some(if name.is_empty() {
+          Help::UnnamedTeam
+        } else {
+          Help::UndefinedTeam(name.clone())
+        }),
+      )
+    })?;
+    res.extend_from_slice(definition);
+    i += len + 1;
+  }
+
+  Ok(res)
+}
+
+fn parse_definition<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  teams: &Teams<'a>,
+  edition: Edition,
+) -> Result<(String, TokenBuffer<'a>), Error<'a>> {
+  let error = |unexpected, expected: &'static [lexer::TokenValue<'static>], help: Help| {
+    Error::new(
+      ErrorKind::InvalidDefinition,
+      operation::Define,
+      op_token,
+      unexpected,
+      Some(expected.into()),
+      Some(help),
+    )
+  };
+
+  let team_index = tokens
+    .iter()
+    .position(|t| t.value() != lexer::Whitespace)
+    .filter(|i| tokens[*i].value() == DEFINITION_TEAM)
+    .ok_or_else(|| {
+      error(
+        tokens
+          .iter()
+          .find(|t| t.value() != lexer::Whitespace)
+          .cloned(),
+        &[DEFINITION_TEAM],
+        Help::DefinitionNotTeam,
+      )
+    })?;
+  let (name, len) = get_name(&tokens[team_index + 1..]);
+  let as_index = team_index + len + 1;
+  if name.is_empty() {
+    return Err(error(
+      tokens.get(as_index).cloned(),
+      &[lexer::Word("")],
+      Help::UnnamedTeam,
+    ));
+  }
+  let as_index = as_index
+    + tokens[as_index..]
+      .iter()
+      .position(|t| t.value() != lexer::Whitespace)
+      .filter(|i| tokens[as_index + i].value() == LINKER_AS)
+      .ok_or_else(|| {
+        error(
+          tokens[as_index..]
+            .iter()
+            .find(|t| t.value() != lexer::Whitespace)
+            .cloned(),
+          &[LINKER_AS],
+          Help::EmptyTeam,
+        )
+      })?;
+
+  let members = &tokens[as_index + 1..];
+  let error_handler = get_parse_list_error_handler_generator(operation::Define, op_token, edition);
+  parse_list(members, &[SEPARATOR]).map_err(error_handler(&[SEPARATOR], Element::Name))?;
+  match members.last() {
+    Some(t) if t.value() == SEPARATOR => {}
+    last => {
+      return Err(error(
+        last.filter(|t| is_terminator(t.value())).cloned(),
+        &[SEPARATOR],
+        Help::UnterminatedDefinition,
+      ))
+    }
+  }
+
+  let members = expand_references(op_token, &members[..members.len() - 1], teams)?;
+  Ok((name, members))
+}
+
+// Definitions are removed from the token stream and every reference to a defined team is replaced
+// with the tokens of its members, so the parser never sees them. Teams stay defined across calls,
+// so statements can be expanded one at a time.
+#[derive(Clone, Debug, Default)]
+pub struct Definitions<'a> {
+  teams: Teams<'a>,
+}
+
+impl<'a> Definitions<'a> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn expand<'b>(
+    &mut self,
+    tokens: &'b [lexer::Token<'a>],
+    edition: Edition,
+  ) -> Result<Cow<'b, [lexer::Token<'a>]>, Error<'a>> {
+    if !tokens
+      .iter()
+      .any(|t| t.value() == KEYWORD_DEFINE || t.value() == DEFINITION_TEAM)
+    {
+      return Ok(tokens.into());
+    }
+
+    let mut res = Vec::with_capacity(tokens.len());
+
+    let mut i = 0;
+    while i < tokens.len() {
+      let token = tokens[i];
+      let op_tokens = get_operation_tokens(&tokens[i + 1..]);
+      match token.value() {
+        KEYWORD_DEFINE => {
+          let (name, members) = parse_definition(token, op_tokens, &self.teams, edition)?;
+          self.teams.insert(name, members);
+        }
+        _ if is_keyword(token.value()) || token.value() == CONDITION_IF => {
+          res.push(token);
+          res.extend(expand_references(token, op_tokens, &self.teams)?);
+        }
+        _ => {
+          res.push(token);
+          i += 1;
+          continue;
+        }
+      }
+      i += op_tokens.len() + 1;
+    }
+
+    Ok(res.into())
+  }
+}
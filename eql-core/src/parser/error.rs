@@ -0,0 +1,240 @@
+use super::lexer;
+use crate::error::ErrorKind;
+use crate::messages::{Catalog, English};
+use crate::operation;
+use crate::util;
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+// What a list in the erroneous operation holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Element {
+  Name,
+  Department,
+  NameOrDepartment,
+}
+
+// Syntax added after the first edition, which older editions reject.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Syntax {
+  QuotedNames,
+  Numbers,
+  Comments,
+  // Apostrophes and hyphens in names.
+  JoinedNames,
+  Operation(operation::OperationKind),
+}
+
+// Explains what is wrong with the operation. The wording comes from a message catalog, so the
+// parts that depend on the input are kept here as data.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Help {
+  MissingOperation,
+  Unterminated,
+  InvalidTerminator(String),
+  TrailingTokens,
+  EmptyList(Element, Option<String>),
+  UnterminatedList,
+  // The reserved word, and how to quote it to use it as a name if the edition has quoted names.
+  ReservedWord(String, Option<String>),
+  MultipleElements(Element),
+  MissingVerb,
+  MissingNoun,
+  DuplicateClause,
+  LimitTooLarge,
+  LimitZero,
+  ExceptWithoutDepartments,
+  MisplacedAlias,
+  // Only Show and Find take a limit.
+  MisplacedLimit(operation::OperationKind),
+  // What follows "starting", which is neither a day of the week nor a valid date.
+  InvalidEffective(String),
+  UnsupportedSyntax(Syntax),
+  ConditionNotExistence,
+  ConditionMissingVerb,
+  ConditionMissingComma,
+  ConditionMissingOperation,
+  UnnamedTeam,
+  UndefinedTeam(String),
+  DefinitionNotTeam,
+  EmptyTeam,
+  UnterminatedDefinition,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error<'a> {
+  kind: ErrorKind,
+  operation_kind: operation::OperationKind,
+  operation_token: lexer::Token<'a>,
+  unexpected_token: Option<lexer::Token<'a>>,
+  expected_tokens: Option<Cow<'static, [lexer::TokenValue<'static>]>>,
+  help: Option<Help>,
+}
+
+impl<'a> Error<'a> {
+  pub fn new(
+    kind: ErrorKind,
+    operation_kind: operation::OperationKind,
+    operation_token: lexer::Token<'a>,
+    unexpected_token: Option<lexer::Token<'a>>,
+    expected_tokens: Option<Cow<'static, [lexer::TokenValue<'static>]>>,
+    help: Option<Help>,
+  ) -> Self {
+    Error {
+      kind,
+      operation_kind,
+      operation_token,
+      unexpected_token,
+      expected_tokens,
+      help,
+    }
+  }
+
+  pub fn kind(&self) -> ErrorKind {
+    self.kind
+  }
+
+  pub fn unexpected_token(&self) -> Option<lexer::Token<'a>> {
+    self.unexpected_token
+  }
+
+  pub fn help(&self) -> Option<&Help> {
+    self.help.as_ref()
+  }
+
+  pub fn expected_tokens(&self) -> Option<&[lexer::TokenValue<'static>]> {
+    self.expected_tokens.as_deref()
+  }
+
+  pub fn token(&self) -> lexer::Token<'a> {
+    self.unexpected_token.unwrap_or(self.operation_token)
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error<'_> {}
+
+fn fmt_unexpected(e: &Error) -> String {
+  e.unexpected_token
+    .filter(|t| *t != e.operation_token)
+    .map_or("".into(), |un_token| {
+      let s = English.unexpected(un_token);
+      if un_token.line() != e.operation_token.line() {
+        let (excerpt, padding, pointer) = util::fmt_excerpt(
+          un_token.line(),
+          un_token.value().get(),
+          un_token.column_number(),
+        );
+        format!("\n{}:\n  {}\n  {}{}", s, excerpt, padding, pointer)
+      } else {
+        let op_end = e.operation_token.offset() + e.operation_token.text().len();
+        let between = un_token.line().get(op_end..un_token.offset());
+        let (padding, pointer) =
+          util::fmt_token_pointer(between.unwrap_or_default(), un_token.value().get());
+        format!("{}{}\n{}", padding, pointer, s)
+      }
+    })
+}
+
+fn fmt_expected(e: &Error) -> String {
+  match e.expected_tokens() {
+    Some(ex_tokens) if !ex_tokens.is_empty() => {
+      format!(
+        "\n{}",
+        English.expected(ex_tokens, e.unexpected_token.is_some())
+      )
+    }
+    _ => "".into(),
+  }
+}
+
+impl fmt::Display for Error<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let op_token = &self.operation_token;
+    let un_token = self
+      .unexpected_token
+      .filter(|t| t.line_number() == op_token.line_number())
+      .unwrap_or(*op_token);
+    let (excerpt, padding, pointer) = util::fmt_excerpt(
+      op_token.line(),
+      op_token.value().get(),
+      op_token.column_number(),
+    );
+    write!(
+      f,
+      "Error {} on {} operation on line {}, column {}:\n  {}\n  {}{}{}{}{}",
+      self.kind,
+      self.operation_kind,
+      un_token.line_number(),
+      un_token.column_number(),
+      excerpt,
+      padding,
+      pointer,
+      fmt_unexpected(self),
+      fmt_expected(self),
+      self
+        .help
+        .as_ref()
+        .map_or("".into(), |help| ["\n", &English.help(help)].join(""))
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::lexer::lex;
+  use crate::parser::{parse, Edition, Parser, ParserOptions};
+
+  #[test]
+  fn display_unexpected() {
+    let err = parse(lex("Add Ana to\n  , HR.").unwrap()).unwrap_err();
+    let shown = err.to_string();
+    assert!(
+      shown.contains("Unexpected punctuation \",\" at 2:3:\n    , HR.\n    ^\n"),
+      "{}",
+      shown
+    );
+    let err = parse(lex("Add Ana to , HR.").unwrap()).unwrap_err();
+    let shown = err.to_string();
+    assert!(
+      shown.contains("\nUnexpected punctuation \",\" at 1:12\n"),
+      "{}",
+      shown
+    );
+  }
+  #[test]
+  fn display_reserved_word() {
+    let err = parse(lex("Add Mihai to Show").unwrap()).unwrap_err();
+    let shown = err.to_string();
+    assert!(
+      shown.ends_with(
+        "Can't use keyword token \"Show\" in lists, it's reserved! Write \"Show\" to use it as a name."
+      ),
+      "{}",
+      shown
+    );
+    let options = ParserOptions::new().set_edition(Edition::V1);
+    let err = Parser::with_options(options)
+      .parse("Add Mihai to Show")
+      .unwrap_err();
+    let shown = err.to_string();
+    assert!(
+      shown.ends_with("Can't use keyword token \"Show\" in lists, it's reserved!"),
+      "{}",
+      shown
+    );
+  }
+  #[cfg(feature = "unicode")]
+  #[test]
+  fn display_wide_characters() {
+    let err = parse(lex("Add 孫德明 to , 工程.").unwrap()).unwrap_err();
+    let shown = err.to_string();
+    assert!(
+      shown.contains("  Add 孫德明 to , 工程.\n  ^^^           ^\n"),
+      "{}",
+      shown
+    );
+  }
+}
@@ -0,0 +1,67 @@
+use super::constants::grammar_index;
+use crate::lexer::TokenValue;
+
+// Other spellings for the words and the punctuation of the grammar, for scripts written in other
+// languages: a Romanian grammar could spell "Add" as "Adaugă" and "to" as "la". Spellings are
+// matched exactly, and the English ones keep working alongside them. Grammars are static tables,
+// so options that hold them stay cheap to copy.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Grammar {
+  spellings: &'static [(&'static str, TokenValue<'static>)],
+}
+
+impl Grammar {
+  // Fails if a spelling stands for something that isn't part of the grammar.
+  pub fn new(spellings: &'static [(&'static str, TokenValue<'static>)]) -> Option<Self> {
+    spellings
+      .iter()
+      .all(|(_, value)| grammar_index(*value).is_some())
+      .then_some(Grammar { spellings })
+  }
+
+  pub fn spellings(&self) -> &'static [(&'static str, TokenValue<'static>)] {
+    self.spellings
+  }
+
+  // The index of the grammar token the text spells, see constants::grammar_token.
+  pub(crate) fn lookup(&self, text: &str) -> Option<u8> {
+    self
+      .spellings
+      .iter()
+      .find(|(spelling, _)| *spelling == text)
+      .and_then(|(_, value)| grammar_index(*value))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer::{self, lex_with, Keyword, LexerOptions, Punctuation, Word};
+  use crate::parser::{parse, Parser, ParserOptions};
+
+  static ROMANIAN: [(&str, TokenValue); 5] = [
+    ("Creează", Keyword("Create")),
+    ("Adaugă", Keyword("Add")),
+    ("la", Word("to")),
+    ("și", Word("and")),
+    (";", Punctuation(".")),
+  ];
+
+  #[test]
+  fn localized() {
+    assert_eq!(Grammar::new(&[("Șterge", Word("Remove"))]), None);
+    let grammar = Grammar::new(&ROMANIAN).unwrap();
+    let source = "Creează HR; Adaugă Ana și Mihai la HR;";
+    let expect = parse(lexer::lex("Create HR. Add Ana and Mihai to HR.").unwrap()).unwrap();
+    let options = ParserOptions::new().set_grammar(grammar);
+    assert_eq!(Parser::with_options(options).parse(source), Ok(expect));
+    assert!(Parser::new().parse(source).is_err());
+
+    let tokens = lex_with(source, &LexerOptions::new().set_grammar(grammar)).unwrap();
+    assert_eq!(tokens[0].value(), Keyword("Create"));
+    assert_eq!(tokens[0].written_value(), Keyword("Creează"));
+    assert_eq!(tokens[3].value(), Punctuation("."));
+    assert_eq!(tokens[3].written_value(), Punctuation(";"));
+    assert_eq!(tokens[9].value(), Word("and"));
+  }
+}
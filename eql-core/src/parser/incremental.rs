@@ -0,0 +1,233 @@
+use super::constants::*;
+use super::definitions::Definitions;
+use super::options::ParserOptions;
+use super::parse_into;
+use crate::error::Error;
+use crate::lexer;
+use crate::operation::Operation;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+// An edit replaced the bytes start..old_end of the old source with the bytes start..new_end of the
+// new source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Edit {
+  pub start: usize,
+  pub old_end: usize,
+  pub new_end: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Statement {
+  pub range: Range<usize>,
+  pub operation: Operation,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ParseResult {
+  pub statements: Vec<Statement>,
+  has_definitions: bool,
+  // Reparsing uses the options the source was first parsed with.
+  options: ParserOptions,
+}
+
+impl ParseResult {
+  pub fn operations(&self) -> impl Iterator<Item = &Operation> {
+    self.statements.iter().map(|s| &s.operation)
+  }
+}
+
+fn parse_range<'a>(
+  source: &'a str,
+  range: Range<usize>,
+  options: &ParserOptions,
+) -> Result<ParseResult, Error<'a>> {
+  let base = range.start;
+  let mut tokens = Vec::new();
+  for (segment, token) in lexer::segments(&source[range]) {
+    // Tokens are respelled before they are checked, as the lexer does with the grammar.
+    let token = options.prepare(lexer::check(token.respell(&options.get_grammar()))?)?;
+    let offset = base + segment.as_ptr() as usize - source[base..].as_ptr() as usize;
+    tokens.push((offset, segment.len(), token));
+  }
+
+  let mut res = ParseResult {
+    options: *options,
+    ..ParseResult::default()
+  };
+  let mut definitions = Definitions::new();
+  let mut statement: Vec<lexer::Token> = Vec::new();
+  let mut start = 0;
+  for (offset, len, token) in tokens {
+    if statement.is_empty() {
+      if is_trivia(token.value()) {
+        continue;
+      }
+      start = offset;
+    }
+    statement.push(token);
+    res.has_definitions |= token.value() == KEYWORD_DEFINE || token.value() == DEFINITION_TEAM;
+    if !is_terminator(token.value()) {
+      continue;
+    }
+    let mut ops = Vec::with_capacity(1);
+    parse_into(
+      &statement,
+      &mut definitions,
+      options.get_edition(),
+      &mut ops,
+    )?;
+    res
+      .statements
+      .extend(ops.into_iter().map(|operation| Statement {
+        range: start..offset + len,
+        operation,
+      }));
+    statement.clear();
+  }
+  if !statement.is_empty() {
+    parse_into(
+      &statement,
+      &mut definitions,
+      options.get_edition(),
+      &mut Vec::new(),
+    )?;
+  }
+
+  Ok(res)
+}
+
+pub fn parse_statements(source: &str) -> Result<ParseResult, Error<'_>> {
+  parse_statements_with(source, &ParserOptions::default())
+}
+
+pub fn parse_statements_with<'a>(
+  source: &'a str,
+  options: &ParserOptions,
+) -> Result<ParseResult, Error<'a>> {
+  parse_range(source, 0..source.len(), options)
+}
+
+// Statements that end before the edit are reused, and statements that start after it are reused
+// with their ranges shifted. Only the text between them is parsed again. If that text doesn't parse
+// on its own, or if teams are defined, the whole source is parsed again, so the result is always
+// the same as parsing the new source from scratch with the same options.
+pub fn reparse<'a>(
+  old: &ParseResult,
+  source: &'a str,
+  edit: &Edit,
+) -> Result<ParseResult, Error<'a>> {
+  if old.has_definitions {
+    return parse_statements_with(source, &old.options);
+  }
+
+  let before = old
+    .statements
+    .iter()
+    .take_while(|s| s.range.end < edit.start)
+    .count();
+  let after = old
+    .statements
+    .iter()
+    .position(|s| s.range.start > edit.old_end)
+    .unwrap_or(old.statements.len());
+  let shift = |i: usize| i + edit.new_end - edit.old_end;
+
+  let region_start = old.statements[..before].last().map_or(0, |s| s.range.end);
+  let region_end = old
+    .statements
+    .get(after)
+    .map_or(source.len(), |s| shift(s.range.start));
+  let region = match parse_range(source, region_start..region_end, &old.options) {
+    Ok(region) if !region.has_definitions => region,
+    _ => return parse_statements_with(source, &old.options),
+  };
+
+  let mut statements = old.statements[..before].to_vec();
+  statements.extend(region.statements);
+  statements.extend(old.statements[after..].iter().map(|s| Statement {
+    range: shift(s.range.start)..shift(s.range.end),
+    operation: s.operation.clone(),
+  }));
+  Ok(ParseResult {
+    statements,
+    has_definitions: false,
+    options: old.options,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn apply(source: &str, start: usize, end: usize, text: &str) -> (String, Edit) {
+    let edited = format!("{}{}{}", &source[..start], text, &source[end..]);
+    let edit = Edit {
+      start,
+      old_end: end,
+      new_end: start + text.len(),
+    };
+    (edited, edit)
+  }
+
+  #[test]
+  fn statement_ranges() {
+    let source = "Create HR.\nAdd Mihai to HR!  Show HR?";
+    let got = parse_statements(source).unwrap();
+    let ranges: Vec<_> = got
+      .statements
+      .iter()
+      .map(|s| &source[s.range.clone()])
+      .collect();
+    assert_eq!(ranges, vec!["Create HR.", "Add Mihai to HR!", "Show HR?"]);
+  }
+
+  #[test]
+  fn reparse_matches_full_parse() {
+    let source = "Create HR. Add Mihai to HR. Show HR.";
+    let old = parse_statements(source).unwrap();
+    let edits = &[
+      (15, 20, "Ana and Ioan"),
+      (0, 0, "Create Sales. "),
+      (36, 36, " Remove Mihai from HR."),
+      (26, 27, ""),
+      (9, 11, ""),
+      (7, 9, "Sales"),
+    ];
+    for (start, end, text) in edits {
+      let (edited, edit) = apply(source, *start, *end, text);
+      let got = reparse(&old, &edited, &edit);
+      assert_eq!(got, parse_statements(&edited), "{}", edited);
+    }
+  }
+
+  #[test]
+  fn parse_with_options() {
+    use crate::parser::Edition;
+
+    let options = ParserOptions::new().set_case_insensitive(true);
+    let source = "create HR. add Mihai to HR.";
+    let old = parse_statements_with(source, &options).unwrap();
+    assert_eq!(old.operations().count(), 2);
+    assert!(parse_statements(source).is_err());
+    let (edited, edit) = apply(source, 15, 20, "Ana");
+    let got = reparse(&old, &edited, &edit);
+    assert_eq!(got, parse_statements_with(&edited, &options));
+
+    let options = ParserOptions::new().set_edition(Edition::V1);
+    assert!(parse_statements_with("Add \"Mary\" to HR.", &options).is_err());
+  }
+
+  #[test]
+  fn reparse_with_definitions() {
+    let source = "Define team Core as Mihai and Ana. Add team Core to HR.";
+    let old = parse_statements(source).unwrap();
+    let (edited, edit) = apply(source, 20, 25, "Ioan");
+    let got = reparse(&old, &edited, &edit).unwrap();
+    assert_eq!(got, parse_statements(&edited).unwrap());
+    assert_eq!(
+      got.operations().next().unwrap().get_names().unwrap(),
+      &["Ioan", "Ana"]
+    );
+  }
+}
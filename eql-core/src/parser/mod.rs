@@ -0,0 +1,1522 @@
+#![allow(clippy::result_large_err)]
+#![forbid(unsafe_code)]
+
+mod aliases;
+mod buffered;
+mod clauses;
+mod compound;
+mod conditional;
+pub(crate) mod constants;
+mod definitions;
+mod error;
+mod grammar;
+mod incremental;
+mod nouns;
+mod options;
+mod semantic;
+mod split;
+mod stream;
+mod tokens;
+mod util;
+
+use self::aliases::*;
+pub use self::buffered::Parser;
+use self::clauses::*;
+use self::compound::*;
+use self::conditional::*;
+use self::constants::*;
+use self::definitions::*;
+pub use self::error::*;
+pub use self::grammar::Grammar;
+pub use self::incremental::{
+  parse_statements, parse_statements_with, reparse, Edit, ParseResult, Statement,
+};
+use self::nouns::*;
+use self::options::unsupported;
+pub use self::options::{Edition, ParserOptions};
+pub use self::semantic::classify_tokens;
+pub use self::split::{split_statements, SourceRange};
+pub use self::stream::ParseIter;
+pub use self::tokens::TokenStream;
+use self::util::*;
+use super::lexer;
+use crate::error::ErrorKind;
+use crate::operation::{self, Operation};
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::cmp::min;
+use core::num::IntErrorKind;
+
+// Operations as the parser builds them, with the names written as a single token borrowed from the
+// source. The names are only copied once the operations are handed out, see parse_compound.
+type ParsedOperation<'a> = Operation<Cow<'a, str>>;
+type ParsedList<'a> = operation::List<Cow<'a, str>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TokenClass {
+  Keyword,
+  Linker,
+  Terminator,
+  Separator,
+  Word,
+  Whitespace,
+  Comment,
+  Unknown,
+}
+
+pub fn classify(value: lexer::TokenValue) -> TokenClass {
+  match value {
+    _ if is_keyword(value) => TokenClass::Keyword,
+    _ if is_linker(value) => TokenClass::Linker,
+    _ if is_terminator(value) => TokenClass::Terminator,
+    SEPARATOR_VALUES => TokenClass::Separator,
+    lexer::Word(_) | lexer::Number(_) | lexer::Quoted(_) => TokenClass::Word,
+    lexer::Whitespace => TokenClass::Whitespace,
+    lexer::Comment(_) => TokenClass::Comment,
+    _ => TokenClass::Unknown,
+  }
+}
+
+// "Remove everyone except Mihai from Sales." leaves Mihai out. The exclusions run from "except" to
+// the linker or terminator after it, and the rest of the statement is parsed without them.
+fn parse_except<'a, 'b>(
+  op_kind: operation::OperationKind,
+  op_token: lexer::Token<'a>,
+  tokens: &'b [lexer::Token<'a>],
+  edition: Edition,
+) -> Result<(Cow<'b, [lexer::Token<'a>]>, ParsedList<'a>), Error<'a>> {
+  let start = tokens
+    .iter()
+    .position(|t| t.value() == LINKER_EXCEPT)
+    .filter(|_| edition >= Edition::V2);
+  let start = match start {
+    Some(start) => start,
+    None => return Ok((tokens.into(), operation::List::new())),
+  };
+  const TERMINATORS: [lexer::TokenValue; 5] = [
+    LINKER_TO,
+    LINKER_FROM,
+    SEPARATOR,
+    SEPARATOR_OVERWRITE,
+    SEPARATOR_FAIL_SILENTLY,
+  ];
+  let error_handler = get_parse_list_error_handler_generator(op_kind, op_token, edition);
+  let (except, i) = parse_list(&tokens[start + 1..], &TERMINATORS)
+    .map_err(error_handler(&TERMINATORS, Element::Name))?;
+  let end = min(start + 1 + i, tokens.len());
+  Ok(([&tokens[..start], &tokens[end..]].concat().into(), except))
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_add<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let (tokens, except) = parse_except(operation::Add, op_token, tokens, edition)?;
+  let error_handler = get_parse_list_error_handler_generator(operation::Add, op_token, edition);
+  let (names, i) =
+    parse_list(&tokens, &[LINKER_TO]).map_err(error_handler(&[LINKER_TO], Element::Name))?;
+  let (departments, j) = parse_list(&tokens[min(i + 1, tokens.len())..], &TERMINATORS)
+    .map_err(error_handler(&TERMINATORS, Element::Department))?;
+  handle_terminator(
+    &tokens[min(i + j + 1, tokens.len())..],
+    Operation::add_except(departments, silently, names, false, except),
+    op_token,
+  )
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_create<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let error_handler = get_parse_list_error_handler_generator(operation::Create, op_token, edition);
+  let (departments, i) =
+    parse_list(tokens, &TERMINATORS).map_err(error_handler(&TERMINATORS, Element::Department))?;
+  handle_terminator(
+    &tokens[min(i, tokens.len())..],
+    Operation::create(departments, silently, false),
+    op_token,
+  )
+}
+
+// Strips the trailing clauses of Show and Find. They can be given in any order, but each at most
+// once. Only Show can be detailed or ordered.
+fn strip_clauses<'a>(
+  op_kind: operation::OperationKind,
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  edition: Edition,
+) -> Result<(Vec<lexer::Token<'a>>, operation::ShowClauses<Cow<'a, str>>), Error<'a>> {
+  let v2 = edition >= Edition::V2;
+  let show = op_kind == operation::Show;
+  let mut tokens = tokens.to_vec();
+  let mut clauses = operation::ShowClauses::default();
+  loop {
+    let (start, end, duplicate) = if let Some((start, end)) = find_detail(&tokens).filter(|_| show)
+    {
+      (start, end, core::mem::replace(&mut clauses.detailed, true))
+    } else if let Some((start, end, number)) = find_limit(&tokens).filter(|_| v2) {
+      let limit = parse_limit(op_kind, op_token, number)?;
+      (start, end, clauses.limit.replace(limit).is_some())
+    } else if let Some((start, end, ordering)) = find_ordering(&tokens).filter(|_| v2 && show) {
+      (start, end, clauses.ordering.replace(ordering).is_some())
+    } else {
+      return Ok((tokens, clauses));
+    };
+    if duplicate {
+      return Err(Error::new(
+        ErrorKind::DuplicateClause,
+        op_kind,
+        op_token,
+        clause_keyword(&tokens[start..end]),
+        None,
+        Some(Help::DuplicateClause),
+      ));
+    }
+    tokens.drain(start..end);
+  }
+}
+
+// The keyword of a clause found together with the comma before it.
+fn clause_keyword<'a>(clause: &[lexer::Token<'a>]) -> Option<lexer::Token<'a>> {
+  clause
+    .iter()
+    .find(|t| !matches!(t.value(), lexer::Whitespace | SEPARATOR_VALUES))
+    .copied()
+}
+
+// A limit of zero would show nothing, so it is as invalid as one that doesn't fit. Numbers joined by
+// hyphens aren't limits at all.
+fn parse_limit<'a>(
+  op_kind: operation::OperationKind,
+  op_token: lexer::Token<'a>,
+  number: lexer::Token<'a>,
+) -> Result<usize, Error<'a>> {
+  let help = match number.text().parse::<usize>() {
+    Ok(0) => Some(Help::LimitZero),
+    Ok(limit) => return Ok(limit),
+    Err(e) if *e.kind() == IntErrorKind::PosOverflow => Some(Help::LimitTooLarge),
+    Err(_) => None,
+  };
+  Err(Error::new(
+    ErrorKind::InvalidLimit,
+    op_kind,
+    op_token,
+    Some(number),
+    None,
+    help,
+  ))
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_show<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let (tokens, mut clauses) = strip_clauses(operation::Show, op_token, tokens, edition)?;
+  let (tokens, except) = parse_except(operation::Show, op_token, &tokens, edition)?;
+  clauses.except = except;
+  if let Some(i) = find_wildcard(&tokens).filter(|_| edition >= Edition::V2) {
+    return handle_terminator(
+      &tokens[i..],
+      Operation::show_with(operation::NameList::All, silently, clauses),
+      op_token,
+    );
+  }
+  let error_handler = get_parse_list_error_handler_generator(operation::Show, op_token, edition);
+  let (departments, i) =
+    parse_list(&tokens, &TERMINATORS).map_err(error_handler(&TERMINATORS, Element::Department))?;
+  handle_terminator(
+    &tokens[min(i, tokens.len())..],
+    Operation::show_with(departments, silently, clauses),
+    op_token,
+  )
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_where<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let verb = next_non_whitespace(tokens, 0)
+    .filter(|i| matches!(tokens[*i].value(), LINKER_IS | LINKER_ARE))
+    .ok_or_else(|| {
+      Error::new(
+        ErrorKind::MissingVerb,
+        operation::Where,
+        op_token,
+        next_non_whitespace(tokens, 0).map(|i| tokens[i]),
+        Some((&[LINKER_IS, LINKER_ARE][..]).into()),
+        Some(Help::MissingVerb),
+      )
+    })?;
+  let tokens = &tokens[verb + 1..];
+  const TERMINATORS: [lexer::TokenValue; 2] = [SEPARATOR, SEPARATOR_FAIL_SILENTLY];
+  let error_handler = get_parse_list_error_handler_generator(operation::Where, op_token, edition);
+  let (names, i) =
+    parse_list(tokens, &TERMINATORS).map_err(error_handler(&TERMINATORS, Element::Name))?;
+  handle_terminator(
+    &tokens[min(i, tokens.len())..],
+    Operation::where_is(names),
+    op_token,
+  )
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_find<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let (tokens, operation::ShowClauses { limit, .. }) =
+    strip_clauses(operation::Find, op_token, tokens, edition)?;
+  const TERMINATORS: [lexer::TokenValue; 2] = [SEPARATOR, SEPARATOR_FAIL_SILENTLY];
+  let error_handler = get_parse_list_error_handler_generator(operation::Find, op_token, edition);
+  let (names, i) =
+    parse_list(&tokens, &TERMINATORS).map_err(error_handler(&TERMINATORS, Element::Name))?;
+  handle_terminator(
+    &tokens[min(i, tokens.len())..],
+    Operation::find_first(names, silently, limit),
+    op_token,
+  )
+}
+
+// Returns the single element of a list, or an error pointing at the second one.
+fn parse_single<'a>(
+  op_kind: operation::OperationKind,
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  terminators: &'static [lexer::TokenValue<'static>],
+  element: Element,
+  edition: Edition,
+) -> Result<(Cow<'a, str>, usize), Error<'a>> {
+  let error_handler = get_parse_list_error_handler_generator(op_kind, op_token, edition);
+  let (mut list, i) =
+    parse_list(tokens, terminators).map_err(error_handler(terminators, element))?;
+  if list.len() > 1 {
+    return Err(Error::new(
+      ErrorKind::MultipleElements,
+      op_kind,
+      op_token,
+      tokens[..i]
+        .iter()
+        .find(|t| matches!(t.value(), SEPARATOR_VALUES | LINKER_AND))
+        .copied(),
+      Some(terminators.into()),
+      Some(Help::MultipleElements(element)),
+    ));
+  }
+  Ok((list.pop().unwrap_or_default(), i))
+}
+
+// Employees are renamed with "Rename employee Mihai to Mihai Popescu." and departments with
+// "Rename Sales to Business Development." or "Rename department Sales to ...", so the two can't be
+// confused.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_rename<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let first = next_non_whitespace(tokens, 0);
+  let (kind, element, tokens) = match first {
+    Some(i) if matches!(tokens[i].value(), NOUN_EMPLOYEE | NOUN_PERSON) => {
+      (operation::RenameEmployee, Element::Name, &tokens[i + 1..])
+    }
+    Some(i) if tokens[i].value() == NOUN_DEPARTMENT && edition >= Edition::V2 => (
+      operation::RenameDepartment,
+      Element::Department,
+      &tokens[i + 1..],
+    ),
+    Some(i) if tokens[i].value() != LINKER_TO && edition >= Edition::V2 => {
+      (operation::RenameDepartment, Element::Department, tokens)
+    }
+    _ => {
+      return Err(Error::new(
+        ErrorKind::MissingNoun,
+        operation::RenameDepartment,
+        op_token,
+        first.map(|i| tokens[i]),
+        Some((&[NOUN_EMPLOYEE, NOUN_PERSON][..]).into()),
+        Some(Help::MissingNoun),
+      ))
+    }
+  };
+  let (old_name, i) = parse_single(kind, op_token, tokens, &[LINKER_TO], element, edition)?;
+  let tokens = &tokens[min(i + 1, tokens.len())..];
+  const TERMINATORS: [lexer::TokenValue; 2] = [SEPARATOR, SEPARATOR_FAIL_SILENTLY];
+  let (new_name, i) = parse_single(kind, op_token, tokens, &TERMINATORS, element, edition)?;
+  let op = match kind {
+    operation::RenameEmployee => Operation::rename_employee(old_name, new_name, silently),
+    _ => Operation::rename_department(old_name, new_name, silently),
+  };
+  handle_terminator(&tokens[min(i, tokens.len())..], op, op_token)
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_dissolve<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  const TERMINATORS: [lexer::TokenValue; 2] = [SEPARATOR, SEPARATOR_FAIL_SILENTLY];
+  let error_handler =
+    get_parse_list_error_handler_generator(operation::Dissolve, op_token, edition);
+  let (departments, reassign_to, tokens) = match find_reassignment(tokens) {
+    Some((start, end)) => {
+      // The departments are parsed as if the statement ended before the clause.
+      let mut department_tokens = tokens[..start].to_vec();
+      department_tokens.extend(tokens.last().filter(|t| is_terminator(t.value())));
+      let (departments, _) = parse_list(&department_tokens, &TERMINATORS)
+        .map_err(error_handler(&TERMINATORS, Element::Department))?;
+      let tokens = &tokens[end..];
+      let (department, i) = parse_single(
+        operation::Dissolve,
+        op_token,
+        tokens,
+        &TERMINATORS,
+        Element::Department,
+        edition,
+      )?;
+      (
+        departments,
+        Some(department),
+        &tokens[min(i, tokens.len())..],
+      )
+    }
+    None => {
+      let (departments, i) = parse_list(tokens, &TERMINATORS)
+        .map_err(error_handler(&TERMINATORS, Element::Department))?;
+      (departments, None, &tokens[min(i, tokens.len())..])
+    }
+  };
+  handle_terminator(
+    tokens,
+    Operation::dissolve(departments, reassign_to, silently),
+    op_token,
+  )
+}
+
+// Replaces the "everyone" that was parsed as a name with the wildcard it stands for.
+fn everyone_or<S>(names: operation::List<S>, everyone: bool) -> operation::NameList<S> {
+  match everyone {
+    true => operation::NameList::All,
+    false => names.into(),
+  }
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_move<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  const TERMINATORS: [lexer::TokenValue; 2] = [SEPARATOR, SEPARATOR_FAIL_SILENTLY];
+  let (tokens, except) = parse_except(operation::Move, op_token, tokens, edition)?;
+  let everyone = is_everyone(&tokens);
+  let error_handler = get_parse_list_error_handler_generator(operation::Move, op_token, edition);
+  let (names, i) =
+    parse_list(&tokens, &[LINKER_FROM]).map_err(error_handler(&[LINKER_FROM], Element::Name))?;
+  let tokens = &tokens[min(i + 1, tokens.len())..];
+  let (from, i) = parse_single(
+    operation::Move,
+    op_token,
+    tokens,
+    &[LINKER_TO],
+    Element::Department,
+    edition,
+  )?;
+  let tokens = &tokens[min(i + 1, tokens.len())..];
+  let (to, i) = parse_single(
+    operation::Move,
+    op_token,
+    tokens,
+    &TERMINATORS,
+    Element::Department,
+    edition,
+  )?;
+  handle_terminator(
+    &tokens[min(i, tokens.len())..],
+    Operation::move_except(everyone_or(names, everyone), from, to, silently, except),
+    op_token,
+  )
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
+fn parse_remove<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  silently: bool,
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let except_token = tokens.iter().find(|t| t.value() == LINKER_EXCEPT).copied();
+  let (tokens, except) = parse_except(operation::Remove, op_token, tokens, edition)?;
+  let everyone = edition >= Edition::V2 && is_everyone(&tokens);
+  let error_handler = get_parse_list_error_handler_generator(operation::Remove, op_token, edition);
+  const LIST_TERMINATORS: [lexer::TokenValue; 4] = [
+    LINKER_FROM,
+    SEPARATOR,
+    SEPARATOR_OVERWRITE,
+    SEPARATOR_FAIL_SILENTLY,
+  ];
+  let (first_list, i) = parse_list(&tokens, &LIST_TERMINATORS)
+    .map_err(error_handler(&TERMINATORS, Element::NameOrDepartment))?;
+  let (second_list, j) = parse_list(&tokens[min(i + 1, tokens.len())..], &TERMINATORS)
+    .map_err(error_handler(&TERMINATORS, Element::Department))
+    .unwrap_or_default();
+  let (names, departments, j) = if second_list.is_empty() {
+    (first_list, second_list, j)
+  } else {
+    (second_list, first_list, j + 1)
+  };
+  // Removing whole departments leaves no one to exclude.
+  if departments.is_empty() && !except.is_empty() {
+    return Err(Error::new(
+      ErrorKind::EmptyList,
+      operation::Remove,
+      op_token,
+      except_token,
+      Some((&[LINKER_FROM][..]).into()),
+      Some(Help::ExceptWithoutDepartments),
+    ));
+  }
+  handle_terminator(
+    &tokens[min(i + j, tokens.len())..],
+    Operation::remove_except(names, silently, everyone_or(departments, everyone), except),
+    op_token,
+  )
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all, fields(tokens = tokens.len()), err(level = "debug")))]
+pub fn parse(tokens: Vec<lexer::Token<'_>>) -> Result<Vec<Operation>, Error<'_>> {
+  parse_slice(&tokens)
+}
+
+fn parse_operation<'a>(
+  op_token: lexer::Token<'a>,
+  tokens: &[lexer::Token<'a>],
+  edition: Edition,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let kind = get_keyword(op_token.value()).or_else(|| get_compound_keyword(op_token.value()));
+  let v2 = edition >= Edition::V2;
+  match kind {
+    Some(kind @ (operation::Move | operation::Find)) if !v2 => {
+      return Err(unsupported(op_token, Syntax::Operation(kind)));
+    }
+    _ => {}
+  }
+  // Questions can't fail silently, so "silently" is part of the names they ask about.
+  let silently = find_silently(tokens).filter(|_| v2 && kind != Some(operation::Where));
+  let (tokens, silently): (Cow<[lexer::Token]>, _) = match silently {
+    Some((start, end)) => ([&tokens[..start], &tokens[end..]].concat().into(), true),
+    None => (tokens.into(), false),
+  };
+  // "starting" right before the terminator must start a clause, so a misspelled day isn't taken
+  // for a name, and so must the "starting" of another clause before it.
+  let (tokens, effective): (Cow<[lexer::Token]>, _) = match find_effective(&tokens) {
+    Some((start, end, effective)) => {
+      let kind = kind.unwrap_or(operation::Remove);
+      let effective = effective.map_err(|when| {
+        Error::new(
+          ErrorKind::InvalidEffective,
+          kind,
+          op_token,
+          Some(when),
+          None,
+          Some(Help::InvalidEffective(when.text().into())),
+        )
+      })?;
+      let tokens = [&tokens[..start], &tokens[end..]].concat();
+      if let Some((start, _, _)) = find_effective(&tokens) {
+        return Err(Error::new(
+          ErrorKind::DuplicateClause,
+          kind,
+          op_token,
+          Some(tokens[start]),
+          None,
+          Some(Help::DuplicateClause),
+        ));
+      }
+      (tokens.into(), Some(effective))
+    }
+    None => (tokens, None),
+  };
+  // Only the results of questions can be limited. Elsewhere the limit would end up in a name.
+  let limited = matches!(kind, Some(operation::Show | operation::Find));
+  if let Some((start, end, _)) = find_limit(&tokens).filter(|_| v2 && !limited) {
+    let kind = kind.unwrap_or(operation::Remove);
+    return Err(Error::new(
+      ErrorKind::MisplacedClause,
+      kind,
+      op_token,
+      clause_keyword(&tokens[start..end]),
+      None,
+      Some(Help::MisplacedLimit(kind)),
+    ));
+  }
+  if kind == Some(operation::RenameEmployee) {
+    return parse_rename(op_token, &tokens, silently, edition)
+      .map(|op| op.set_effective(effective));
+  }
+  let tokens = strip_nouns(&tokens);
+  let (tokens, aliases) = match kind {
+    Some(operation::Add) | Some(operation::Remove) => strip_aliases(&tokens).map_err(|also| {
+      Error::new(
+        ErrorKind::MisplacedAlias,
+        kind.unwrap_or(operation::Remove),
+        op_token,
+        Some(also),
+        None,
+        Some(Help::MisplacedAlias),
+      )
+    })?,
+    _ => (tokens.as_ref().into(), Vec::new()),
+  };
+  let op = match kind {
+    Some(operation::Add) => parse_add(op_token, &tokens, silently, edition),
+    Some(operation::Create) => parse_create(op_token, &tokens, silently, edition),
+    Some(operation::Show) => parse_show(op_token, &tokens, silently, edition),
+    Some(operation::Where) => parse_where(op_token, &tokens, edition),
+    Some(operation::Dissolve) => parse_dissolve(op_token, &tokens, silently, edition),
+    Some(operation::Move) => parse_move(op_token, &tokens, silently, edition),
+    Some(operation::Find) => parse_find(op_token, &tokens, silently, edition),
+    _ => parse_remove(op_token, &tokens, silently, edition),
+  }?
+  .set_effective(effective);
+  Ok(op.clone().set_aliases(aliases).unwrap_or(op))
+}
+
+pub fn parse_iter<'a, I>(tokens: I) -> ParseIter<'a, I::IntoIter>
+where
+  I: IntoIterator<Item = Result<lexer::Token<'a>, lexer::Error<'a>>>,
+{
+  ParseIter::new(tokens.into_iter())
+}
+
+pub fn parse_slice<'a>(tokens: &[lexer::Token<'a>]) -> Result<Vec<Operation>, Error<'a>> {
+  parse_slice_map(tokens, Cow::into_owned)
+}
+
+// Like parse_slice, but every name is passed to f as soon as its operation is parsed, borrowed from
+// the tokens where it can be, so it can be e.g. interned without being copied first.
+pub fn parse_slice_map<'a, S>(
+  tokens: &[lexer::Token<'a>],
+  mut f: impl FnMut(Cow<'a, str>) -> S,
+) -> Result<Vec<Operation<S>>, Error<'a>> {
+  let mut res = Vec::new();
+  parse_into_map(
+    tokens,
+    &mut Definitions::new(),
+    Edition::LATEST,
+    &mut res,
+    &mut f,
+  )?;
+  Ok(res)
+}
+
+fn parse_into<'a>(
+  tokens: &[lexer::Token<'a>],
+  definitions: &mut Definitions<'a>,
+  edition: Edition,
+  res: &mut Vec<Operation>,
+) -> Result<(), Error<'a>> {
+  parse_into_map(tokens, definitions, edition, res, &mut Cow::into_owned)
+}
+
+fn parse_into_map<'a, S>(
+  tokens: &[lexer::Token<'a>],
+  definitions: &mut Definitions<'a>,
+  edition: Edition,
+  res: &mut Vec<Operation<S>>,
+  f: &mut impl FnMut(Cow<'a, str>) -> S,
+) -> Result<(), Error<'a>> {
+  // Comments are trivia, so they are dropped before the statement is looked at. Synonyms of the
+  // keywords are resolved here, so that every entry point accepts them.
+  let synonyms = edition >= Edition::V2 && tokens.iter().any(|t| get_synonym(t.value()).is_some());
+  let tokens: Cow<[lexer::Token<'a>]> =
+    match synonyms || tokens.iter().any(|t| t.value().is_comment()) {
+      true => tokens
+        .iter()
+        .filter(|t| !t.value().is_comment())
+        .map(|t| match synonyms {
+          true => t.resolve_synonym(),
+          false => *t,
+        })
+        .collect::<Vec<_>>()
+        .into(),
+      false => tokens.into(),
+    };
+  let tokens = definitions.expand(&tokens, edition)?;
+
+  let mut i = 0;
+  while i < tokens.len() {
+    let token = tokens[i];
+    match token.value() {
+      lexer::Whitespace => {}
+      KEYWORD_ADD | KEYWORD_CREATE | KEYWORD_SHOW | KEYWORD_REMOVE | KEYWORD_WHERE
+      | KEYWORD_RENAME | KEYWORD_DISSOLVE | KEYWORD_MOVE | KEYWORD_FIND => {
+        let op_tokens = get_operation_tokens(&tokens[i + 1..]);
+        i += op_tokens.len();
+        parse_compound(token, op_tokens, None, edition, res, f)?;
+      }
+      CONDITION_IF => {
+        let op_tokens = get_operation_tokens(&tokens[i + 1..]);
+        i += op_tokens.len();
+        parse_conditional(token, op_tokens, edition, res, f)?;
+      }
+      _ => {
+        return Err(Error::new(
+          ErrorKind::MissingOperation,
+          operation::Unknown,
+          token,
+          Some(token),
+          Some(match edition >= Edition::V2 {
+            true => (&KEYWORDS_AND_SYNONYMS[..]).into(),
+            false => (&KEYWORDS[..]).into(),
+          }),
+          Some(Help::MissingOperation),
+        ))
+      }
+    }
+    i += 1;
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util;
+
+  fn parse_v1(source: &str) -> Result<Vec<Operation>, crate::Error<'_>> {
+    Parser::with_options(ParserOptions::new().set_edition(Edition::V1)).parse(source)
+  }
+
+  #[test]
+  fn test_parse_add() {
+    let test_sources = &[
+      "Add Mihai, Andrei and Ioan to Science and Engineering!",
+      "Add Mihai, Andrei and Ioan to Science and Engineering",
+      "Add Mihai, Andrei and Ioan to .",
+      "Add Mihai, Andrei and Ioan.",
+      "Add to?",
+      "Add Mihai",
+      "Add!",
+    ];
+    type FnExpect = fn(Result<Operation, Error>) -> bool;
+    let expect: &[FnExpect] = &[
+      |res| {
+        res
+          == Ok(Operation::add(
+            util::to_string_vec(vec!["Science", "Engineering"]),
+            false,
+            util::to_string_vec(vec!["Mihai", "Andrei", "Ioan"]),
+            true,
+          ))
+      },
+      |res| res.is_err(),
+      |res| res.is_err(),
+      |res| res.is_err(),
+      |res| res.is_err(),
+      |res| res.is_err(),
+      |res| res.is_err(),
+    ];
+    test_sources
+      .iter()
+      .map(|s| lexer::lex(s).unwrap())
+      .map(|t| {
+        parse_add(t[0], &t[1..], false, Edition::LATEST).map(|op| op.into_map(Cow::into_owned))
+      })
+      .zip(expect.iter())
+      .for_each(|(res, f)| assert!(f(res)));
+  }
+
+  #[test]
+  fn test_parse_create() {
+    let tokens = lexer::lex("Create Science, Engineering and Physics.").unwrap();
+    let expect = Operation::create(
+      util::to_string_vec(vec!["Science", "Engineering", "Physics"]),
+      false,
+      false,
+    );
+    let got = parse(tokens).unwrap();
+    assert_eq!(got[0], expect);
+  }
+
+  #[test]
+  fn test_parse_show() {
+    let tokens = lexer::lex("Show Science, Engineering and Physics?").unwrap();
+    let expect = Operation::show(
+      util::to_string_vec(vec!["Science", "Engineering", "Physics"]),
+      true,
+    );
+    let got = parse(tokens).unwrap();
+    assert_eq!(got[0], expect);
+  }
+
+  #[test]
+  fn test_parse_remove() {
+    let source = vec!["Remove Science.", "Remove Michael from Physics?"];
+    let expect = vec![
+      Operation::remove(util::to_string_vec(vec!["Science"]), false, vec![]),
+      Operation::remove(
+        util::to_string_vec(vec!["Physics"]),
+        true,
+        util::to_string_vec(vec!["Michael"]),
+      ),
+    ];
+    source
+      .into_iter()
+      .map(|s| parse(lexer::lex(s).unwrap()).unwrap())
+      .zip(expect)
+      .for_each(|(got, expected)| assert_eq!(got[0], expected));
+  }
+
+  #[test]
+  fn test_parse_definitions() {
+    let source = "Define team Alpha as Mihai, Ana and Ioan.
+Define team Beta Two as team Alpha and Maria.
+Add team Beta Two to HR. Remove team Alpha from Sales?";
+    let expect = vec![
+      Operation::add(
+        util::to_string_vec(vec!["HR"]),
+        false,
+        util::to_string_vec(vec!["Mihai", "Ana", "Ioan", "Maria"]),
+        false,
+      ),
+      Operation::remove(
+        util::to_string_vec(vec!["Sales"]),
+        true,
+        util::to_string_vec(vec!["Mihai", "Ana", "Ioan"]),
+      ),
+    ];
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn test_parse_slice_map() {
+    // Only the names of more than one word are copied before they are passed on.
+    let tokens = lexer::lex("Add Mihai and Ana Maria to HR. Create Sales, then show it.").unwrap();
+    let got = parse_slice_map(&tokens, |name| matches!(name, Cow::Borrowed(_))).unwrap();
+    assert_eq!(got[0].names(), &[true, false]);
+    assert_eq!(got[0].departments(), &[true]);
+    assert_eq!(got[2].departments(), &[true]);
+  }
+
+  #[test]
+  fn test_parse_compound() {
+    let source = "Create HR and Sales!
+Create PR, then add Mihai and Ana to it, then show it and HR. Create Sales, Then.";
+    let expect = "Create HR and Sales! Create PR. Add Mihai and Ana to PR. Show PR and HR. \
+Create Sales and Then.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(got, parse(lexer::lex(expect).unwrap()).unwrap());
+
+    let got = parse(lexer::lex("Show HR, then add Mihai to it?").unwrap()).unwrap();
+    assert!(got.iter().all(|op| op.fail_silently()));
+    assert!(parse(lexer::lex("Create HR, then add to it.").unwrap()).is_err());
+
+    let got = parse(lexer::lex("Create HR then add Mihai to it.").unwrap()).unwrap();
+    let expect = parse(lexer::lex("Create HR. Add Mihai to HR.").unwrap()).unwrap();
+    assert_eq!(got, expect);
+    let got = parse(lexer::lex("Create Then and Now.").unwrap()).unwrap();
+    assert_eq!(got[0].departments(), &["Then", "Now"]);
+  }
+
+  #[test]
+  fn test_parse_conditional() {
+    let source = "If Science exists, add Mihai to Science.
+If HR and PR do not exist, create it, then show it?
+Define team Alpha as Ana and Ioan. If Sales exists, add team Alpha to it!";
+    let exists = |departments: Vec<&str>, exists| {
+      Some(operation::Condition {
+        departments: util::to_string_vec(departments).into(),
+        exists,
+      })
+    };
+    let expect = vec![
+      Operation::add(
+        util::to_string_vec(vec!["Science"]),
+        false,
+        util::to_string_vec(vec!["Mihai"]),
+        false,
+      )
+      .set_condition(exists(vec!["Science"], true)),
+      Operation::create(util::to_string_vec(vec!["HR", "PR"]), true, false)
+        .set_condition(exists(vec!["HR", "PR"], false)),
+      Operation::show(util::to_string_vec(vec!["HR", "PR"]), true)
+        .set_condition(exists(vec!["HR", "PR"], false)),
+      Operation::add(
+        util::to_string_vec(vec!["Sales"]),
+        false,
+        util::to_string_vec(vec!["Ana", "Ioan"]),
+        true,
+      )
+      .set_condition(exists(vec!["Sales"], true)),
+    ];
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(got, expect);
+
+    let invalid = &[
+      "If Science, add Mihai to Science.",
+      "If Science exists add Mihai to Science.",
+      "If Science does exist, add Mihai to Science.",
+      "If Science exists, Mihai is in Science.",
+      "If exists, show HR.",
+    ];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+  }
+
+  #[test]
+  fn test_parse_effective() {
+    let source = "Add Mihai to HR starting Monday. Create Starting Point starting Friday!
+Remove Ana from HR, then show it starting Sunday? Show starting Monday and HR.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    let effective: Vec<_> = got.iter().map(Operation::get_effective).collect();
+    let day = |day| Some(operation::Effective::Day(day));
+    assert_eq!(
+      effective,
+      vec![
+        day(operation::Weekday::Monday),
+        day(operation::Weekday::Friday),
+        None,
+        day(operation::Weekday::Sunday),
+        None
+      ]
+    );
+    assert_eq!(got[1].departments(), &["Starting Point"]);
+    assert_eq!(got[4].departments(), &["starting Monday", "HR"]);
+    let got = parse(lexer::lex("Show HR starting.").unwrap()).unwrap();
+    assert_eq!(got[0].departments(), &["HR starting"]);
+
+    let got = parse(lexer::lex("Add Ana to HR starting 2024-02-29.").unwrap()).unwrap();
+    let date = operation::Effective::Date {
+      year: 2024,
+      month: 2,
+      day: 29,
+    };
+    assert_eq!(got[0].get_effective(), Some(date));
+    assert_eq!(got[0].departments(), &["HR"]);
+    assert_eq!(got[0].to_string(), "Add Ana to HR starting 2024-02-29");
+    for (source, kind, at) in &[
+      (
+        "Add Ana to HR starting Funday.",
+        ErrorKind::InvalidEffective,
+        "Funday",
+      ),
+      (
+        "Add Ana to HR starting 2023-02-29.",
+        ErrorKind::InvalidEffective,
+        "2023-02-29",
+      ),
+      (
+        "Add Ana to HR starting Monday starting Tuesday.",
+        ErrorKind::DuplicateClause,
+        "starting",
+      ),
+    ] {
+      let err = parse(lexer::lex(source).unwrap()).unwrap_err();
+      assert_eq!((err.kind(), err.token().text()), (*kind, *at), "{}", source);
+    }
+    let err = parse(lexer::lex("Add Ana to HR starting Funday.").unwrap()).unwrap_err();
+    assert_eq!(err.help(), Some(&Help::InvalidEffective("Funday".into())));
+  }
+
+  #[test]
+  fn test_parse_aliases() {
+    let source = "Add Mihai, also known as Mike, Ana and Ioan Pop, also known as John, to HR.
+Remove Ana, also known as Anna Maria, and Mihai from HR.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    let alias = |name: &str, alias: &str| operation::Alias {
+      name: name.into(),
+      alias: alias.into(),
+    };
+    assert_eq!(got[0].names(), &["Mihai", "Ana", "Ioan Pop"]);
+    assert_eq!(
+      got[0].aliases(),
+      &[alias("Mihai", "Mike"), alias("Ioan Pop", "John")]
+    );
+    assert_eq!(got[1].names(), &["Ana", "Mihai"]);
+    assert_eq!(got[1].aliases(), &[alias("Ana", "Anna Maria")]);
+    assert_eq!(
+      got[0].aliases_of("Ioan Pop").collect::<Vec<_>>(),
+      vec!["John"]
+    );
+    let got = parse(lexer::lex("Add Mihai, also known as Mike to HR.").unwrap()).unwrap();
+    assert_eq!(got[0].names(), &["Mihai"]);
+    assert_eq!(got[0].aliases(), &[alias("Mihai", "Mike")]);
+
+    [
+      "Remove Mihai from HR, also known as Human Resources.",
+      "Add Mihai, also known as Mike, to HR, also known as People.",
+      "Remove Sales, also known as Business.",
+    ]
+    .iter()
+    .for_each(|s| {
+      let err = parse(lexer::lex(s).unwrap()).unwrap_err();
+      assert_eq!(err.kind(), ErrorKind::MisplacedAlias, "{}", s);
+      assert_eq!(err.token().text(), "also", "{}", s);
+    });
+  }
+
+  #[test]
+  fn test_parse_nouns() {
+    let source = "Add Mihai to the departments Science and Maths. Create departments HR and PR.
+Remove the employee Ana from department HR? Show the Department of Hats.";
+    let expect = "Add Mihai to Science and Maths. Create HR and PR.
+Remove Ana from HR? Show the Department of Hats.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(got, parse(lexer::lex(expect).unwrap()).unwrap());
+  }
+
+  #[test]
+  fn test_parse_where() {
+    let got =
+      parse(lexer::lex("Where is Mihai? Where are the employees Ana and Ioan.").unwrap()).unwrap();
+    let expect = vec![
+      Operation::where_is(util::to_string_vec(vec!["Mihai"])),
+      Operation::where_is(util::to_string_vec(vec!["Ana", "Ioan"])),
+    ];
+    assert_eq!(got, expect);
+
+    let invalid = &[
+      "Where Mihai?",
+      "Where is?",
+      "Where is Mihai!",
+      "Where is Mihai",
+    ];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+  }
+
+  #[test]
+  fn test_parse_show_in_detail() {
+    let source = "Show HR in detail. Show HR and PR in detail starting Monday? Show Sales.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    let detailed: Vec<_> = got.iter().map(Operation::detailed).collect();
+    assert_eq!(detailed, vec![true, true, false]);
+    assert_eq!(got[1].departments(), &["HR", "PR"]);
+    assert!(got[1].get_effective().is_some());
+    assert!(parse(lexer::lex("Show in detail.").unwrap()).is_err());
+  }
+
+  #[test]
+  fn test_parse_show_all() {
+    let source = "Show all departments. Show everything in detail? Show all. Show \"all\".";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(got[0], Operation::show_all(false));
+    assert_eq!(got[0].get_departments(), None);
+    assert!(!Operation::show(operation::List::<String>::new(), false).shows_all());
+    assert!(got[1].shows_all() && got[1].detailed() && got[1].fail_silently());
+    assert!(got[2].shows_all());
+    assert_eq!(got[3].departments(), &["all"]);
+    assert!(!got[3].shows_all());
+    assert_eq!(
+      parse(lexer::lex("Show all and HR.").unwrap()).unwrap()[0].departments(),
+      &["all", "HR"]
+    );
+    assert!(parse(lexer::lex("Show all departments").unwrap()).is_err());
+    assert!(parse(lexer::lex("Show.").unwrap()).is_err());
+    assert_eq!(parse_v1("Show all.").unwrap()[0].departments(), &["all"]);
+  }
+
+  #[test]
+  fn test_parse_show_ordered() {
+    let source = "Show Sales ordered by name. Show HR ordered by name descending in detail? \
+Show HR and PR ordered by name ascending.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    let ordering = |descending| Some(operation::Ordering::Name { descending });
+    assert_eq!(
+      got[0],
+      Operation::show(util::to_string_vec(vec!["Sales"]), false)
+        .set_ordering(ordering(false))
+        .unwrap()
+    );
+    assert_eq!(got[1].get_ordering(), ordering(true));
+    assert!(got[1].detailed() && got[1].fail_silently());
+    assert_eq!(got[2].departments(), &["HR", "PR"]);
+    assert_eq!(got[2].get_ordering(), ordering(false));
+
+    let got = parse(lexer::lex("Show Ordered By Name. Show HR ordered by age.").unwrap()).unwrap();
+    assert_eq!(got[0].get_ordering(), None);
+    assert_eq!(got[1].departments(), &["HR ordered by age"]);
+    assert!(parse(lexer::lex("Show ordered by name.").unwrap()).is_err());
+    let got = parse_v1("Show HR ordered by name.").unwrap();
+    assert_eq!(got[0].departments(), &["HR ordered by name"]);
+    assert_eq!(got[0].get_ordering(), None);
+  }
+
+  #[test]
+  fn test_parse_limit() {
+    let source = "Show Engineering, first 10. Show HR limit 3 in detail? Show Sales ordered by name, first 5. Find Mihai and Ana, first 1.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::show(util::to_string_vec(vec!["Engineering"]), false)
+        .set_limit(Some(10))
+        .unwrap()
+    );
+    assert_eq!(got[1].get_limit(), Some(3));
+    assert!(got[1].detailed() && got[1].fail_silently());
+    assert_eq!(got[2].get_limit(), Some(5));
+    assert!(got[2].get_ordering().is_some());
+    assert_eq!(got[3].names(), &["Mihai", "Ana"]);
+    assert_eq!(got[3].get_limit(), Some(1));
+
+    let got = parse(lexer::lex("Show First. Find Mihai first.").unwrap()).unwrap();
+    assert_eq!(got[0].get_limit(), None);
+    assert_eq!(got[1].names(), &["Mihai first"]);
+    assert!(parse(lexer::lex("Show first 10.").unwrap()).is_err());
+    assert!(Operation::create(Vec::<String>::new(), false, false)
+      .set_limit(Some(1))
+      .is_none());
+    let err = parse_v1("Show HR, first 10.").unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::UnsupportedSyntax));
+
+    let got =
+      parse(lexer::lex("Show HR, first 2 ordered by name. Show PR in detail, limit 4.").unwrap())
+        .unwrap();
+    assert_eq!(got[0].departments(), &["HR"]);
+    assert_eq!(got[0].get_limit(), Some(2));
+    assert!(got[0].get_ordering().is_some());
+    assert_eq!(got[1].departments(), &["PR"]);
+    assert!(got[1].detailed() && got[1].get_limit() == Some(4));
+    let err = parse(lexer::lex("Show HR, first 99999999999999999999999.").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLimit);
+    assert_eq!(err.token().text(), "99999999999999999999999");
+    let err = parse(lexer::lex("Show HR, first 0.").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidLimit);
+    assert_eq!(err.help(), Some(&Help::LimitZero));
+    let err = parse(lexer::lex("Show HR, first 3-4.").unwrap()).unwrap_err();
+    assert_eq!((err.kind(), err.help()), (ErrorKind::InvalidLimit, None));
+    let err = parse(lexer::lex("Find Ana limit 00.").unwrap()).unwrap_err();
+    assert_eq!(err.token().text(), "00");
+    let err = parse(lexer::lex("Show HR, first 10, first 3.").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicateClause);
+    assert_eq!(err.token().text(), "first");
+    let err = parse(lexer::lex("Find Ana limit 1, first 3.").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::DuplicateClause);
+
+    let got = parse(lexer::lex("Show HR, first 10, ordered by name. Show IT, in detail.").unwrap());
+    let got = got.unwrap();
+    assert_eq!(got[0].get_departments().unwrap()[..], ["HR"]);
+    assert_eq!(got[0].get_limit(), Some(10));
+    assert_eq!(
+      got[0].get_ordering(),
+      Some(operation::Ordering::Name { descending: false })
+    );
+    assert_eq!(got[1].get_departments().unwrap()[..], ["IT"]);
+    assert!(got[1].detailed());
+    for source in &["Add Mihai to HR, first 3.", "Where is Ana, limit 2?"] {
+      let err = parse(lexer::lex(source).unwrap()).unwrap_err();
+      assert_eq!(err.kind(), ErrorKind::MisplacedClause, "{}", source);
+    }
+    assert_eq!(
+      parse_v1("Add Mihai to HR first 3.").unwrap_err().kind(),
+      Some(ErrorKind::UnsupportedSyntax)
+    );
+  }
+
+  #[test]
+  fn test_parse_rename_employee() {
+    let source =
+      "Rename employee Mihai to Mihai Popescu. Rename person Ana to Ana Pop starting Monday?";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::rename_employee("Mihai".into(), "Mihai Popescu".into(), false)
+    );
+    assert_eq!(got[1].names(), &["Ana"]);
+    assert_eq!(got[1].new_name(), "Ana Pop");
+    assert!(got[1].fail_silently() && got[1].get_effective().is_some());
+
+    let invalid = &[
+      "Rename employee Mihai and Ana to Mihai Popescu.",
+      "Rename employee Mihai to Mihai, Popescu.",
+      "Rename employee Mihai.",
+      "Rename employee Mihai to Mihai Popescu!",
+    ];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+  }
+
+  #[test]
+  fn test_parse_everyone() {
+    let source =
+      "Remove everyone from Sales. Move everyone from HR to PR? Remove \"everyone\" from HR.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::remove_everyone(util::to_string_vec(vec!["Sales"]), false)
+    );
+    assert!(!got[0].removes_departments());
+    assert!(got[1].names().is_all() && !got[1].names().is_empty());
+    assert_eq!(got[1].get_reassign_to(), Some("PR"));
+    assert!(!got[2].names().is_all());
+    assert_eq!(got[2].names(), &["everyone"]);
+    assert_eq!(got[2].to_source(), "Remove \"everyone\" from HR.");
+
+    let got = parse(lexer::lex("Remove everyone and Ana from HR. Remove Sales.").unwrap()).unwrap();
+    assert_eq!(got[0].names(), &["everyone", "Ana"]);
+    assert!(got[1].removes_departments());
+    let got = parse_v1("Remove everyone from Sales.").unwrap();
+    assert_eq!(got[0].names(), &["everyone"]);
+    assert!(!got[0].names().is_all());
+  }
+
+  #[test]
+  fn test_parse_except() {
+    let source = "Remove everyone except Mihai from Sales. Add Ana, Ioan except Ioan to HR! \
+Show HR except Ana and Ioan in detail?";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::remove_everyone(util::to_string_vec(vec!["Sales"]), false)
+        .set_except(util::to_string_vec(vec!["Mihai"]))
+        .unwrap()
+    );
+    assert_eq!(got[1].names(), &["Ana", "Ioan"]);
+    assert_eq!(got[1].except(), &["Ioan"]);
+    assert!(got[1].overwrite());
+    assert_eq!(got[2].departments(), &["HR"]);
+    assert_eq!(got[2].except(), &["Ana", "Ioan"]);
+    assert!(got[2].detailed());
+
+    let invalid = &[
+      "Remove Sales except Mihai.",
+      "Remove everyone except from Sales.",
+      "Add Ana except Ana.",
+    ];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+    let got = parse_v1("Add Ana except to HR. Add Mihai, Ana to HR except Ana.").unwrap();
+    assert_eq!(got[0].names(), &["Ana except"]);
+    assert_eq!(got[1].departments(), &["HR except Ana"]);
+    assert!(got[1].except().is_empty());
+  }
+
+  #[test]
+  fn test_parse_silently() {
+    let source = "Add Ana to HR silently! Create HR starting Monday silently! Show HR silently.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::add(
+        util::to_string_vec(vec!["HR"]),
+        true,
+        util::to_string_vec(vec!["Ana"]),
+        true
+      )
+    );
+    assert!(got[1].overwrite() && got[1].fail_silently() && got[1].get_effective().is_some());
+    assert_eq!(got[1].departments(), &["HR"]);
+    assert!(got[2].fail_silently());
+
+    let got = parse(lexer::lex("Where is Mihai silently? Add Mihai silently Pop to HR.").unwrap());
+    let got = got.unwrap();
+    assert_eq!(got[0].names(), &["Mihai silently"]);
+    assert_eq!(got[1].names(), &["Mihai silently Pop"]);
+    assert!(parse(lexer::lex("Show HR silently!").unwrap()).is_err());
+    let got = parse_v1("Create Sales silently. Add Mihai to HR silently.").unwrap();
+    assert_eq!(got[0].departments(), &["Sales silently"]);
+    assert!(!got[0].fail_silently());
+    assert_eq!(got[1].departments(), &["HR silently"]);
+  }
+
+  #[test]
+  fn test_parse_synonyms() {
+    let source = "Insert Ana to HR. Delete Sales. List HR? Display HR and PR.";
+    let got: Vec<Operation> = parse_iter(lexer::Lexer::new(source))
+      .collect::<Result<_, _>>()
+      .unwrap();
+    let canonical = "Add Ana to HR. Remove Sales. Show HR? Show HR and PR.";
+    assert_eq!(got, parse(lexer::lex(canonical).unwrap()).unwrap());
+    assert_eq!(got[1].to_source(), "Remove Sales.");
+    assert_eq!(parse(lexer::lex(source).unwrap()).unwrap(), got);
+    assert_eq!(
+      parse_slice(&lexer::lex("Delete Sales.").unwrap()).unwrap()[0],
+      got[1]
+    );
+    let err = parse(lexer::lex("Erase Sales.").unwrap()).unwrap_err();
+    assert!(
+      err.to_string().contains(r#"keyword token "Delete""#),
+      "{}",
+      err
+    );
+
+    let options = ParserOptions::new().set_edition(Edition::V1);
+    let err = parse_iter(lexer::Lexer::new(source))
+      .with_options(options)
+      .next()
+      .unwrap()
+      .unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::MissingOperation));
+  }
+
+  #[test]
+  fn test_parse_find() {
+    let source = "Find Mihai. Find Mihai and Andrei? Find the employees Ana, Ioan.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::find(util::to_string_vec(vec!["Mihai"]), false)
+    );
+    assert_eq!(got[1].names(), &["Mihai", "Andrei"]);
+    assert!(got[1].fail_silently());
+    assert_eq!(got[2].names(), &["Ana", "Ioan"]);
+
+    let invalid = &["Find.", "Find Mihai!", "Find Mihai"];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+    let err = parse_v1("Find Mihai?").unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::UnsupportedSyntax));
+  }
+
+  #[test]
+  fn test_parse_move() {
+    let source =
+      "Move Mihai and Ioan from Sales to Engineering. Move the employee Ana from HR to PR?";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::move_between(
+        util::to_string_vec(vec!["Mihai", "Ioan"]),
+        "Sales".into(),
+        "Engineering".into(),
+        false
+      )
+    );
+    assert_eq!(got[1].names(), &["Ana"]);
+    assert_eq!(got[1].departments(), &["HR"]);
+    assert_eq!(got[1].get_reassign_to(), Some("PR"));
+    assert!(got[1].fail_silently());
+
+    let invalid = &[
+      "Move Mihai to Engineering.",
+      "Move Mihai from Sales.",
+      "Move from Sales to Engineering.",
+      "Move Mihai from Sales and HR to Engineering.",
+      "Move Mihai from Sales to Engineering and HR.",
+      "Move Mihai from Sales to Engineering!",
+    ];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+    let err = parse_v1("Move Mihai from Sales to Engineering.").unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::UnsupportedSyntax));
+  }
+
+  #[test]
+  fn test_parse_rename_department() {
+    let source = "Rename Sales to Business Development. Rename \"R and D\" to Research? \
+Rename department HR to People.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got[0],
+      Operation::rename_department("Sales".into(), "Business Development".into(), false)
+    );
+    assert_eq!(got[1].departments(), &["R and D"]);
+    assert_eq!(got[1].new_name(), "Research");
+    assert!(got[1].fail_silently());
+    assert_eq!(got[2].departments(), &["HR"]);
+    assert_eq!(got[2].new_name(), "People");
+
+    let err = parse(lexer::lex("Rename to Research.").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::MissingNoun);
+    let invalid = &[
+      "Rename Sales and HR to Business.",
+      "Rename Sales.",
+      "Rename department to Business.",
+      "Rename Sales to Business!",
+    ];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+    let err = parse_v1("Rename Sales to Business Development.").unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::MissingNoun));
+    assert!(parse_v1("Rename employee Mihai to Mihai Popescu.").is_ok());
+  }
+
+  #[test]
+  fn test_parse_dissolve() {
+    let source =
+      "Dissolve HR and move everyone to General. Dissolve HR and PR and move everyone to Sales?
+Dissolve the department Sales.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    let expect = vec![
+      Operation::dissolve(
+        util::to_string_vec(vec!["HR"]),
+        Some("General".into()),
+        false,
+      ),
+      Operation::dissolve(
+        util::to_string_vec(vec!["HR", "PR"]),
+        Some("Sales".into()),
+        true,
+      ),
+      Operation::dissolve(util::to_string_vec(vec!["Sales"]), None, false),
+    ];
+    assert_eq!(got, expect);
+
+    let invalid = &[
+      "Dissolve and move everyone to General.",
+      "Dissolve HR and move everyone to.",
+      "Dissolve HR and move everyone to General and Sales.",
+      "Dissolve HR!",
+    ];
+    invalid
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+  }
+
+  #[test]
+  fn test_parse_iter() {
+    let source = "Define team Alpha as Mihai and Ana.
+Create HR, Sales. Add team Alpha to HR!
+Remove Ana from HR?   Show HR.
+Create PR, then add Ana to it.
+";
+    let got: Vec<Operation> = parse_iter(lexer::Lexer::new(source))
+      .collect::<Result<_, _>>()
+      .unwrap();
+    assert_eq!(got, parse(lexer::lex(source).unwrap()).unwrap());
+  }
+
+  #[test]
+  fn test_parse_iter_errors() {
+    let mut ops = parse_iter(lexer::Lexer::new("Create HR. Show $. Show HR."));
+    assert!(matches!(ops.next(), Some(Ok(_))));
+    assert!(matches!(ops.next(), Some(Err(crate::Error::Lex(_)))));
+    assert_eq!(ops.next(), None);
+
+    let mut ops = parse_iter(lexer::Lexer::new("Create HR. Show HR"));
+    assert!(matches!(ops.next(), Some(Ok(_))));
+    assert!(matches!(ops.next(), Some(Err(crate::Error::Parse(_)))));
+    assert_eq!(ops.next(), None);
+  }
+
+  #[test]
+  fn test_parse_definitions_errors() {
+    let sources = &[
+      "Add team Alpha to HR.",
+      "Define Alpha as Mihai.",
+      "Define team as Mihai.",
+      "Define team Alpha Mihai.",
+      "Define team Alpha as.",
+      "Define team Alpha as Mihai!",
+    ];
+    sources
+      .iter()
+      .for_each(|s| assert!(parse(lexer::lex(s).unwrap()).is_err(), "{}", s));
+  }
+
+  #[test]
+  fn test_parse_quoted() {
+    let source = "Add \"Mary and Sons\", Ana and \"and\" to \"Show\".";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    let expect = Operation::add(
+      util::to_string_vec(vec!["Show"]),
+      false,
+      util::to_string_vec(vec!["Mary and Sons", "Ana", "and"]),
+      false,
+    );
+    assert_eq!(got, vec![expect]);
+
+    let options = ParserOptions::new().set_edition(Edition::V1);
+    let err = parse_iter(lexer::Lexer::new(source))
+      .with_options(options)
+      .next()
+      .unwrap()
+      .unwrap_err();
+    assert_eq!(err.kind(), Some(ErrorKind::UnsupportedSyntax));
+    assert!(Parser::with_options(options).parse(source).is_err());
+    assert!(Parser::new().parse(source).is_ok());
+  }
+  #[test]
+  fn test_parse_numbers() {
+    let source = "Create Team 42, Floor 3. Add Agent 007 to Team 42.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(got[0].departments(), &["Team 42", "Floor 3"]);
+    assert_eq!(got[1].names(), &["Agent 007"]);
+    assert_eq!(got[0].to_source(), "Create Team 42 and Floor 3.");
+  }
+  #[test]
+  fn test_parse_comments() {
+    let source = "# Onboarding.
+Create HR. -- the new department!
+Add Mihai # and only Mihai.
+  to HR.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(
+      got,
+      parse(lexer::lex("Create HR. Add Mihai to HR.").unwrap()).unwrap()
+    );
+    let got: Vec<Operation> = parse_iter(lexer::Lexer::new(source))
+      .collect::<Result<_, _>>()
+      .unwrap();
+    assert_eq!(got.len(), 2);
+    assert_eq!(parse_statements(source).unwrap().statements.len(), 2);
+  }
+  #[test]
+  fn test_parse_case_insensitive() {
+    let source = "add mihai TO Sales. IF HR Does NOT exist, create HR, THEN Add Ana to IT.";
+    let options = ParserOptions::new().set_case_insensitive(true);
+    let got: Vec<Operation> = parse_iter(lexer::Lexer::new(source))
+      .with_options(options)
+      .collect::<Result<_, _>>()
+      .unwrap();
+    let expect = "Add mihai to Sales. If HR does not exist, create HR, then add Ana to IT.";
+    assert_eq!(got, parse(lexer::lex(expect).unwrap()).unwrap());
+    let got = Parser::with_options(options)
+      .parse("create It, THEN add Ana to it. SHOW First, LIMIT 2.")
+      .unwrap();
+    assert_eq!(got[0].departments(), &["It"]);
+    assert_eq!(got[1].departments(), &["It"]);
+    assert_eq!(got[2].departments(), &["First", "LIMIT 2"]);
+    assert_eq!(got[2].get_limit(), None);
+    assert!(Parser::new().parse(source).is_err());
+
+    let err = Parser::with_options(options)
+      .parse("add Mihai TO SHOW")
+      .unwrap_err();
+    assert!(
+      err
+        .to_string()
+        .contains(r#"Can't use keyword token "SHOW""#),
+      "{}",
+      err
+    );
+  }
+  #[cfg(feature = "nfc")]
+  #[test]
+  fn test_parse_normalized() {
+    let composed = parse(lexer::lex("Add Ștefan to Vânzări.").unwrap()).unwrap();
+    let decomposed = parse(lexer::lex("Add S\u{326}tefan to Va\u{302}nza\u{306}ri.").unwrap());
+    assert_eq!(decomposed.unwrap(), composed);
+    // Marks are put in canonical order before they are composed.
+    let source = "Add Vie\u{302}\u{323}t and \"Vie\u{323}\u{302}t\" to HR.";
+    let got = parse(lexer::lex(source).unwrap()).unwrap();
+    assert_eq!(got[0].names(), &["Việt", "Việt"]);
+  }
+  // TODO: more tests
+}
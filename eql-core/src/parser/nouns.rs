@@ -0,0 +1,45 @@
+use super::constants::*;
+use super::util::next_non_whitespace;
+use crate::lexer;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+// Returns the range of the "the departments" or "employee" marker at the given index, if there is
+// one.
+fn find_noun(tokens: &[lexer::Token], from: usize) -> Option<(usize, usize)> {
+  let start = next_non_whitespace(tokens, from)?;
+  let noun = match tokens[start].value() {
+    NOUN_THE => next_non_whitespace(tokens, start + 1)?,
+    _ => start,
+  };
+  if is_noun(tokens[noun].value()) {
+    Some((start, noun + 1))
+  } else {
+    None
+  }
+}
+
+// "Add Mihai to the departments Science and Maths." is parsed as "Add Mihai to Science and Maths.":
+// nouns at the start of a list only say what the list contains.
+pub fn strip_nouns<'a, 'b>(tokens: &'b [lexer::Token<'a>]) -> Cow<'b, [lexer::Token<'a>]> {
+  let list_starts = core::iter::once(0).chain(
+    tokens
+      .iter()
+      .enumerate()
+      .filter(|(_, t)| matches!(t.value(), LINKER_TO | LINKER_FROM | LINKER_IS | LINKER_ARE))
+      .map(|(i, _)| i + 1),
+  );
+  let nouns: Vec<(usize, usize)> = list_starts.filter_map(|i| find_noun(tokens, i)).collect();
+  if nouns.is_empty() {
+    return tokens.into();
+  }
+
+  let mut res = Vec::with_capacity(tokens.len());
+  let mut i = 0;
+  for (start, end) in nouns {
+    res.extend_from_slice(&tokens[i..start]);
+    i = end;
+  }
+  res.extend_from_slice(&tokens[i..]);
+  res.into()
+}
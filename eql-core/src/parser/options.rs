@@ -0,0 +1,142 @@
+use super::error::{Error, Help, Syntax};
+use super::grammar::Grammar;
+use crate::error::ErrorKind;
+use crate::lexer;
+use crate::operation;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+// Editions pin the grammar a script is written in. When parsing in an edition, tokens and operations
+// added after it are rejected and the words of later clauses are parts of names, as they were then,
+// so scripts keep parsing the same way as the language grows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Edition {
+  // The grammar as of the introduction of editions.
+  V1,
+  // Extensions from then on, such as quoted names and new operations.
+  #[default]
+  V2,
+}
+
+impl Edition {
+  pub const LATEST: Edition = Edition::V2;
+}
+
+impl fmt::Display for Edition {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(match self {
+      Edition::V1 => "1",
+      Edition::V2 => "2",
+    })
+  }
+}
+
+impl FromStr for Edition {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.trim_start_matches(['v', 'V']) {
+      "1" => Ok(Edition::V1),
+      "2" => Ok(Edition::V2),
+      _ => Err(format!("{} is not an edition", s)),
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ParserOptions {
+  edition: Edition,
+  // Keywords and the other words of the grammar are recognized in any casing, e.g. "add" and "ADD"
+  // as "Add". Errors still show them as written.
+  case_insensitive: bool,
+  grammar: Grammar,
+}
+
+pub(super) fn unsupported(token: lexer::Token<'_>, syntax: Syntax) -> Error<'_> {
+  Error::new(
+    ErrorKind::UnsupportedSyntax,
+    operation::Unknown,
+    token,
+    Some(token),
+    None,
+    Some(Help::UnsupportedSyntax(syntax)),
+  )
+}
+
+impl ParserOptions {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn get_edition(&self) -> Edition {
+    self.edition
+  }
+
+  pub fn set_edition(self, edition: Edition) -> Self {
+    ParserOptions { edition, ..self }
+  }
+
+  pub fn get_case_insensitive(&self) -> bool {
+    self.case_insensitive
+  }
+
+  pub fn set_case_insensitive(self, case_insensitive: bool) -> Self {
+    ParserOptions {
+      case_insensitive,
+      ..self
+    }
+  }
+
+  // The parser lexes with the grammar too. Tokens lexed without it are respelled as well, but
+  // punctuation the default rules don't know has already failed to lex.
+  pub fn get_grammar(&self) -> Grammar {
+    self.grammar
+  }
+
+  pub fn set_grammar(self, grammar: Grammar) -> Self {
+    ParserOptions { grammar, ..self }
+  }
+
+  // Applies the options to a token before it is parsed.
+  pub(crate) fn prepare<'a>(&self, token: lexer::Token<'a>) -> Result<lexer::Token<'a>, Error<'a>> {
+    self.check(token)?;
+    let token = token.respell(&self.grammar);
+    Ok(match self.case_insensitive {
+      true => token.ignore_case(),
+      false => token,
+    })
+  }
+
+  // Rejects tokens of syntax added after the edition.
+  fn check<'a>(&self, token: lexer::Token<'a>) -> Result<(), Error<'a>> {
+    match token.value() {
+      _ if self.edition >= Edition::V2 => Ok(()),
+      lexer::Quoted(_) => Err(unsupported(token, Syntax::QuotedNames)),
+      lexer::Number(_) => Err(unsupported(token, Syntax::Numbers)),
+      lexer::Comment(_) => Err(unsupported(token, Syntax::Comments)),
+      lexer::Word(w) if w.contains(['\'', '\u{2019}', '-']) => {
+        Err(unsupported(token, Syntax::JoinedNames))
+      }
+      _ => Ok(()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn editions() {
+    assert_eq!(ParserOptions::new().get_edition(), Edition::LATEST);
+    assert_eq!("v1".parse(), Ok(Edition::V1));
+    assert_eq!(
+      "2".parse::<Edition>().map(|e| e.to_string()),
+      Ok("2".into())
+    );
+    assert!("3".parse::<Edition>().is_err());
+    assert!(Edition::V1 < Edition::V2);
+  }
+}
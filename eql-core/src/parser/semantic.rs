@@ -0,0 +1,159 @@
+use super::constants::*;
+use super::definitions::Definitions;
+use super::options::Edition;
+use super::util::get_string_from_tokens;
+use super::{classify, parse_into, TokenClass};
+use crate::lexer;
+use crate::operation::{NameList, Operation};
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+fn names_of(operations: &[Operation]) -> BTreeSet<&str> {
+  let mut names = BTreeSet::new();
+  for op in operations {
+    names.extend(
+      op.get_names()
+        .and_then(NameList::listed)
+        .unwrap_or_default()
+        .iter()
+        .map(String::as_str),
+    );
+    let departments = op.get_departments().unwrap_or_default();
+    names.extend(departments.iter().map(String::as_str));
+    names.extend(
+      op.get_except()
+        .unwrap_or_default()
+        .iter()
+        .map(String::as_str),
+    );
+    names.extend(op.get_new_name());
+    names.extend(op.get_reassign_to());
+    if let Some(condition) = op.get_condition() {
+      names.extend(condition.departments.iter().map(String::as_str));
+    }
+    for alias in op.aliases() {
+      names.extend([alias.name.as_str(), alias.alias.as_str()]);
+    }
+  }
+  names
+}
+
+fn is_name_part(value: lexer::TokenValue) -> bool {
+  matches!(
+    value,
+    lexer::Word(_) | lexer::Keyword(_) | lexer::Number(_) | lexer::Quoted(_) | lexer::Whitespace
+  )
+}
+
+// The longest run of tokens from the start that spells one of the names, if any.
+fn name_len(tokens: &[lexer::Token], names: &BTreeSet<&str>) -> Option<usize> {
+  let run = tokens
+    .iter()
+    .position(|t| !is_name_part(t.value()))
+    .unwrap_or(tokens.len());
+  (1..=run)
+    .rev()
+    .filter(|&len| tokens[len - 1].value() != lexer::Whitespace)
+    .find(|&len| names.contains(get_string_from_tokens(&tokens[..len]).as_ref()))
+}
+
+fn classify_statement(tokens: &[lexer::Token], names: &BTreeSet<&str>, res: &mut Vec<TokenClass>) {
+  let mut i = 0;
+  while i < tokens.len() {
+    let value = tokens[i].value();
+    if let Some(len) = name_len(&tokens[i..], names).filter(|_| is_name_part(value)) {
+      let classes = tokens[i..i + len].iter().map(|t| match t.value() {
+        lexer::Whitespace => TokenClass::Whitespace,
+        _ => TokenClass::Word,
+      });
+      res.extend(classes);
+      i += len;
+      continue;
+    }
+    res.push(match classify(value) {
+      TokenClass::Word if grammar_index(value).is_some() => TokenClass::Keyword,
+      TokenClass::Word if get_compound_keyword(value).is_some() => TokenClass::Keyword,
+      class => class,
+    });
+    i += 1;
+  }
+}
+
+// Classifies the tokens of the source by the part they play in their statement, which classify
+// can't tell from the token alone: the words of names are words even if the grammar gives them
+// a meaning elsewhere, and the other grammar words are keywords or linkers. Statements that don't
+// parse are classified token by token.
+pub fn classify_tokens(source: &str) -> Vec<(lexer::Token<'_>, TokenClass)> {
+  let tokens: Vec<_> = lexer::segments(source).map(|(_, t)| t).collect();
+  let mut classes = Vec::with_capacity(tokens.len());
+  let mut definitions = Definitions::new();
+  let mut rest = &tokens[..];
+  while !rest.is_empty() {
+    let len = rest
+      .iter()
+      .position(|t| is_terminator(t.value()))
+      .map_or(rest.len(), |i| i + 1);
+    let (statement, tail) = rest.split_at(len);
+    let mut operations = Vec::new();
+    let names = match parse_into(
+      statement,
+      &mut definitions,
+      Edition::LATEST,
+      &mut operations,
+    ) {
+      Ok(()) => names_of(&operations),
+      Err(_) => BTreeSet::new(),
+    };
+    classify_statement(statement, &names, &mut classes);
+    rest = tail;
+  }
+  tokens.into_iter().zip(classes).collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn classify_in_context() {
+    let source =
+      "If HR does not exist, create HR, then add Ana and \"Show\" to it. Show Sales in detail.";
+    let got: Vec<_> = classify_tokens(source)
+      .into_iter()
+      .filter(|(t, _)| t.value() != lexer::Whitespace)
+      .map(|(t, class)| (t.text(), class))
+      .collect();
+    use TokenClass::*;
+    assert_eq!(
+      got,
+      vec![
+        ("If", Keyword),
+        ("HR", Word),
+        ("does", Keyword),
+        ("not", Keyword),
+        ("exist", Keyword),
+        (",", Separator),
+        ("create", Keyword),
+        ("HR", Word),
+        (",", Separator),
+        ("then", Keyword),
+        ("add", Keyword),
+        ("Ana", Word),
+        ("and", Linker),
+        ("\"Show\"", Word),
+        ("to", Linker),
+        ("it", Keyword),
+        (".", Terminator),
+        ("Show", Keyword),
+        ("Sales", Word),
+        ("in", Keyword),
+        ("detail", Keyword),
+        (".", Terminator),
+      ]
+    );
+    let got = classify_tokens("Add Ana to $ Sales.");
+    assert_eq!(got[0].1, Keyword);
+    assert_eq!(got[2].1, Word);
+  }
+}
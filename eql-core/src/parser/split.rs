@@ -0,0 +1,69 @@
+use super::constants::*;
+use crate::lexer;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+// Byte offsets of a statement in the source it was split from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SourceRange {
+  pub start: usize,
+  pub end: usize,
+}
+
+impl SourceRange {
+  pub fn slice<'a>(&self, source: &'a str) -> &'a str {
+    &source[self.start..self.end]
+  }
+
+  pub fn contains(&self, offset: usize) -> bool {
+    self.start <= offset && offset < self.end
+  }
+}
+
+impl From<SourceRange> for Range<usize> {
+  fn from(range: SourceRange) -> Self {
+    range.start..range.end
+  }
+}
+
+// Returns the range of every statement, from its first token to its terminator, without the
+// whitespace between statements. Text after the last terminator is returned as a statement of its
+// own. Nothing is parsed, so invalid statements and unknown tokens are split like any other.
+pub fn split_statements(source: &str) -> Vec<SourceRange> {
+  let mut res = Vec::new();
+  let mut current: Option<SourceRange> = None;
+  for (segment, token) in lexer::segments(source) {
+    if is_trivia(token.value()) {
+      continue;
+    }
+    let start = segment.as_ptr() as usize - source.as_ptr() as usize;
+    let end = start + segment.len();
+    let range = current.get_or_insert(SourceRange { start, end });
+    range.end = end;
+    if is_terminator(token.value()) {
+      res.extend(current.take());
+    }
+  }
+  res.extend(current);
+  res
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_split_statements() {
+    let source = "  Create HR.\nAdd Mihai\n to HR!Show 42?  \n Remove Ana";
+    let got: Vec<&str> = split_statements(source)
+      .iter()
+      .map(|r| r.slice(source))
+      .collect();
+    assert_eq!(
+      got,
+      vec!["Create HR.", "Add Mihai\n to HR!", "Show 42?", "Remove Ana"]
+    );
+    assert!(split_statements(" \n ").is_empty());
+    assert!(split_statements("# Create HR.\n").is_empty());
+  }
+}
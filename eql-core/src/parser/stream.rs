@@ -0,0 +1,108 @@
+use super::constants::*;
+use super::definitions::Definitions;
+use super::options::ParserOptions;
+use super::parse_into;
+use crate::error::Error;
+use crate::lexer;
+use crate::operation::Operation;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+// Only the tokens of the statement being parsed are buffered, so the input is never materialized.
+pub struct ParseIter<'a, I> {
+  tokens: I,
+  buffer: Vec<lexer::Token<'a>>,
+  // Compound statements parse into several operations, which are returned one at a time.
+  pending: VecDeque<Operation>,
+  definitions: Definitions<'a>,
+  cancelled: Option<&'a AtomicBool>,
+  options: ParserOptions,
+  done: bool,
+}
+
+impl<'a, I> ParseIter<'a, I>
+where
+  I: Iterator<Item = Result<lexer::Token<'a>, lexer::Error<'a>>>,
+{
+  pub fn new(tokens: I) -> Self {
+    ParseIter {
+      tokens,
+      buffer: Vec::new(),
+      pending: VecDeque::new(),
+      definitions: Definitions::new(),
+      cancelled: None,
+      options: ParserOptions::default(),
+      done: false,
+    }
+  }
+
+  pub fn with_options(mut self, options: ParserOptions) -> Self {
+    self.options = options;
+    self
+  }
+
+  pub fn options(&self) -> ParserOptions {
+    self.options
+  }
+
+  // Parsing stops with Error::Cancelled once the flag is set. It is checked before every token, so
+  // even a single huge statement is abandoned promptly.
+  pub fn cancel_on(mut self, cancelled: &'a AtomicBool) -> Self {
+    self.cancelled = Some(cancelled);
+    self
+  }
+
+  // Returns false if the input ended before a terminator was found.
+  fn read_statement(&mut self) -> Result<bool, Error<'a>> {
+    self.buffer.clear();
+    loop {
+      if self.cancelled.is_some_and(|c| c.load(Ordering::Relaxed)) {
+        return Err(Error::Cancelled);
+      }
+      let token = match self.tokens.next() {
+        Some(token) => token?,
+        None => return Ok(false),
+      };
+      let token = self.options.prepare(token)?;
+      if self.buffer.is_empty() && is_trivia(token.value()) {
+        continue;
+      }
+      self.buffer.push(token);
+      if is_terminator(token.value()) {
+        return Ok(true);
+      }
+    }
+  }
+}
+
+impl<'a, I> Iterator for ParseIter<'a, I>
+where
+  I: Iterator<Item = Result<lexer::Token<'a>, lexer::Error<'a>>>,
+{
+  type Item = Result<Operation, Error<'a>>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let mut res = Vec::with_capacity(1);
+    while !self.done && self.pending.is_empty() {
+      match self.read_statement() {
+        Ok(terminated) => self.done = !terminated,
+        Err(e) => {
+          self.done = true;
+          return Some(Err(e));
+        }
+      }
+      if let Err(e) = parse_into(
+        &self.buffer,
+        &mut self.definitions,
+        self.options.get_edition(),
+        &mut res,
+      ) {
+        self.done = true;
+        return Some(Err(e.into()));
+      }
+      self.pending.extend(res.drain(..));
+    }
+    self.pending.pop_front().map(Ok)
+  }
+}
@@ -0,0 +1,98 @@
+use crate::lexer;
+
+// A cursor over the tokens of a statement that skips whitespace, so the grammar can look ahead by
+// tokens instead of by indices. Positions are still indices into the slice, for the code that
+// works with slices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenStream<'a, 'b> {
+  tokens: &'b [lexer::Token<'a>],
+  pos: usize,
+}
+
+impl<'a, 'b> TokenStream<'a, 'b> {
+  pub fn new(tokens: &'b [lexer::Token<'a>]) -> Self {
+    TokenStream { tokens, pos: 0 }
+  }
+
+  fn index(&self, n: usize) -> Option<usize> {
+    self.tokens[self.pos..]
+      .iter()
+      .enumerate()
+      .filter(|(_, t)| t.value() != lexer::Whitespace)
+      .nth(n)
+      .map(|(i, _)| self.pos + i)
+  }
+
+  // Looks n tokens ahead without consuming anything: peek(0) is the token next returns.
+  pub fn peek(&self, n: usize) -> Option<lexer::Token<'a>> {
+    self.index(n).map(|i| self.tokens[i])
+  }
+
+  pub fn next_if(
+    &mut self,
+    pred: impl FnOnce(&lexer::Token<'a>) -> bool,
+  ) -> Option<lexer::Token<'a>> {
+    match self.peek(0) {
+      Some(token) if pred(&token) => self.next(),
+      _ => None,
+    }
+  }
+
+  // Consumes the next token if it has the given value. Otherwise the token found instead is
+  // returned, or none at the end of the tokens.
+  pub fn expect(
+    &mut self,
+    value: lexer::TokenValue,
+  ) -> Result<lexer::Token<'a>, Option<lexer::Token<'a>>> {
+    self
+      .next_if(|t| t.value() == value)
+      .ok_or_else(|| self.peek(0))
+  }
+
+  // The index of the first token that wasn't consumed.
+  pub fn position(&self) -> usize {
+    self.pos
+  }
+
+  pub fn rest(&self) -> &'b [lexer::Token<'a>] {
+    &self.tokens[self.pos..]
+  }
+}
+
+impl<'a> Iterator for TokenStream<'a, '_> {
+  type Item = lexer::Token<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let i = self.index(0);
+    self.pos = i.map_or(self.tokens.len(), |i| i + 1);
+    i.map(|i| self.tokens[i])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lexer::{lex, Keyword, Punctuation, Word};
+
+  #[test]
+  fn lookahead() {
+    let tokens = lex("Add  Ana to HR.").unwrap();
+    let mut stream = TokenStream::new(&tokens);
+    assert_eq!(stream.peek(0).map(|t| t.value()), Some(Keyword("Add")));
+    assert_eq!(stream.peek(2).map(|t| t.value()), Some(Word("to")));
+    assert_eq!(stream.peek(5), None);
+    assert_eq!(stream.expect(Keyword("Add")).map(|t| t.text()), Ok("Add"));
+    assert_eq!(
+      stream.expect(Word("to")).map_err(|t| t.unwrap().text()),
+      Err("Ana")
+    );
+    assert_eq!(stream.position(), 1);
+    assert_eq!(stream.next().map(|t| t.text()), Some("Ana"));
+    assert_eq!(stream.position(), 3);
+    assert_eq!(stream.rest().len(), 5);
+    let rest: Vec<_> = stream.by_ref().map(|t| t.value()).collect();
+    assert_eq!(rest, [Word("to"), Word("HR"), Punctuation(".")]);
+    assert_eq!(stream.expect(Punctuation(".")), Err(None));
+    assert_eq!(stream.position(), tokens.len());
+  }
+}
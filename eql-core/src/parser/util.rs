@@ -0,0 +1,306 @@
+use super::constants::*;
+use super::error::{Element, Error, Help};
+use super::options::Edition;
+use super::{ParsedList, ParsedOperation};
+use crate::error::ErrorKind;
+use crate::lexer;
+use crate::operation;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::cmp::min;
+
+pub fn handle_terminator<'a>(
+  tokens: &[lexer::Token<'a>],
+  op: ParsedOperation<'a>,
+  op_token: lexer::Token<'a>,
+) -> Result<ParsedOperation<'a>, Error<'a>> {
+  let get_terminators = |op_kind| -> &[lexer::TokenValue] {
+    match op_kind {
+      operation::Unknown => &[],
+      operation::Create | operation::Add => {
+        &[SEPARATOR, SEPARATOR_OVERWRITE, SEPARATOR_FAIL_SILENTLY]
+      }
+      operation::Show
+      | operation::Remove
+      | operation::Where
+      | operation::RenameEmployee
+      | operation::RenameDepartment
+      | operation::Dissolve
+      | operation::Move
+      | operation::Find => &[SEPARATOR, SEPARATOR_FAIL_SILENTLY],
+      operation::Define => &[SEPARATOR],
+    }
+  };
+  match tokens {
+    [] => Err(Error::new(
+      ErrorKind::Unterminated,
+      op.kind(),
+      op_token,
+      None,
+      Some(get_terminators(op.kind()).into()),
+      Some(Help::Unterminated),
+    )),
+    [token] => {
+      let value = token.value();
+      let op_kind = op.kind();
+      match value {
+        SEPARATOR_OVERWRITE => op.set_overwrite(true),
+        // Questions end with a question mark, which doesn't make them fail silently.
+        SEPARATOR_FAIL_SILENTLY if op_kind == operation::Where => Some(op),
+        SEPARATOR_FAIL_SILENTLY => op.set_fail_silently(true),
+        _ => Some(op),
+      }
+      .ok_or_else(|| {
+        Error::new(
+          ErrorKind::InvalidTerminator,
+          op_kind,
+          op_token,
+          Some(*token),
+          Some(get_terminators(op_kind).into()),
+          Some(Help::InvalidTerminator(value.to_string())),
+        )
+      })
+    }
+    [_, unexpected, ..] => Err(Error::new(
+      ErrorKind::TrailingTokens,
+      op.kind(),
+      op_token,
+      Some(*unexpected),
+      None,
+      Some(Help::TrailingTokens),
+    )),
+  }
+}
+
+fn get_list_element_tokens<'a, 'b>(
+  tokens: &'b [lexer::Token<'a>],
+  terminators: &[lexer::TokenValue],
+) -> Result<(&'b [lexer::Token<'a>], usize, bool), usize> {
+  let mut words = None;
+  let element = |words: Option<(usize, usize)>| match words {
+    Some((first, last)) => &tokens[first..=last],
+    None => &tokens[..0],
+  };
+
+  for (i, token) in tokens.iter().enumerate() {
+    match token.value() {
+      SEPARATOR_VALUES | LINKER_AND => return Ok((element(words), i, false)),
+      _ if terminators.contains(&token.value()) => return Ok((element(words), i, true)),
+      lexer::Whitespace => {}
+      lexer::Word(_) | lexer::Keyword(_) | lexer::Number(_) | lexer::Quoted(_) => {
+        words = Some((words.map_or(i, |(first, _)| first), i))
+      }
+      _ => return Err(i),
+    }
+  }
+  Ok((element(words), tokens.len(), false))
+}
+
+// A name of a single token borrows its text where it can, so it is only copied once the operation it
+// is parsed into is handed out.
+pub fn get_string_from_tokens<'a>(tokens: &[lexer::Token<'a>]) -> Cow<'a, str> {
+  if let [token] = tokens {
+    return token.name();
+  }
+  let len = tokens.iter().map(|t| t.value().get().len()).sum();
+  let mut s = String::with_capacity(len);
+  tokens.iter().for_each(|t| s.push_str(&t.name()));
+  s.into()
+}
+
+type ParseListError<'a> = (Option<lexer::Token<'a>>, bool);
+
+// Lists of up to three names of a single word each are parsed without allocating.
+pub fn parse_list<'a>(
+  tokens: &[lexer::Token<'a>],
+  terminators: &[lexer::TokenValue],
+) -> Result<(ParsedList<'a>, usize), ParseListError<'a>> {
+  let mut elements = ParsedList::new();
+
+  let mut i = 0;
+  let mut after_comma = false;
+  // Whether a comma or an "and" was the last thing in the list, so another element must follow.
+  let mut after_separator = false;
+  while i < tokens.len() {
+    let token = tokens[i];
+
+    match token.value() {
+      lexer::Whitespace => {}
+      _ if terminators.contains(&token.value()) => break,
+      lexer::Word(_) | lexer::Keyword(_) | lexer::Number(_) | lexer::Quoted(_) => {
+        match get_list_element_tokens(&tokens[i..], terminators) {
+          // The comma before the "and" of "Mihai, Andrei, and Ioan" is optional.
+          Ok(([], incr, false)) if after_comma && token.value() == LINKER_AND => {
+            i += incr;
+            after_comma = false;
+          }
+          Ok(([], ..)) => return Err((Some(token), true)),
+          Ok((elem_tokens, incr, is_terminator)) => {
+            i += incr;
+            let separator = tokens.get(i).map(|t| t.value());
+            after_comma = separator == Some(SEPARATOR_VALUES);
+            after_separator = after_comma || separator == Some(LINKER_AND);
+            elements.push(get_string_from_tokens(elem_tokens));
+            if is_terminator {
+              continue;
+            }
+          }
+          Err(incr) => {
+            i += incr;
+            continue;
+          }
+        }
+      }
+      _ => return Err((Some(token), elements.is_empty())),
+    }
+    i += 1;
+  }
+  if elements.is_empty() || after_separator {
+    Err((
+      tokens.get(min(i, tokens.len().saturating_sub(1))).cloned(),
+      true,
+    ))
+  } else if i > tokens.len() {
+    Err((tokens.last().cloned(), false))
+  } else {
+    Ok((elements, i))
+  }
+}
+
+pub fn get_parse_list_error_handler_generator<'a>(
+  op_kind: operation::OperationKind,
+  op_token: lexer::Token<'a>,
+  edition: Edition,
+) -> impl Fn(
+  &'static [lexer::TokenValue<'static>],
+  Element,
+) -> Box<dyn Fn(ParseListError<'a>) -> Error<'a> + 'a> {
+  move |terminators, element| {
+    Box::new(move |(t, is_empty)| {
+      const EXPECTED: &[lexer::TokenValue] = &[lexer::Whitespace, lexer::Word("")];
+      if is_empty {
+        Error::new(
+          ErrorKind::EmptyList,
+          op_kind,
+          op_token,
+          t,
+          Some(EXPECTED.into()),
+          Some(Help::EmptyList(
+            element,
+            t.map(|v| v.written_value().to_string()),
+          )),
+        )
+      } else {
+        let reserved = t.filter(|v| is_reserved(v.value()));
+        Error::new(
+          match reserved {
+            Some(_) => ErrorKind::ReservedWord,
+            None => ErrorKind::UnterminatedList,
+          },
+          op_kind,
+          op_token,
+          t.filter(|v| {
+            reserved.is_some() || !matches!(v.value(), lexer::Word(_) | lexer::Keyword(_))
+          }),
+          Some([EXPECTED, terminators].concat().into()),
+          Some(reserved.map_or(Help::UnterminatedList, |v| {
+            // Quoted, a reserved word is a name like any other.
+            let quoted = Some(format!("\"{}\"", v.text())).filter(|_| edition >= Edition::V2);
+            Help::ReservedWord(v.written_value().to_string(), quoted)
+          })),
+        )
+      }
+    })
+  }
+}
+
+pub fn next_non_whitespace(tokens: &[lexer::Token], from: usize) -> Option<usize> {
+  (from..tokens.len()).find(|i| tokens[*i].value() != lexer::Whitespace)
+}
+
+pub fn get_operation_tokens<'a, 'b>(tokens: &'b [lexer::Token<'a>]) -> &'b [lexer::Token<'a>] {
+  for i in 0..tokens.len() {
+    if is_terminator(tokens[i].value()) {
+      return &tokens[..=i];
+    }
+  }
+  tokens
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::util;
+
+  fn tv(tokens: Vec<lexer::Token>) -> Vec<lexer::TokenValue> {
+    tokens.into_iter().map(|t| t.value()).collect()
+  }
+
+  #[test]
+  fn test_get_list_element_tokens() {
+    let tokens = lexer::lex("Moraru    Mihaela  , Mircea Ioan and Amalia Brad").unwrap();
+    let (got, ..) = get_list_element_tokens(&tokens, &[]).unwrap();
+    let expect = lexer::lex("Moraru Mihaela").unwrap();
+    assert_eq!(tv(got.into()), tv(expect));
+  }
+
+  #[test]
+  fn test_get_string_from_tokens() {
+    let tokens = lexer::lex("Moraru   Mihaela").unwrap();
+    assert!(matches!(
+      get_string_from_tokens(&tokens[..1]),
+      Cow::Borrowed("Moraru")
+    ));
+    assert_eq!(get_string_from_tokens(&tokens), "Moraru Mihaela");
+  }
+
+  #[test]
+  fn test_parse_list() {
+    let tokens = lexer::lex("Moraru   Mihaela  , Mircea Ioan and Amalia Brad.").unwrap();
+    let (got, ..) = parse_list(&tokens, &[SEPARATOR]).unwrap();
+    let expect = util::to_string_vec(vec!["Moraru Mihaela", "Mircea Ioan", "Amalia Brad"]);
+    assert_eq!(got.into_vec(), expect);
+
+    for (source, expect) in [
+      ("Mihai, Andrei, and Ioan.", &["Mihai", "Andrei", "Ioan"][..]),
+      ("Mihai, and Ioan.", &["Mihai", "Ioan"]),
+      ("Mihai.", &["Mihai"]),
+    ] {
+      let tokens = lexer::lex(source).unwrap();
+      let (got, ..) = parse_list(&tokens, &[SEPARATOR]).unwrap();
+      assert_eq!(got.into_vec(), util::to_string_vec(expect.to_vec()));
+    }
+
+    // A separator must be followed by an element.
+    for (source, at) in [
+      ("Mihai, and to HR.", "to"),
+      ("Mihai, and and Ioan to HR.", "and"),
+      ("Mihai and to HR.", "to"),
+      ("Mihai, to HR.", "to"),
+    ] {
+      let tokens = lexer::lex(source).unwrap();
+      let (token, is_empty) = parse_list(&tokens, &[LINKER_TO]).unwrap_err();
+      assert_eq!(token.unwrap().text(), at, "{}", source);
+      assert!(is_empty, "{}", source);
+    }
+    let err = crate::parse(lexer::lex("Add Mihai, and to HR.").unwrap()).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::EmptyList);
+  }
+
+  #[test]
+  fn parse_list_borrows() {
+    // Nothing is allocated: the names borrow from the source and fit in the list.
+    let tokens = lexer::lex("Mihai, Andrei and Ioan.").unwrap();
+    let (got, _) = parse_list(&tokens, &[SEPARATOR]).unwrap();
+    assert!(!got.spilled());
+    assert!(got.iter().all(|name| matches!(name, Cow::Borrowed(_))));
+    assert_eq!(got.as_slice(), &["Mihai", "Andrei", "Ioan"]);
+    // Names of more words are joined into a new string.
+    let tokens = lexer::lex("Moraru Mihaela and Ioan.").unwrap();
+    let (got, _) = parse_list(&tokens, &[SEPARATOR]).unwrap();
+    assert!(matches!(got[0], Cow::Owned(_)) && matches!(got[1], Cow::Borrowed("Ioan")));
+  }
+  // TODO: more tests
+}
@@ -0,0 +1,164 @@
+use crate::lexer::{self, Token};
+use crate::operation::Operation;
+use crate::parser;
+use bumpalo::Bump;
+use smallvec::SmallVec;
+use std::collections::HashMap;
+use std::error::Error;
+use std::rc::Rc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+pub type SymbolList = SmallVec<[Symbol; 3]>;
+
+// Every string of the operation is interned, so it can be resolved back into the operation.
+pub type InternedOperation = Operation<Symbol>;
+
+#[derive(Debug, Default)]
+struct Interner {
+  symbols: HashMap<Rc<str>, Symbol>,
+  strings: Vec<Rc<str>>,
+}
+
+impl Interner {
+  fn intern(&mut self, s: &str) -> Symbol {
+    if let Some(symbol) = self.symbols.get(s) {
+      return *symbol;
+    }
+    let symbol = Symbol(self.strings.len() as u32);
+    let s: Rc<str> = s.into();
+    self.strings.push(s.clone());
+    self.symbols.insert(s, symbol);
+    symbol
+  }
+
+  fn resolve(&self, symbol: Symbol) -> &str {
+    &self.strings[symbol.0 as usize]
+  }
+}
+
+// Borrows only the arena, so the interner can be used while the tokens are.
+fn lex_in<'s, 'a: 's>(arena: &'s Bump, s: &'a str) -> Result<&'s [Token<'a>], lexer::Error<'a>> {
+  let mut tokens = bumpalo::collections::Vec::new_in(arena);
+  for token in lexer::Lexer::new(s) {
+    tokens.push(token?);
+  }
+  Ok(tokens.into_bump_slice())
+}
+
+// Tokens lexed through a session live in its arena until the session is reset, so parsing many
+// small statements doesn't allocate and free a token vector for each of them. Interned strings
+// outlive resets.
+#[derive(Debug, Default)]
+pub struct ParseSession {
+  arena: Bump,
+  interner: Interner,
+}
+
+impl ParseSession {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn lex<'s, 'a: 's>(&'s self, s: &'a str) -> Result<&'s [Token<'a>], lexer::Error<'a>> {
+    lex_in(&self.arena, s)
+  }
+
+  pub fn lex_parse<'a>(&self, s: &'a str) -> Result<Vec<Operation>, Box<dyn Error + 'a>> {
+    Ok(parser::parse_slice(self.lex(s)?)?)
+  }
+
+  // The names are interned as they are parsed, so the operations never own a copy of them.
+  pub fn lex_parse_interned<'a>(
+    &mut self,
+    s: &'a str,
+  ) -> Result<Vec<InternedOperation>, Box<dyn Error + 'a>> {
+    let tokens = lex_in(&self.arena, s)?;
+    let interner = &mut self.interner;
+    Ok(parser::parse_slice_map(tokens, |name| {
+      interner.intern(&name)
+    })?)
+  }
+
+  pub fn intern(&mut self, s: &str) -> Symbol {
+    self.interner.intern(s)
+  }
+
+  // Panics if the symbol wasn't interned by this session.
+  pub fn resolve(&self, symbol: Symbol) -> &str {
+    self.interner.resolve(symbol)
+  }
+
+  pub fn intern_operation(&mut self, op: &Operation) -> InternedOperation {
+    op.map(|s| self.intern(s))
+  }
+
+  // Panics if a symbol of the operation wasn't interned by this session.
+  pub fn resolve_operation(&self, op: &InternedOperation) -> Operation {
+    op.map(|symbol| self.resolve(*symbol).into())
+  }
+
+  pub fn allocated_bytes(&self) -> usize {
+    self.arena.allocated_bytes()
+  }
+
+  pub fn reset(&mut self) {
+    self.arena.reset();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lex_in_arena() {
+    let source = "Create HR. Show HR?";
+    let session = ParseSession::new();
+    let got = session.lex(source).unwrap();
+    assert_eq!(got, &lexer::lex(source).unwrap()[..]);
+    assert!(session.allocated_bytes() > 0);
+  }
+
+  #[test]
+  fn lex_parse_and_reset() {
+    let mut session = ParseSession::new();
+    for _ in 0..3 {
+      let got = session.lex_parse("Add Mihai to HR!").unwrap();
+      assert_eq!(got, crate::lex_parse("Add Mihai to HR!").unwrap());
+      session.reset();
+    }
+    assert!(session.lex_parse("Add $ to HR.").is_err());
+  }
+
+  #[test]
+  fn intern_and_resolve() {
+    let mut session = ParseSession::new();
+    let got = session
+      .lex_parse_interned("Create HR. Add Mihai to HR and Sales. Remove Mihai from Sales?")
+      .unwrap();
+    let (hr, sales, mihai) = (
+      session.intern("HR"),
+      session.intern("Sales"),
+      session.intern("Mihai"),
+    );
+    assert_eq!(got[0].departments(), &[hr]);
+    assert_eq!(got[1].departments(), &[hr, sales]);
+    assert_eq!(got[1].names(), &[mihai]);
+    assert_eq!(got[2].departments(), &[sales]);
+    assert!(got[2].fail_silently());
+    assert_eq!(session.resolve(sales), "Sales");
+  }
+
+  #[test]
+  fn intern_every_field() {
+    let source = "Rename employee Mihai to Mihai Popescu. Dissolve HR and move everyone to Sales. \
+Move everyone except Ana from PR to IT? If Sales exists, add Ioan, also known as John, to it \
+starting Monday. Show Sales ordered by name, first 3.";
+    let mut session = ParseSession::new();
+    let got = session.lex_parse_interned(source).unwrap();
+    let resolved: Vec<_> = got.iter().map(|op| session.resolve_operation(op)).collect();
+    assert_eq!(resolved, crate::lex_parse(source).unwrap());
+  }
+}
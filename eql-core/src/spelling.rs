@@ -0,0 +1,103 @@
+use crate::operation::{self, Operation};
+use crate::util;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Misspelling {
+  pub operation_index: usize,
+  pub department: String,
+  pub suggestion: String,
+}
+
+impl fmt::Display for Misspelling {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "Warning on operation {}: department \"{}\" might be misspelled, did you mean \"{}\"?",
+      self.operation_index + 1,
+      self.department,
+      self.suggestion
+    )
+  }
+}
+
+fn max_distance(word: &str) -> usize {
+  (util::string_length(word) / 4).max(1)
+}
+
+fn closest<'a>(department: &str, known: &[&'a str]) -> Option<&'a str> {
+  known
+    .iter()
+    .map(|k| (*k, util::edit_distance(department, k)))
+    .filter(|(_, d)| *d <= max_distance(department))
+    .min_by_key(|(_, d)| *d)
+    .map(|(k, _)| k)
+}
+
+// Departments created earlier in the script are considered known, too.
+pub fn check_departments<'a>(ops: &'a [Operation], dictionary: &[&'a str]) -> Vec<Misspelling> {
+  let mut known = dictionary.to_vec();
+  let mut res = Vec::new();
+
+  for (i, op) in ops.iter().enumerate() {
+    let departments = op.get_departments().unwrap_or_default();
+    if op.kind() == operation::Create {
+      known.extend(departments.iter().map(String::as_str));
+      continue;
+    }
+    for department in departments {
+      if known.contains(&department.as_str()) {
+        continue;
+      }
+      if let Some(suggestion) = closest(department, &known) {
+        res.push(Misspelling {
+          operation_index: i,
+          department: department.clone(),
+          suggestion: suggestion.into(),
+        });
+      }
+    }
+  }
+
+  res
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_check_departments() {
+    let ops = crate::lex_parse(
+      "Create Engineering. Add Mihai to Sciense and Enginering. Show Physics. Remove HR.",
+    )
+    .unwrap();
+    let got = check_departments(&ops, &["Science", "Physics"]);
+    let expect = vec![
+      Misspelling {
+        operation_index: 1,
+        department: "Sciense".into(),
+        suggestion: "Science".into(),
+      },
+      Misspelling {
+        operation_index: 1,
+        department: "Enginering".into(),
+        suggestion: "Engineering".into(),
+      },
+    ];
+    assert_eq!(got, expect);
+  }
+
+  #[test]
+  fn format() {
+    let m = Misspelling {
+      operation_index: 0,
+      department: "Sciense".into(),
+      suggestion: "Science".into(),
+    };
+    assert_eq!(
+      m.to_string(),
+      r#"Warning on operation 1: department "Sciense" might be misspelled, did you mean "Science"?"#
+    );
+  }
+}
@@ -0,0 +1,121 @@
+use crate::operation::{self, NameList, Operation, OperationKind};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+  pub operations: usize,
+  // Kinds are listed in the order they first appear in.
+  pub kinds: Vec<(OperationKind, usize)>,
+  pub departments: usize,
+  pub names: usize,
+  // Operations that remove people or departments, or overwrite existing ones.
+  pub destructive: usize,
+  pub conditional: usize,
+  pub effective: usize,
+  pub aliased: usize,
+  pub fail_silently: usize,
+  pub overwrite: usize,
+  pub detailed: usize,
+}
+
+fn is_destructive(op: &Operation) -> bool {
+  matches!(op.kind(), operation::Remove | operation::Dissolve) || op.get_overwrite() == Some(true)
+}
+
+impl From<&[Operation]> for Stats {
+  fn from(ops: &[Operation]) -> Self {
+    let mut stats = Stats {
+      operations: ops.len(),
+      ..Default::default()
+    };
+    let mut departments = HashSet::new();
+    let mut names = HashSet::new();
+    for op in ops {
+      match stats.kinds.iter_mut().find(|(k, _)| *k == op.kind()) {
+        Some((_, count)) => *count += 1,
+        None => stats.kinds.push((op.kind(), 1)),
+      }
+      departments.extend(
+        op.get_departments()
+          .unwrap_or_default()
+          .iter()
+          .map(String::as_str),
+      );
+      departments.extend(op.get_reassign_to());
+      names.extend(
+        op.get_names()
+          .and_then(NameList::listed)
+          .unwrap_or_default()
+          .iter()
+          .map(String::as_str),
+      );
+      match op.kind() {
+        operation::RenameDepartment => departments.extend(op.get_new_name()),
+        _ => names.extend(op.get_new_name()),
+      }
+      stats.destructive += is_destructive(op) as usize;
+      stats.conditional += op.get_condition().is_some() as usize;
+      stats.effective += op.get_effective().is_some() as usize;
+      stats.aliased += !op.aliases().is_empty() as usize;
+      stats.fail_silently += (op.get_fail_silently() == Some(true)) as usize;
+      stats.overwrite += (op.get_overwrite() == Some(true)) as usize;
+      stats.detailed += (op.get_detailed() == Some(true)) as usize;
+    }
+    stats.departments = departments.len();
+    stats.names = names.len();
+    stats
+  }
+}
+
+impl fmt::Display for Stats {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "Operations: {}", self.operations)?;
+    for (kind, count) in &self.kinds {
+      writeln!(f, "  {}: {}", kind, count)?;
+    }
+    writeln!(f, "Departments: {}", self.departments)?;
+    writeln!(f, "Names: {}", self.names)?;
+    writeln!(f, "Destructive: {}", self.destructive)?;
+    writeln!(f, "Modifiers:")?;
+    writeln!(f, "  conditions: {}", self.conditional)?;
+    writeln!(f, "  effective dates: {}", self.effective)?;
+    writeln!(f, "  aliases: {}", self.aliased)?;
+    writeln!(f, "  fail silently: {}", self.fail_silently)?;
+    writeln!(f, "  overwrite: {}", self.overwrite)?;
+    write!(f, "  in detail: {}", self.detailed)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::lex_parse;
+
+  #[test]
+  fn from_operations() {
+    let ops = lex_parse(
+      "Create HR. Add Mihai, Ana to HR! If HR exists, show HR in detail. Remove Ana from HR? Show PR starting Monday.",
+    )
+    .unwrap();
+    let expect = Stats {
+      operations: 5,
+      kinds: vec![
+        (operation::Create, 1),
+        (operation::Add, 1),
+        (operation::Show, 2),
+        (operation::Remove, 1),
+      ],
+      departments: 2,
+      names: 2,
+      destructive: 2,
+      conditional: 1,
+      effective: 1,
+      aliased: 0,
+      fail_silently: 1,
+      overwrite: 1,
+      detailed: 1,
+    };
+    assert_eq!(Stats::from(&ops[..]), expect);
+  }
+}
@@ -0,0 +1,251 @@
+use crate::error::ErrorKind;
+use crate::lexer::{self, Completeness};
+use crate::operation::Operation;
+use crate::parser::constants::{is_trivia, KEYWORD_DEFINE};
+use crate::parser::{self, ParserOptions};
+use futures_core::Stream;
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::AsyncBufRead;
+
+// Lexing and parsing errors borrow the statements they were found in, which the stream doesn't
+// keep, so only their kind and message are.
+#[derive(Debug)]
+pub enum StreamError {
+  Io(io::Error),
+  Parse {
+    kind: Option<ErrorKind>,
+    message: String,
+  },
+}
+
+impl From<crate::Error<'_>> for StreamError {
+  fn from(e: crate::Error<'_>) -> Self {
+    StreamError::Parse {
+      kind: e.kind(),
+      message: e.to_string(),
+    }
+  }
+}
+
+impl fmt::Display for StreamError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      StreamError::Io(e) => e.fmt(f),
+      StreamError::Parse { message, .. } => f.write_str(message),
+    }
+  }
+}
+
+impl std::error::Error for StreamError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    match self {
+      StreamError::Io(e) => Some(e),
+      StreamError::Parse { .. } => None,
+    }
+  }
+}
+
+// Parses the statements read from the reader as soon as they are complete, so a server doesn't
+// have to wait for the whole request. Only the statement still being read is buffered, along with
+// the teams defined so far, which later statements can refer to. The lines in errors are counted
+// from the first statement completed by the same read. The stream ends after the first error.
+pub struct OperationStream<R> {
+  reader: R,
+  // What was read after the last complete statement, which can end in the middle of a character.
+  pending: Vec<u8>,
+  definitions: String,
+  ready: VecDeque<Result<Operation, StreamError>>,
+  options: ParserOptions,
+  done: bool,
+}
+
+impl<R: AsyncBufRead + Unpin> OperationStream<R> {
+  pub fn new(reader: R) -> Self {
+    OperationStream {
+      reader,
+      pending: Vec::new(),
+      definitions: String::new(),
+      ready: VecDeque::new(),
+      options: ParserOptions::default(),
+      done: false,
+    }
+  }
+
+  pub fn with_options(mut self, options: ParserOptions) -> Self {
+    self.options = options;
+    self
+  }
+
+  // Parses the complete statements that were read, or all of them at the end of the input.
+  fn parse_complete(&mut self, at_end: bool) {
+    // A character can be split between reads. Invalid bytes are reported after the complete
+    // statements before them are parsed.
+    let (valid, invalid) = match std::str::from_utf8(&self.pending) {
+      Ok(text) => (text.len(), false),
+      Err(e) => (e.valid_up_to(), at_end || e.error_len().is_some()),
+    };
+    let text = std::str::from_utf8(&self.pending[..valid]).unwrap_or_default();
+    let end = match at_end && !invalid {
+      true => text.len(),
+      // An unclosed quote is an error whatever follows, so it is reported right away.
+      false => parser::split_statements(text)
+        .iter()
+        .rev()
+        .map(|range| range.end)
+        .find(|end| {
+          matches!(
+            lexer::is_statement_complete(&text[..*end]),
+            Completeness::Complete | Completeness::UnclosedQuote
+          )
+        })
+        .unwrap_or(0),
+    };
+    let statements = &text[..end];
+    let lexer_options = lexer::LexerOptions::new()
+      .set_coalesce_whitespace(true)
+      .set_grammar(self.options.get_grammar());
+    let tokens = lexer::Lexer::with_options(&self.definitions, lexer_options.clone())
+      .chain(lexer::Lexer::with_options(statements, lexer_options));
+    for op in parser::parse_iter(tokens).with_options(self.options) {
+      self.done |= op.is_err();
+      self.ready.push_back(op.map_err(StreamError::from));
+    }
+    if self.done {
+      return;
+    }
+    for range in parser::split_statements(statements) {
+      let statement = range.slice(statements);
+      let keyword = lexer::lex_iter(statement)
+        .filter_map(Result::ok)
+        .find(|t| !is_trivia(t.value()));
+      if keyword.is_some_and(|t| t.value() == KEYWORD_DEFINE) {
+        self.definitions.push_str(statement);
+        self.definitions.push('\n');
+      }
+    }
+    self.pending.drain(..end);
+    if invalid {
+      if let Err(e) = lexer::lex_bytes(&self.pending) {
+        self.ready.push_back(Err(crate::Error::from(e).into()));
+      }
+      self.done = true;
+    }
+  }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for OperationStream<R> {
+  type Item = Result<Operation, StreamError>;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+    loop {
+      if let Some(item) = this.ready.pop_front() {
+        return Poll::Ready(Some(item));
+      }
+      if this.done {
+        return Poll::Ready(None);
+      }
+      let read = match ready!(Pin::new(&mut this.reader).poll_fill_buf(cx)) {
+        Ok(buf) => {
+          this.pending.extend_from_slice(buf);
+          buf.len()
+        }
+        Err(e) => {
+          this.done = true;
+          return Poll::Ready(Some(Err(StreamError::Io(e))));
+        }
+      };
+      Pin::new(&mut this.reader).consume(read);
+      this.parse_complete(read == 0);
+      this.done |= read == 0;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::task::Waker;
+  use tokio::io::{AsyncRead, BufReader, ReadBuf};
+
+  // Hands out a chunk per read, and has the reader wait before each of them.
+  struct Chunks {
+    chunks: VecDeque<&'static [u8]>,
+    waited: bool,
+  }
+
+  impl AsyncRead for Chunks {
+    fn poll_read(
+      self: Pin<&mut Self>,
+      cx: &mut Context<'_>,
+      buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+      let this = self.get_mut();
+      this.waited = !this.waited;
+      if this.waited {
+        cx.waker().wake_by_ref();
+        return Poll::Pending;
+      }
+      if let Some(chunk) = this.chunks.pop_front() {
+        buf.put_slice(chunk);
+      }
+      Poll::Ready(Ok(()))
+    }
+  }
+
+  fn collect(chunks: &[&'static [u8]]) -> Vec<Result<Operation, StreamError>> {
+    let chunks = Chunks {
+      chunks: chunks.iter().copied().collect(),
+      waited: false,
+    };
+    let mut stream = OperationStream::new(BufReader::new(chunks));
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut res = Vec::new();
+    loop {
+      match Pin::new(&mut stream).poll_next(&mut cx) {
+        Poll::Ready(Some(item)) => res.push(item),
+        Poll::Ready(None) => return res,
+        Poll::Pending => {}
+      }
+    }
+  }
+
+  #[test]
+  fn parse_chunks() {
+    let chunks: &[&[u8]] = &[
+      b"Create H",
+      b"R. Add \xC8",
+      b"\x98tefan to HR",
+      b".\nDefine team Alpha as Ana.",
+      b" Add team Alpha to HR. Show",
+      b" HR? Remove Ana from HR",
+    ];
+    let got = collect(chunks);
+    let source = "Create HR. Add Ștefan to HR.
+Define team Alpha as Ana. Add team Alpha to HR. Show HR?";
+    let expect = crate::lex_parse(source).unwrap();
+    let unterminated = crate::lex_parse_iter("Remove Ana from HR")
+      .find_map(Result::err)
+      .and_then(|e| e.kind());
+    assert_eq!(got.len(), expect.len() + 1);
+    for (got, expect) in got.iter().zip(&expect) {
+      assert_eq!(got.as_ref().unwrap(), expect);
+    }
+    match got.last().unwrap() {
+      Err(StreamError::Parse { kind, .. }) => assert_eq!(*kind, unterminated),
+      other => panic!("{:?}", other),
+    }
+
+    let got = collect(&[b"Create HR. Add \xFF", b" to HR. Show HR."]);
+    assert_eq!(got.len(), 2);
+    assert!(got[0].is_ok());
+    match &got[1] {
+      Err(StreamError::Parse { kind, .. }) => assert_eq!(*kind, Some(ErrorKind::InvalidUtf8)),
+      other => panic!("{:?}", other),
+    }
+  }
+}
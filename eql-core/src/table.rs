@@ -0,0 +1,124 @@
+use crate::collation::{CodePoints, Collate};
+use crate::operation::Ordering;
+use crate::util::display_width;
+use std::fmt::Write;
+
+const DEPARTMENT: &str = "Department";
+const MEMBERS: &str = "Members";
+
+// Renders what Show found as a table of the departments and their members, for frontends that run
+// the operations. The departments are sorted by name and the members in the order Show asked for,
+// or left as given without one, both compared with the collator.
+pub struct TableRenderer {
+  collator: Box<dyn Collate>,
+}
+
+impl Default for TableRenderer {
+  fn default() -> Self {
+    TableRenderer {
+      collator: Box::new(CodePoints),
+    }
+  }
+}
+
+fn write_row(out: &mut String, width: usize, department: &str, members: &str) {
+  let padding = " ".repeat(width - display_width(department));
+  let _ = writeln!(out, "{}{} | {}", department, padding, members);
+  // Departments without members would leave a trailing space.
+  if members.is_empty() {
+    out.truncate(out.len() - 2);
+    out.push('\n');
+  }
+}
+
+impl TableRenderer {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn set_collator(self, collator: impl Collate + 'static) -> Self {
+    TableRenderer {
+      collator: Box::new(collator),
+    }
+  }
+
+  pub fn render<S: AsRef<str>>(
+    &self,
+    departments: &[(S, Vec<S>)],
+    ordering: Option<Ordering>,
+  ) -> String {
+    let mut rows: Vec<(&str, Vec<&str>)> = departments
+      .iter()
+      .map(|(department, members)| {
+        let members = members.iter().map(AsRef::as_ref).collect();
+        (department.as_ref(), members)
+      })
+      .collect();
+    rows.sort_by(|a, b| self.collator.compare(a.0, b.0));
+    let rows: Vec<(&str, String)> = rows
+      .into_iter()
+      .map(|(department, mut members)| {
+        if let Some(ordering) = ordering {
+          ordering.sort(&mut members, &*self.collator);
+        }
+        (department, members.join(", "))
+      })
+      .collect();
+
+    let width = rows
+      .iter()
+      .map(|(department, _)| display_width(department))
+      .fold(DEPARTMENT.len(), usize::max);
+    let members_width = rows
+      .iter()
+      .map(|(_, members)| display_width(members))
+      .fold(MEMBERS.len(), usize::max);
+    let mut out = String::new();
+    write_row(&mut out, width, DEPARTMENT, MEMBERS);
+    let _ = writeln!(out, "{}-+-{}", "-".repeat(width), "-".repeat(members_width));
+    for (department, members) in &rows {
+      write_row(&mut out, width, department, members);
+    }
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn render() {
+    let departments = [
+      ("Sales", vec!["Zoe", "Ștefan", "Ana"]),
+      ("IT", vec![]),
+      ("Ökonomie", vec!["Ana"]),
+    ];
+    let ordering = Some(Ordering::Name { descending: false });
+    assert_eq!(
+      TableRenderer::new().render(&departments, ordering),
+      "\
+Department | Members
+-----------+-----------------
+IT         |
+Sales      | Ana, Zoe, Ștefan
+Ökonomie   | Ana
+"
+    );
+
+    let reversed = |a: &str, b: &str| b.cmp(a);
+    let table = TableRenderer::new()
+      .set_collator(reversed)
+      .render(&departments, None);
+    assert_eq!(
+      table,
+      "\
+Department | Members
+-----------+-----------------
+Ökonomie   | Ana
+Sales      | Zoe, Ștefan, Ana
+IT         |
+"
+    );
+  }
+}
@@ -0,0 +1,212 @@
+// Text segmentation and classification. With the default "unicode" feature, graphemes and word
+// boundaries follow UAX #29 and letters are recognized by their Unicode general category. Without
+// it, the Unicode tables are left out of the build and only basic Latin text is supported: letters
+// are ASCII letters and the Latin-1 Supplement and Latin Extended-A/B letters, graphemes are single
+// characters followed by combining diacritics, and any other character is a segment of its own, so
+// scripts in other alphabets fail to lex.
+
+use alloc::borrow::Cow;
+
+#[cfg(feature = "unicode")]
+pub use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg(not(feature = "unicode"))]
+pub use self::basic::UnicodeSegmentation;
+
+#[cfg(feature = "unicode")]
+pub fn is_letter(c: char) -> bool {
+  unic_ucd_category::GeneralCategory::of(c).is_letter()
+}
+
+#[cfg(not(feature = "unicode"))]
+pub fn is_letter(c: char) -> bool {
+  basic::is_latin_letter(c)
+}
+
+// Symbols like emoji, which some names include.
+#[cfg(feature = "unicode")]
+pub fn is_symbol(c: char) -> bool {
+  unic_ucd_category::GeneralCategory::of(c) == unic_ucd_category::GeneralCategory::OtherSymbol
+}
+
+#[cfg(not(feature = "unicode"))]
+pub fn is_symbol(_: char) -> bool {
+  false
+}
+
+// How many columns a grapheme takes in a terminal: East Asian wide and fullwidth characters and
+// emoji take two, the others one.
+pub fn width(grapheme: &str) -> usize {
+  match grapheme.chars().next() {
+    Some(c) if is_wide(c) || grapheme.contains('\u{FE0F}') => 2,
+    _ => 1,
+  }
+}
+
+fn is_wide(c: char) -> bool {
+  matches!(
+    c as u32,
+    0x1100..=0x115F
+      | 0x2E80..=0x303E
+      | 0x3041..=0x33FF
+      | 0x3400..=0x4DBF
+      | 0x4E00..=0x9FFF
+      | 0xA000..=0xA4CF
+      | 0xAC00..=0xD7A3
+      | 0xF900..=0xFAFF
+      | 0xFE30..=0xFE4F
+      | 0xFF00..=0xFF60
+      | 0xFFE0..=0xFFE6
+      | 0x1F300..=0x1F64F
+      | 0x1F900..=0x1F9FF
+      | 0x20000..=0x3FFFD
+  )
+}
+
+// Names are compared by their text, so with the "nfc" feature they are put in Normalization Form C
+// first. Text that is already normalized is borrowed as is.
+#[cfg(feature = "nfc")]
+pub fn normalize<'a>(s: Cow<'a, str>) -> Cow<'a, str> {
+  use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+  match is_nfc_quick(s.chars()) {
+    IsNormalized::Yes => s,
+    _ => s.nfc().collect::<alloc::string::String>().into(),
+  }
+}
+
+#[cfg(not(feature = "nfc"))]
+pub fn normalize<'a>(s: Cow<'a, str>) -> Cow<'a, str> {
+  s
+}
+
+#[cfg(not(feature = "unicode"))]
+mod basic {
+  pub fn is_latin_letter(c: char) -> bool {
+    match c {
+      'a'..='z' | 'A'..='Z' => true,
+      '×' | '÷' => false,
+      'À'..='ÿ' | '\u{100}'..='\u{24f}' => true,
+      _ => false,
+    }
+  }
+
+  fn is_combining(c: char) -> bool {
+    ('\u{300}'..='\u{36f}').contains(&c)
+  }
+
+  fn continues_grapheme(prev: char, c: char) -> bool {
+    is_combining(c) || (prev == '\r' && c == '\n')
+  }
+
+  fn continues_word(prev: char, c: char) -> bool {
+    let is_word = |c: char| is_latin_letter(c) || c.is_ascii_digit() || is_combining(c);
+    let is_space = |c: char| c.is_whitespace() && c != '\r' && c != '\n';
+    continues_grapheme(prev, c) || (is_word(prev) && is_word(c)) || (is_space(prev) && is_space(c))
+  }
+
+  // Splits a string into the runs of characters for which `continues` holds between neighbours.
+  #[derive(Clone)]
+  pub struct Segments<'a> {
+    s: &'a str,
+    front: usize,
+    back: usize,
+    continues: fn(char, char) -> bool,
+  }
+
+  impl<'a> Segments<'a> {
+    fn new(s: &'a str, continues: fn(char, char) -> bool) -> Self {
+      Segments {
+        s,
+        front: 0,
+        back: s.len(),
+        continues,
+      }
+    }
+  }
+
+  impl<'a> Iterator for Segments<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+      let rest = &self.s[self.front..self.back];
+      let mut chars = rest.char_indices();
+      let (_, mut prev) = chars.next()?;
+      let len = chars
+        .find(|(_, c)| {
+          let stop = !(self.continues)(prev, *c);
+          prev = *c;
+          stop
+        })
+        .map_or(rest.len(), |(i, _)| i);
+      let start = self.front;
+      self.front += len;
+      Some((start, &self.s[start..self.front]))
+    }
+  }
+
+  impl<'a> DoubleEndedIterator for Segments<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+      let rest = &self.s[self.front..self.back];
+      let mut chars = rest.char_indices().rev();
+      let (mut start, mut next) = chars.next()?;
+      for (i, c) in chars {
+        if !(self.continues)(c, next) {
+          break;
+        }
+        start = i;
+        next = c;
+      }
+      let end = self.back;
+      self.back = self.front + start;
+      Some((self.back, &self.s[self.back..end]))
+    }
+  }
+
+  pub type GraphemeIndices<'a> = Segments<'a>;
+  pub type Graphemes<'a> = core::iter::Map<Segments<'a>, fn((usize, &'a str)) -> &'a str>;
+  pub type WordBounds<'a> = Graphemes<'a>;
+
+  fn segment((_, s): (usize, &str)) -> &str {
+    s
+  }
+
+  // Mirrors the subset of unicode_segmentation::UnicodeSegmentation the crate uses.
+  pub trait UnicodeSegmentation {
+    fn graphemes(&self, is_extended: bool) -> Graphemes<'_>;
+    fn grapheme_indices(&self, is_extended: bool) -> GraphemeIndices<'_>;
+    fn split_word_bounds(&self) -> WordBounds<'_>;
+  }
+
+  impl UnicodeSegmentation for str {
+    fn graphemes(&self, _: bool) -> Graphemes<'_> {
+      Segments::new(self, continues_grapheme).map(segment)
+    }
+
+    fn grapheme_indices(&self, _: bool) -> GraphemeIndices<'_> {
+      Segments::new(self, continues_grapheme)
+    }
+
+    fn split_word_bounds(&self) -> WordBounds<'_> {
+      Segments::new(self, continues_word).map(segment)
+    }
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    #[test]
+    fn segments() {
+      let s = "Add Ștefan  e\u{301}a,\r\n×";
+      let words: Vec<&str> = s.split_word_bounds().collect();
+      assert_eq!(
+        words,
+        vec!["Add", " ", "Ștefan", "  ", "e\u{301}a", ",", "\r\n", "×"]
+      );
+      let mut back: Vec<&str> = s.split_word_bounds().rev().collect();
+      back.reverse();
+      assert_eq!(back, words);
+      assert_eq!(s.graphemes(true).count(), 17);
+    }
+  }
+}
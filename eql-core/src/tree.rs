@@ -0,0 +1,137 @@
+use crate::line_index::LineIndex;
+use crate::operation::{NameList, Operation};
+use crate::parser::Statement;
+use std::fmt::Write;
+
+const INDENT: &str = "  ";
+
+fn write_list(out: &mut String, depth: usize, label: &str, list: &[String]) {
+  let _ = writeln!(out, "{}{}", INDENT.repeat(depth), label);
+  for element in list {
+    let _ = writeln!(out, "{}{:?}", INDENT.repeat(depth + 1), element);
+  }
+}
+
+fn write_field(out: &mut String, depth: usize, label: &str, value: impl std::fmt::Display) {
+  let _ = writeln!(out, "{}{}: {}", INDENT.repeat(depth), label, value);
+}
+
+fn write_operation(out: &mut String, depth: usize, op: &Operation) {
+  if let Some(condition) = op.get_condition() {
+    let _ = writeln!(out, "{}condition", INDENT.repeat(depth));
+    write_list(out, depth + 1, "departments", &condition.departments);
+    write_field(out, depth + 1, "exists", condition.exists);
+  }
+  match op.get_names() {
+    Some(NameList::Listed(names)) => write_list(out, depth, "names", names),
+    Some(NameList::All) => write_field(out, depth, "names", "everyone"),
+    None => {}
+  }
+  if !op.aliases().is_empty() {
+    let _ = writeln!(out, "{}aliases", INDENT.repeat(depth));
+    for alias in op.aliases() {
+      let _ = writeln!(
+        out,
+        "{}{:?} -> {:?}",
+        INDENT.repeat(depth + 1),
+        alias.name,
+        alias.alias
+      );
+    }
+  }
+  if let Some(except) = op.get_except().filter(|e| !e.is_empty()) {
+    write_list(out, depth, "except", except);
+  }
+  if let Some(new_name) = op.get_new_name() {
+    write_field(out, depth, "new name", format_args!("{:?}", new_name));
+  }
+  if let Some(departments) = op.get_departments() {
+    write_list(out, depth, "departments", departments);
+  }
+  if let Some(reassign_to) = op.get_reassign_to() {
+    write_field(out, depth, "reassign to", format_args!("{:?}", reassign_to));
+  }
+  if let Some(ordering) = op.get_ordering() {
+    write_field(out, depth, "ordering", ordering);
+  }
+  if let Some(limit) = op.get_limit() {
+    write_field(out, depth, "limit", limit);
+  }
+  if let Some(effective) = op.get_effective() {
+    write_field(out, depth, "effective", effective);
+  }
+  let flags = [
+    ("fail silently", op.get_fail_silently()),
+    ("overwrite", op.get_overwrite()),
+    ("detailed", op.get_detailed()),
+  ];
+  for (label, value) in flags {
+    if let Some(value) = value {
+      write_field(out, depth, label, value);
+    }
+  }
+}
+
+// Renders operations as an indented tree, one node per operation.
+pub fn render_operations(ops: &[Operation]) -> String {
+  let mut out = String::new();
+  for op in ops {
+    let _ = writeln!(out, "{}", op.kind());
+    write_operation(&mut out, 1, op);
+  }
+  out
+}
+
+// Renders the statements of a source as an indented tree. Every statement is labelled with the
+// line and column range it spans.
+pub fn render(source: &str, statements: &[Statement]) -> String {
+  let index = LineIndex::new(source);
+  let mut out = String::new();
+  for statement in statements {
+    let position = |offset| {
+      let (line, column) = index.position(offset).unwrap_or_default();
+      format!("{}:{}", line, column)
+    };
+    let _ = writeln!(
+      out,
+      "{} [{}..{}]",
+      statement.operation.kind(),
+      position(statement.range.start),
+      position(statement.range.end)
+    );
+    write_operation(&mut out, 1, &statement.operation);
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::parse_statements;
+
+  #[test]
+  fn render_statements() {
+    let source = "Create HR.\nIf HR exists, add Mihai, also known as Mike, to HR!";
+    let res = parse_statements(source).unwrap();
+    let expect = r#"Create [1:1..1:11]
+  departments
+    "HR"
+  fail silently: false
+  overwrite: false
+Add [2:1..2:52]
+  condition
+    departments
+      "HR"
+    exists: true
+  names
+    "Mihai"
+  aliases
+    "Mihai" -> "Mike"
+  departments
+    "HR"
+  fail silently: false
+  overwrite: true
+"#;
+    assert_eq!(render(source, &res.statements), expect);
+  }
+}
@@ -0,0 +1,129 @@
+use crate::text::{self, UnicodeSegmentation};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+pub fn repeat_chars(ch: &str, n: usize) -> String {
+  ch.repeat(n)
+}
+
+// Letters can be followed by combining marks, so decomposed accented letters are letters too.
+pub fn is_alphabetic(s: &str) -> bool {
+  s.graphemes(true)
+    .all(|g| g.chars().next().is_some_and(text::is_letter))
+}
+
+pub fn is_whitespace(s: &str) -> bool {
+  s.chars().all(char::is_whitespace)
+}
+
+pub fn string_length(word: &str) -> usize {
+  word.graphemes(true).count()
+}
+
+pub fn display_width(s: &str) -> usize {
+  s.graphemes(true).map(text::width).sum()
+}
+
+#[cfg(feature = "std")]
+pub fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<&str> = b.graphemes(true).collect();
+  let mut row: Vec<usize> = (0..=b.len()).collect();
+
+  for (i, ga) in a.graphemes(true).enumerate() {
+    let mut diagonal = row[0];
+    row[0] = i + 1;
+    for (j, gb) in b.iter().enumerate() {
+      let substitution = diagonal + (ga != *gb) as usize;
+      diagonal = row[j + 1];
+      row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+    }
+  }
+
+  row[b.len()]
+}
+
+const MAX_POINTER_LENGTH: usize = 64;
+const EXCERPT_CONTEXT: usize = 32;
+
+pub fn fmt_truncated(s: &str) -> alloc::borrow::Cow<'_, str> {
+  match s.grapheme_indices(true).nth(MAX_POINTER_LENGTH) {
+    Some((i, _)) => format!("{}...", &s[..i]).into(),
+    None => s.into(),
+  }
+}
+
+// Pointers and their padding are as wide as the text they are under in a terminal, so they line
+// up under wide characters too.
+fn fmt_pointer(token_value: &str) -> String {
+  let mut graphemes = token_value.graphemes(true);
+  let width = graphemes
+    .by_ref()
+    .take(MAX_POINTER_LENGTH)
+    .map(text::width)
+    .sum();
+  if graphemes.next().is_some() {
+    format!(
+      "{}... ({} characters)",
+      repeat_chars("^", width),
+      string_length(token_value)
+    )
+  } else {
+    repeat_chars("^", width)
+  }
+}
+
+pub fn fmt_token_pointer(before: &str, token_value: &str) -> (String, String) {
+  (
+    repeat_chars(" ", display_width(before)),
+    fmt_pointer(token_value),
+  )
+}
+
+// Returns the line shortened around the token, along with the padding and pointer that underline
+// the token in it, so huge lines and tokens don't make huge error messages.
+pub fn fmt_excerpt(line: &str, token_value: &str, col: usize) -> (String, String, String) {
+  let before = col.saturating_sub(1);
+  let skip = before.saturating_sub(EXCERPT_CONTEXT);
+  let take = before - skip + MAX_POINTER_LENGTH + EXCERPT_CONTEXT;
+  let mut graphemes = line.trim_end().graphemes(true).skip(skip);
+  let mut excerpt = String::from(if skip > 0 { "..." } else { "" });
+  let mut padding = excerpt.len();
+  for (i, g) in graphemes.by_ref().take(take).enumerate() {
+    if i < before - skip {
+      padding += text::width(g);
+    }
+    excerpt.push_str(g);
+  }
+  if graphemes.next().is_some() {
+    excerpt.push_str("...");
+  }
+  (
+    excerpt,
+    repeat_chars(" ", padding),
+    fmt_pointer(token_value),
+  )
+}
+
+pub fn fmt_list<T: core::fmt::Display>(elems: &[T], sep: &str, linker: &str) -> String {
+  if let [rest @ .., last] = elems {
+    if rest.is_empty() {
+      format!("{}", last)
+    } else {
+      format!(
+        "{}{} {} {}",
+        rest.iter().map(T::to_string).collect::<Vec<_>>().join(sep),
+        if rest.len() == 1 { "" } else { "," },
+        linker,
+        last
+      )
+    }
+  } else {
+    "".into()
+  }
+}
+
+#[cfg(test)]
+pub fn to_string_vec(v: Vec<&str>) -> Vec<String> {
+  v.into_iter().map(String::from).collect()
+}
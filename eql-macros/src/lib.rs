@@ -0,0 +1,49 @@
+// eql!("Add Mihai to HR.") parses the script at compile time, so invalid scripts fail the build
+// with the parse error reported on the literal. The expansion parses the same literal again at
+// runtime to build the Vec<Operation>, which can't fail once the literal compiled.
+//
+// The macro is re-exported by eql, so it depends on eql-core to parse instead of eql.
+use eql_core::Error;
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+
+#[proc_macro]
+pub fn eql(input: TokenStream) -> TokenStream {
+  let literal = parse_macro_input!(input as LitStr);
+  let source = literal.value();
+  // The same as eql::lex_parse, but with the error typed so it can be located in the literal.
+  let operations: Result<Vec<_>, Error> =
+    eql_core::parser::parse_iter(eql_core::lexer::lex_iter(&source)).collect();
+  if let Err(e) = operations {
+    let message = format!("invalid eql script: {}", e);
+    return syn::Error::new(error_span(&literal, &source, &e), message)
+      .to_compile_error()
+      .into();
+  }
+  quote!(::eql::lex_parse(#literal)
+    .expect("the script was parsed when the eql! macro expanded, so it parses again"))
+  .into()
+}
+
+// The span of the token the error is at inside the literal. Literals whose text differs from the
+// script, because of escapes or because they are raw, and compilers that can't point inside
+// literals get the span of the whole literal.
+fn error_span(literal: &LitStr, source: &str, e: &Error) -> Span {
+  let token = match e {
+    Error::Lex(e) => e.token(),
+    Error::Parse(e) => e.token(),
+    Error::Cancelled => return literal.span(),
+  };
+  let repr = literal.token().to_string();
+  if repr.get(1..repr.len() - 1) != Some(source) {
+    return literal.span();
+  }
+  let start = token.line().as_ptr() as usize - source.as_ptr() as usize + token.offset();
+  let end = start + token.text().len();
+  literal
+    .token()
+    .subspan(start + 1..end + 1)
+    .unwrap_or_else(|| literal.span())
+}
@@ -0,0 +1,12 @@
+use eql::eql;
+
+#[test]
+fn expands_to_operations() {
+  let ops: Vec<eql::Operation> = eql!("Create HR.\nAdd Mihai to HR!");
+  assert_eq!(ops, eql::lex_parse("Create HR. Add Mihai to HR!").unwrap());
+}
+
+#[test]
+fn rejects_invalid_scripts() {
+  trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,5 @@
+use eql::eql;
+
+fn main() {
+  let _ = eql!("Create HR. Add Mihai to , HR.");
+}
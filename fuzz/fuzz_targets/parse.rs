@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Lexing and parsing must never panic, whatever the input.
+fuzz_target!(|source: &str| {
+  let _ = eql::lex_parse(source);
+  let _ = eql::lex_parse_iter(source).count();
+  let _ = eql::parser::parse_statements(source);
+  let _ = eql::parser::Parser::new().parse(source);
+});
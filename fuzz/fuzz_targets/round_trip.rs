@@ -0,0 +1,12 @@
+#![no_main]
+
+use eql::generate::Generator;
+use libfuzzer_sys::fuzz_target;
+
+// Valid scripts parse, and writing their operations back as source parses into the same ones.
+fuzz_target!(|data: &[u8]| {
+  let source = Generator::from_bytes(data).source(1 + data.len() % 8);
+  let operations = eql::lex_parse(&source).unwrap();
+  let written: Vec<_> = operations.iter().map(|op| op.to_source()).collect();
+  assert_eq!(eql::lex_parse(&written.join(" ")).unwrap(), operations);
+});
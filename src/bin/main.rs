@@ -1,37 +1,283 @@
 extern crate eql;
 
-use eql::lexer::last_token_value;
+use std::env;
+use std::fs;
 use std::io::{self, BufRead};
+use std::process;
+use std::sync::OnceLock;
 
-fn get_input(mut handle: impl BufRead, buf: &mut String) -> io::Result<()> {
-  let mut total_read = 0;
-  buf.clear();
+// Set once from the command line, before any script is parsed.
+static OPTIONS: OnceLock<eql::parser::ParserOptions> = OnceLock::new();
+
+fn options() -> eql::parser::ParserOptions {
+  OPTIONS.get().copied().unwrap_or_default()
+}
+
+// Reads lines until at least one statement is terminated and returns the terminated statements.
+// Whatever follows the last terminator is kept in the pending buffer, so only the statement that is
+// still being typed is carried over to the next call. Returns None at the end of the input.
+fn get_input(mut handle: impl BufRead, pending: &mut String) -> io::Result<Option<String>> {
+  let mut line = String::new();
 
   loop {
-    let read = handle.read_line(buf)?;
-    total_read += read;
-    if let Some(tail) = last_token_value(&buf[total_read - read..].trim_end()) {
-      // FIXME: Break on actual terminators
-      if !matches!(tail, eql::lexer::Word(_)) {
-        break;
+    line.clear();
+    if handle.read_line(&mut line)? == 0 {
+      return Ok(None);
+    }
+    pending.push_str(&line);
+    let end = eql::split_statements(pending)
+      .iter()
+      .rev()
+      .map(|range| range.end)
+      .find(|end| {
+        // An unclosed quote is an error whatever follows, so it is reported right away.
+        matches!(
+          eql::lexer::is_statement_complete(&pending[..*end]),
+          eql::lexer::Completeness::Complete | eql::lexer::Completeness::UnclosedQuote
+        )
+      });
+    if let Some(end) = end {
+      let rest = pending.split_off(end);
+      return Ok(Some(std::mem::replace(pending, rest)));
+    }
+  }
+}
+
+fn read_file(path: &str) -> String {
+  fs::read_to_string(path).unwrap_or_else(|e| {
+    eprintln!("{}: {}", path, e);
+    process::exit(1)
+  })
+}
+
+// Setting EQL_ACCESSIBLE describes error locations in words instead of pointing at them, for
+// screen readers.
+fn fmt_error(e: &eql::Error) -> String {
+  match env::var_os("EQL_ACCESSIBLE") {
+    Some(v) if !v.is_empty() && v != "0" => e.describe(),
+    _ => e.to_string(),
+  }
+}
+
+fn parse_file(path: &str, source: &str) -> Vec<eql::Operation> {
+  eql::lex_parse_iter(source)
+    .with_options(options())
+    .collect::<Result<_, _>>()
+    .unwrap_or_else(|e| {
+      eprintln!("{}: {}", path, fmt_error(&e));
+      process::exit(1)
+    })
+}
+
+fn diff(old_path: &str, new_path: &str) -> io::Result<()> {
+  let (old_source, new_source) = (read_file(old_path), read_file(new_path));
+  let old = parse_file(old_path, &old_source);
+  let new = parse_file(new_path, &new_source);
+  for change in eql::diff::diff(&old, &new) {
+    println!("{}", change);
+  }
+  Ok(())
+}
+
+fn lint(path: &str, departments_path: Option<&str>) -> io::Result<()> {
+  let source = read_file(path);
+  let ops = parse_file(path, &source);
+  let mut registry = eql::lint::Registry::new();
+  registry
+    .register(eql::lint::DuplicateElements)
+    .register(eql::lint::Conflicts);
+  if let Some(departments_path) = departments_path {
+    let departments: Vec<String> = read_file(departments_path)
+      .lines()
+      .map(str::trim)
+      .filter(|l| !l.is_empty())
+      .map(String::from)
+      .collect();
+    registry
+      .register(eql::lint::ApprovedDepartments(departments.clone()))
+      .register(eql::lint::Spelling(departments));
+  }
+  // Lints are located in the source when it parses statement by statement, which it can fail to do
+  // with a grammar whose punctuation the default lexer doesn't know.
+  let lints = registry
+    .run_source_with(&source, &options())
+    .unwrap_or_else(|_| registry.run(&ops));
+  let index = eql::line_index::LineIndex::new(&source);
+  for lint in &lints {
+    match lint
+      .spans
+      .first()
+      .and_then(|span| index.position(span.start))
+    {
+      Some((line, column)) => println!("{}:{}:{}: {}", path, line, column, lint),
+      None => println!("{}", lint),
+    }
+  }
+  if !lints.is_empty() {
+    process::exit(1);
+  }
+  Ok(())
+}
+
+fn parse(path: &str, tree: bool) -> io::Result<()> {
+  let source = read_file(path);
+  if !tree {
+    for (i, op) in parse_file(path, &source).iter().enumerate() {
+      println!("{}: {:?}", i, op);
+    }
+    return Ok(());
+  }
+  let res = eql::parser::parse_statements_with(&source, &options()).unwrap_or_else(|e| {
+    eprintln!("{}: {}", path, fmt_error(&e));
+    process::exit(1)
+  });
+  print!("{}", eql::tree::render(&source, &res.statements));
+  Ok(())
+}
+
+fn explain(code: &str) -> io::Result<()> {
+  let kind: eql::ErrorKind = code.parse().unwrap_or_else(|e| {
+    eprintln!("{}", e);
+    process::exit(1)
+  });
+  println!("{}", eql::explain::explain(kind));
+  Ok(())
+}
+
+fn stats(path: &str) -> io::Result<()> {
+  let source = read_file(path);
+  println!(
+    "{}",
+    eql::stats::Stats::from(&parse_file(path, &source)[..])
+  );
+  Ok(())
+}
+
+fn print_source(ops: &[eql::Operation]) {
+  for op in ops {
+    println!("{}", op.to_source());
+  }
+}
+
+fn optimize(path: &str) -> io::Result<()> {
+  let source = read_file(path);
+  let (ops, eliminations) = eql::optimize::eliminate_dead(&parse_file(path, &source));
+  for elimination in &eliminations {
+    eprintln!("{}", elimination);
+  }
+  print_source(&ops);
+  Ok(())
+}
+
+// Prints the runs of lines that differ, each under the line of the old source it starts at. Lines are
+// matched by their longest common subsequence, so a fix that adds or removes a line doesn't show
+// every line after it as changed.
+fn print_line_diff(old: &str, new: &str) {
+  let (old, new): (Vec<&str>, Vec<&str>) = (old.lines().collect(), new.lines().collect());
+  // Fixes are local, so only the lines between the common start and end are compared.
+  let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+  let suffix = old[prefix..]
+    .iter()
+    .rev()
+    .zip(new[prefix..].iter().rev())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let (old, new) = (
+    &old[prefix..old.len() - suffix],
+    &new[prefix..new.len() - suffix],
+  );
+  let mut common = vec![vec![0; new.len() + 1]; old.len() + 1];
+  for i in (0..old.len()).rev() {
+    for j in (0..new.len()).rev() {
+      common[i][j] = match old[i] == new[j] {
+        true => common[i + 1][j + 1] + 1,
+        false => common[i + 1][j].max(common[i][j + 1]),
+      };
+    }
+  }
+  let (mut i, mut j) = (0, 0);
+  while i < old.len() || j < new.len() {
+    if i < old.len() && j < new.len() && old[i] == new[j] {
+      i += 1;
+      j += 1;
+      continue;
+    }
+    println!("@@ line {} @@", prefix + i + 1);
+    let mut added = Vec::new();
+    while i < old.len() || j < new.len() {
+      match (old.get(i), new.get(j)) {
+        (Some(a), Some(b)) if a == b => break,
+        (Some(a), next) if next.is_none() || common[i + 1][j] >= common[i][j + 1] => {
+          println!("-{}", a);
+          i += 1;
+        }
+        (_, Some(b)) => {
+          added.push(*b);
+          j += 1;
+        }
+        (_, None) => break,
       }
     }
+    for line in added {
+      println!("+{}", line);
+    }
   }
+}
 
+// Fixes are applied in place, unless the fixed script is printed instead or only previewed as the
+// lines that would change.
+fn fix(path: &str, flag: Option<&str>) -> io::Result<()> {
+  let source = read_file(path);
+  let (fixed, fixes) = eql::fix::fix_with(&source, &options());
+  for fix in &fixes {
+    eprintln!("{}: {}", path, fix);
+  }
+  match flag {
+    Some("--stdout") => print!("{}", fixed),
+    Some(_) => print_line_diff(&source, &fixed),
+    None if fixes.is_empty() => {}
+    None => fs::write(path, &fixed)?,
+  }
+  if eql::parser::parse_statements_with(&fixed, &options()).is_err() {
+    eprintln!("{}: some errors can't be fixed automatically", path);
+    process::exit(1);
+  }
   Ok(())
 }
 
-fn main() -> io::Result<()> {
-  let stdin = io::stdin();
-  let mut buffer = String::new();
+fn anonymize(path: &str, mapping_path: Option<&str>) -> io::Result<()> {
+  let source = read_file(path);
+  let mut pseudonyms = eql::anonymize::Pseudonyms::new();
+  print_source(&pseudonyms.anonymize(&parse_file(path, &source)));
+  if let Some(mapping_path) = mapping_path {
+    fs::write(mapping_path, pseudonyms.to_string())?;
+  }
+  Ok(())
+}
 
-  loop {
-    get_input(stdin.lock(), &mut buffer)?;
+fn deanonymize(path: &str, mapping_path: &str) -> io::Result<()> {
+  let source = read_file(path);
+  let pseudonyms: eql::anonymize::Pseudonyms =
+    read_file(mapping_path).parse().unwrap_or_else(|e| {
+      eprintln!("{}: {}", mapping_path, e);
+      process::exit(1)
+    });
+  print_source(&pseudonyms.restore(&parse_file(path, &source)));
+  Ok(())
+}
+
+fn repl() -> io::Result<()> {
+  let stdin = io::stdin();
+  let mut pending = String::new();
 
-    let operations = match eql::lex_parse(&buffer) {
+  while let Some(input) = get_input(stdin.lock(), &mut pending)? {
+    let operations = match eql::lex_parse_iter(&input)
+      .with_options(options())
+      .collect::<Result<Vec<_>, _>>()
+    {
       Ok(ops) => ops,
       Err(e) => {
-        eprintln!("{}", e);
+        eprintln!("{}", fmt_error(&e));
         continue;
       }
     };
@@ -39,4 +285,53 @@ fn main() -> io::Result<()> {
       println!("{}: {:?}", i, op);
     }
   }
+
+  Ok(())
+}
+
+fn main() -> io::Result<()> {
+  let mut args: Vec<String> = env::args().skip(1).collect();
+  let mut options = eql::parser::ParserOptions::new();
+  loop {
+    match args.as_slice() {
+      [flag, edition, ..] if flag == "--edition" => {
+        let edition = edition.parse().unwrap_or_else(|e| {
+          eprintln!("{}", e);
+          process::exit(2)
+        });
+        options = options.set_edition(edition);
+        args.drain(..2);
+      }
+      [flag, ..] if flag == "--ignore-case" => {
+        options = options.set_case_insensitive(true);
+        args.remove(0);
+      }
+      _ => break,
+    }
+  }
+  let _ = OPTIONS.set(options);
+  match args.as_slice() {
+    [] => repl(),
+    [command, old, new] if command == "diff" => diff(old, new),
+    [command, script] if command == "lint" => lint(script, None),
+    [command, script, departments] if command == "lint" => lint(script, Some(departments)),
+    [command, script] if command == "parse" => parse(script, false),
+    [command, flag, script] if command == "parse" && flag == "--tree" => parse(script, true),
+    [flag, script] if flag == "--stats" => stats(script),
+    [flag, code] if flag == "--explain" => explain(code),
+    [command, script] if command == "optimize" => optimize(script),
+    [command, script] if command == "fix" => fix(script, None),
+    [command, flag, script] if command == "fix" && (flag == "--dry-run" || flag == "--stdout") => {
+      fix(script, Some(flag))
+    }
+    [command, script] if command == "anonymize" => anonymize(script, None),
+    [command, script, mapping] if command == "anonymize" => anonymize(script, Some(mapping)),
+    [command, script, mapping] if command == "deanonymize" => deanonymize(script, mapping),
+    _ => {
+      eprintln!(
+        "Usage: eql [--edition <1|2>] [--ignore-case] [diff <old> <new> | parse [--tree] <script> | lint <script> [<departments>] | --stats <script> | --explain <code> | optimize <script> | fix [--dry-run | --stdout] <script> | anonymize <script> [<mapping>] | deanonymize <script> <mapping>]"
+      );
+      process::exit(2)
+    }
+  }
 }
@@ -1,15 +1,7 @@
-pub mod lexer;
-pub mod operation;
-pub mod parser;
-mod util;
+// The language is implemented in eql-core. This crate re-exports it together with the eql! macro,
+// which can't live in the same crate as the parser it runs at compile time.
+#![no_std]
 
-pub use lexer::lex;
-pub use operation::Operation;
-pub use parser::parse;
-
-pub fn lex_parse<'a>(s: &'a str) -> Result<Vec<Operation>, Box<dyn std::error::Error + 'a>> {
-  Ok(parse(lex(s)?)?)
-}
-
-// #[cfg(test)]
-// mod tests {}
+pub use eql_core::*;
+#[cfg(feature = "macros")]
+pub use eql_macros::eql;